@@ -1,7 +1,9 @@
 --/ Definitions /--
 -- Constants
-local PROXIMA_URL = 'ws://localhost:13376'
+local DEFAULT_PROXIMA_URL = 'ws://localhost:13376'
 local RECONNECT_DELAY = 5
+local MAX_RECONNECT_DELAY = 60
+local EXECUTION_CHECK_INTERVAL = 0.5
 local LOG_INFO = 0
 local LOG_SUCCESS = 1
 local LOG_WARNING = 2
@@ -10,9 +12,11 @@ local LOG_ERROR = 3
 -- Services
 local Players = game:GetService('Players')
 local HttpService = game:GetService('HttpService')
+local StarterGui = game:GetService('StarterGui')
 
 -- Objects
 local Env = getgenv()
+local PROXIMA_URL = Env.ProximaUrl or DEFAULT_PROXIMA_URL -- Set getgenv().ProximaUrl before loading to use another port
 local LocalPlayer = Players.LocalPlayer
 local ProximaRelayEvent = Instance.new('BindableEvent')
 local Socket = nil
@@ -22,6 +26,10 @@ local Username = nil
 
 -- State
 local Reconnecting = false
+local ReconnectDelay = RECONNECT_DELAY
+local ShutdownReason = nil -- Set when Proxima announces it is closing
+local Executions = {} -- Running executions by ID, with the threads they spawned
+local ThreadExecutions = setmetatable({}, { __mode = 'k' }) -- Execution each script thread belongs to
 
 -- Capabilities
 local Capabilities = {
@@ -506,21 +514,62 @@ local function SendMessage(Type, Data)
 end
 
 --/ Functions /--
-local function Log(Level, ...)
-    local Args = {...}
-    for i = 1, #Args do
+local function FormatMessage(...)
+    local Args = table.pack(...)
+    for i = 1, Args.n do
         Args[i] = tostring(Args[i])
     end
-    local Message = table.concat(Args, ' ')
+    return table.concat(Args, ' ', 1, Args.n)
+end
 
+local function Log(Level, ...)
     SendMessage('log', {
         level = Level,
-        message = Message
+        message = FormatMessage(...)
     })
 end
 
+-- Output from a script, tagged with its execution so it can be traced back to it
+local function LogExecution(Id, Level, ...)
+    SendMessage('log', {
+        level = Level,
+        message = FormatMessage(...),
+        execution_id = Id
+    })
+end
+
+local function Notify(Text)
+    pcall(function()
+        StarterGui:SetCore('SendNotification', {
+            Title = 'Proxima',
+            Text = Text,
+            Duration = 5
+        })
+    end)
+end
+
+local function BackOff()
+    -- Only back off when Proxima closed on purpose; otherwise retry at the normal rate
+    if ShutdownReason then
+        ReconnectDelay = math.min(ReconnectDelay * 2, MAX_RECONNECT_DELAY)
+    end
+end
+
 local function Ready()
-    SendMessage('ready')
+    -- Tags let autoexec rules target groups of clients, e.g. getgenv().PROXIMA_TAGS = { 'farm' }
+    local Tags = {}
+    if type(Env.PROXIMA_TAGS) == 'table' then
+        for _, Tag in ipairs(Env.PROXIMA_TAGS) do
+            table.insert(Tags, tostring(Tag))
+        end
+    end
+
+    SendMessage('ready', {
+        place_id = game.PlaceId,
+        game_id = game.GameId,
+        username = LocalPlayer and LocalPlayer.Name or nil,
+        tags = Tags
+    })
 end
 
 local function Register()
@@ -546,35 +595,138 @@ local function Pong()
     SendMessage('pong')
 end
 
-local function Exec(Script, Redirect)
-    local Func, Err = loadstring(Script)
+-- Map bundle line numbers in an error back to the original file and line
+local function RemapError(Err, LineMap)
+    Err = tostring(Err)
+
+    if not LineMap then
+        return Err
+    end
+
+    return (Err:gsub('%[string ".-"%]:(%d+):', function(Line)
+        local Number = tonumber(Line)
+
+        for _, Segment in ipairs(LineMap) do
+            if Number >= Segment.start and Number <= Segment['end'] then
+                return Segment.path .. ':' .. (Segment.line + Number - Segment.start) .. ':'
+            end
+        end
+    end))
+end
+
+-- Report an execution as ended and stop tracking it
+local function EndExecution(Id, Status, Err)
+    Executions[Id] = nil
+
+    SendMessage('exec_done', {
+        id = Id,
+        status = Status,
+        error = Err
+    })
+end
+
+-- An execution ends once every thread it spawned is dead, or stopped by a redirected error,
+-- and every signal connection it made is disconnected
+local function CheckExecution(Id)
+    local Execution = Executions[Id]
+    if not Execution then
+        return
+    end
+
+    for _, Thread in ipairs(Execution.Threads) do
+        if not Execution.Stopped[Thread] and coroutine.status(Thread) ~= 'dead' then
+            return
+        end
+    end
+
+    for _, Connection in ipairs(Execution.Connections) do
+        if Connection.Connected then
+            return
+        end
+    end
+
+    EndExecution(Id, Execution.Error and 'failed' or 'finished', Execution.Error)
+end
+
+local function CancelExecution(Id)
+    local Execution = Executions[Id]
+    if not Execution then
+        return
+    end
+
+    for _, Connection in ipairs(Execution.Connections) do
+        Connection:Disconnect()
+    end
+
+    local Current = coroutine.running()
+    for _, Thread in ipairs(Execution.Threads) do
+        if Thread ~= Current and coroutine.status(Thread) ~= 'dead' then
+            pcall(task.cancel, Thread)
+        end
+    end
+
+    EndExecution(Id, 'cancelled')
+end
+
+local function RunScript(Data, Execution)
+    local Redirect = Data.redirect
+    local LineMap = Data.line_map
+    local Args = Data.args or {}
+    Args.n = Data.arg_count or #Args -- null arguments decode as holes, so #Args can't be trusted
+    local Func, Err = loadstring(Data.script)
+
+    local function Output(Level, ...)
+        LogExecution(Data.id, Level, ...)
+    end
 
     if not Func then
-        Log(LOG_ERROR, tostring(Err))
+        Execution.Error = RemapError(Err, LineMap)
+        Output(LOG_ERROR, Execution.Error)
         return
     end
 
+    -- Track threads the script spawns, so the execution can be cancelled
+    local function Track(Thread)
+        table.insert(Execution.Threads, Thread)
+        ThreadExecutions[Thread] = Execution
+        return Thread
+    end
+
+    -- Mark the thread a callback runs in as the script's before it starts, so signal
+    -- connections made straight away are recorded
+    local function Own(Callback)
+        if type(Callback) == 'thread' then
+            ThreadExecutions[Callback] = Execution
+            return Callback
+        end
+
+        return function(...)
+            ThreadExecutions[coroutine.running()] = Execution
+            return Callback(...)
+        end
+    end
+
     -- Build a per-execution sandbox that inherits from the global env.
     -- This avoids mutating getgenv(), so overrides persist across yields
     -- without needing save/restore.
     local Sandbox = setmetatable({
         printconsole = function(...)
-            Log(LOG_INFO, ...)
+            Output(LOG_INFO, ...)
         end,
         successconsole = function(...)
-            Log(LOG_SUCCESS, ...)
+            Output(LOG_SUCCESS, ...)
         end,
         warnconsole = function(...)
-            Log(LOG_WARNING, ...)
+            Output(LOG_WARNING, ...)
         end,
         errorconsole = function(...)
-            Log(LOG_ERROR, ...)
+            Output(LOG_ERROR, ...)
         end,
         ProximaRelay = table.freeze({
             OnBroadcast = ProximaRelayEvent.Event,
             Broadcast = function(Content)
                 if type(Content) ~= 'string' then
-                    Log(LOG_ERROR, 'ProximaRelay.Broadcast expects a string argument')
+                    Output(LOG_ERROR, 'ProximaRelay.Broadcast expects a string argument')
                     return
                 end
 
@@ -583,37 +735,97 @@ local function Exec(Script, Redirect)
                 })
             end
         }),
+        task = setmetatable({
+            spawn = function(Callback, ...)
+                return Track(task.spawn(Own(Callback), ...))
+            end,
+            defer = function(Callback, ...)
+                return Track(task.defer(Own(Callback), ...))
+            end,
+            delay = function(Time, Callback, ...)
+                return Track(task.delay(Time, Own(Callback), ...))
+            end,
+        }, { __index = task }),
+        spawn = function(Callback)
+            Track(task.defer(Own(Callback)))
+        end,
+        delay = function(Time, Callback)
+            Track(task.delay(Time, Own(Callback)))
+        end,
+        -- A coroutine left suspended by coroutine.yield keeps the execution running until
+        -- it is cancelled, since it can't be told apart from one waiting on the scheduler
+        coroutine = setmetatable({
+            create = function(Callback)
+                return Track(coroutine.create(Own(Callback)))
+            end,
+            wrap = function(Callback)
+                local Thread = Track(coroutine.create(Own(Callback)))
+                return function(...)
+                    local Result = table.pack(coroutine.resume(Thread, ...))
+                    if not Result[1] then
+                        error(Result[2], 2)
+                    end
+                    return table.unpack(Result, 2, Result.n)
+                end
+            end,
+        }, { __index = coroutine }),
     }, { __index = Env })
 
     if Redirect then
         Sandbox.print = function(...)
-            Log(LOG_INFO, ...)
+            Output(LOG_INFO, ...)
         end
 
         Sandbox.warn = function(...)
-            Log(LOG_WARNING, ...)
+            Output(LOG_WARNING, ...)
         end
 
         Sandbox.error = function(...)
-            Log(LOG_ERROR, ...)
+            Output(LOG_ERROR, ...)
+            Execution.Error = Execution.Error or tostring((...))
+            Execution.Stopped[coroutine.running()] = true
             coroutine.yield()
         end
     end
 
     setfenv(Func, Sandbox)
 
-    if Redirect then
-        -- Run in coroutine so we can yield on error
-        coroutine.wrap(function()
-            xpcall(Func, function(Err)
-                Log(LOG_ERROR, tostring(Err))
-            end)
-        end)()
-    else
+    -- Run in its own thread so it can be cancelled, and so a redirected error can yield it
+    Track(task.spawn(Own(function()
         xpcall(Func, function(Err)
-            Log(LOG_ERROR, tostring(Err))
-        end)
+            Execution.Error = RemapError(Err, LineMap)
+            Output(LOG_ERROR, Execution.Error)
+        end, table.unpack(Args, 1, Args.n))
+    end)))
+end
+
+local function Exec(Data)
+    local Id = Data.id or HttpService:GenerateGUID(false)
+    local Execution = {
+        Threads = {},
+        Connections = {},
+        Stopped = {},
+        Error = nil
+    }
+    Executions[Id] = Execution
+
+    if Data.delay or Data.wait_for_load then
+        table.insert(Execution.Threads, task.spawn(function()
+            if Data.wait_for_load and not game:IsLoaded() then
+                game.Loaded:Wait()
+            end
+
+            if Data.delay then
+                task.wait(Data.delay)
+            end
+
+            RunScript(Data, Execution)
+        end))
+    else
+        RunScript(Data, Execution)
     end
+
+    CheckExecution(Id)
 end
 
 local function HandleMessage(Message)
@@ -628,9 +840,13 @@ local function HandleMessage(Message)
     if Data.type == 'ping' then
         Pong()
     elseif Data.type == 'exec' then
-        Exec(Data.script, Data.redirect)
+        Exec(Data)
+    elseif Data.type == 'exec_cancel' then
+        CancelExecution(Data.id)
     elseif Data.type == 'relay' then
         ProximaRelayEvent:Fire(Data.content)
+    elseif Data.type == 'shutdown' then
+        ShutdownReason = Data.reason or 'Proxima closed'
     elseif Data.type == 'exp_start' then
         ExpStart()
     elseif Data.type == 'exp_stop' then
@@ -665,13 +881,16 @@ local function Connect()
 
     if not Success then
         Reconnecting = true
-        wait(RECONNECT_DELAY)
+        wait(ReconnectDelay)
+        BackOff()
         Reconnecting = false
         Connect()
         return
     end
 
     Socket = Result
+    ShutdownReason = nil
+    ReconnectDelay = RECONNECT_DELAY
 
     Socket.OnMessage:Connect(function(Message)
         HandleMessage(Message)
@@ -687,8 +906,14 @@ local function Connect()
         end
 
         Socket = nil
+
+        if ShutdownReason then
+            Notify(ShutdownReason)
+        end
+
         Reconnecting = true
-        wait(RECONNECT_DELAY)
+        wait(ReconnectDelay)
+        BackOff()
         Reconnecting = false
         Connect()
     end)
@@ -2031,4 +2256,37 @@ function RspyGenerateCode(CallId)
 end
 
 --/ Main /--
+-- Record signal connections made by scripts' threads, so they keep their execution running
+-- and are disconnected when it is cancelled
+if Capabilities.hookfunction and Capabilities.newcclosure then
+    local Signal = game.Changed
+    for _, Method in ipairs({ 'Connect', 'Once' }) do
+        local OriginalMethod
+        OriginalMethod = hookfunction(Signal[Method], newcclosure(function(Self, Callback, ...)
+            local Execution = ThreadExecutions[coroutine.running()]
+            if not Execution or type(Callback) ~= 'function' then
+                return OriginalMethod(Self, Callback, ...)
+            end
+
+            local Connection = OriginalMethod(Self, function(...)
+                ThreadExecutions[coroutine.running()] = Execution
+                return Callback(...)
+            end, ...)
+            table.insert(Execution.Connections, Connection)
+            return Connection
+        end))
+    end
+end
+
+-- Report executions whose threads have all ended
+task.spawn(function()
+    while true do
+        task.wait(EXECUTION_CHECK_INTERVAL)
+
+        for Id in pairs(Executions) do
+            CheckExecution(Id)
+        end
+    end
+end)
+
 Connect()
@@ -0,0 +1,7 @@
+//! Autoexec command handlers
+//!
+//! Commands for listing, enabling and previewing autoexec scripts.
+
+mod scripts;
+
+pub use scripts::{autoexec_dry_run, autoexec_list, autoexec_set_enabled};
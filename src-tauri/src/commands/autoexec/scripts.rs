@@ -0,0 +1,55 @@
+use crate::models::{AutoexecDryRun, AutoexecEntry};
+use crate::services::autoexec::{self, ClientContext};
+use crate::state::ClientRegistry;
+use crate::utils::settings;
+use tauri::{AppHandle, State};
+
+/// List autoexec scripts in run order
+#[tauri::command]
+pub async fn autoexec_list(app: AppHandle) -> Result<Vec<AutoexecEntry>, String> {
+    Ok(autoexec::list_autoexec(&app))
+}
+
+#[tauri::command]
+pub async fn autoexec_set_enabled(
+    app: AppHandle,
+    path: String,
+    enabled: bool,
+) -> Result<(), String> {
+    autoexec::set_enabled(&app, &path, enabled)?;
+    log::info!(
+        "Autoexec script {} {}",
+        path,
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Ok(())
+}
+
+/// List which autoexec scripts would run on an attached client, and in what order
+#[tauri::command]
+pub async fn autoexec_dry_run(
+    app: AppHandle,
+    client_id: String,
+    clients: State<'_, ClientRegistry>,
+) -> Result<AutoexecDryRun, String> {
+    let client = {
+        let clients = clients.read().await;
+        let info = clients
+            .get(&client_id)
+            .ok_or_else(|| format!("Client not found: {}", client_id))?;
+
+        ClientContext {
+            place_id: info.place_id,
+            game_id: info.game_id,
+            username: Some(info.username.clone()),
+            tags: info.tags.clone(),
+        }
+    };
+
+    let (auto_execute, _) = settings::get_execution_settings(&app);
+
+    Ok(AutoexecDryRun {
+        auto_execute,
+        scripts: autoexec::dry_run(&app, &client),
+    })
+}
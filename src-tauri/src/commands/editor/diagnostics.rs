@@ -0,0 +1,27 @@
+use crate::models::Diagnostic;
+use crate::services::diagnostics;
+use crate::services::workspace::Workspace;
+use std::fs;
+use tauri::AppHandle;
+
+/// Get syntax and lint diagnostics for an editor buffer, or for a file by relative path
+/// when no content is given
+#[tauri::command]
+pub fn get_diagnostics(
+    app: AppHandle,
+    content: Option<String>,
+    relative_path: Option<String>,
+) -> Result<Vec<Diagnostic>, String> {
+    let source = match (content, relative_path) {
+        (Some(content), _) => content,
+        (None, Some(relative_path)) => {
+            let file_path = Workspace::load(&app)
+                .and_then(|workspace| workspace.resolve_existing(&relative_path))
+                .map_err(|e| e.to_string())?;
+            fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))?
+        }
+        (None, None) => return Err("Provide the script content or a file path".to_string()),
+    };
+
+    Ok(diagnostics::diagnose(&source))
+}
@@ -1,5 +1,6 @@
 use crate::models::FileNode;
 use crate::services::filesystem;
+use crate::services::workspace::{Workspace, WorkspaceError};
 use tauri::AppHandle;
 
 /// Read the file tree for scripts and autoexec directories
@@ -11,3 +12,31 @@ pub async fn read_file_tree(app: AppHandle) -> Result<Vec<FileNode>, String> {
         .await
         .map_err(|e| format!("Failed to spawn file tree task: {}", e))?
 }
+
+/// Read the tree of a single folder, to refresh part of the file tree
+///
+/// `id` is the folder's node ID in the current tree, so child IDs match the ones a full
+/// rebuild would give. It defaults to the folder's path.
+#[tauri::command]
+pub async fn read_file_subtree(
+    app: AppHandle,
+    relative_path: String,
+    id: Option<String>,
+) -> Result<FileNode, WorkspaceError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let workspace = Workspace::load(&app)?;
+        let folder = workspace.resolve_existing(&relative_path)?;
+        if !folder.is_dir() {
+            return Err(WorkspaceError::NotFound(relative_path));
+        }
+
+        let id = id.unwrap_or_else(|| relative_path.clone());
+        Ok(filesystem::build_subtree(
+            workspace.base_dir(),
+            &folder,
+            &id,
+        )?)
+    })
+    .await
+    .map_err(|e| WorkspaceError::Io(format!("Failed to spawn file tree task: {}", e)))?
+}
@@ -1,7 +1,10 @@
 use crate::log_ui;
-use crate::services::filesystem;
-use crate::utils::events::emit_or_log;
+use crate::models::{SavedFile, VersionSource};
+use crate::services::trash;
+use crate::services::workspace::{validate_name, Workspace, WorkspaceError};
+use crate::services::{filesystem, formatter, versions};
 use crate::utils::paths;
+use crate::utils::settings::get_settings_section;
 use std::fs;
 use tauri::AppHandle;
 
@@ -20,91 +23,100 @@ pub fn initialize_directories(app: AppHandle) -> Result<(), String> {
 
 /// Read the content of a file by relative path
 #[tauri::command]
-pub fn read_file_content(app: AppHandle, relative_path: String) -> Result<String, String> {
-    let base_dir = paths::get_base_directory(&app)?;
-    let file_path = base_dir.join(relative_path);
+pub fn read_file_content(app: AppHandle, relative_path: String) -> Result<String, WorkspaceError> {
+    let file_path = Workspace::load(&app)?.resolve_existing(&relative_path)?;
 
-    fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))
+    fs::read_to_string(&file_path)
+        .map_err(|e| WorkspaceError::Io(format!("Failed to read file: {}", e)))
 }
 
 /// Save a file to scripts or autoexec folder or any descendant path
+///
+/// Luau files are formatted first when `format` is set, or when it is omitted and the
+/// `editor.formatOnSave` setting is on. A script that can't be formatted is saved as is.
+/// The saved content is added to the file's version history, and returned so the editor
+/// can show what was written.
 #[tauri::command]
 pub fn save_file(
     app: AppHandle,
     filename: String,
     folder: String,
     content: String,
-) -> Result<String, String> {
-    let base_dir = paths::get_base_directory(&app)?;
-
-    // Build the target folder path
-    let folder_path = base_dir.join(&folder);
+    format: Option<bool>,
+) -> Result<SavedFile, WorkspaceError> {
+    let workspace = Workspace::load(&app)?;
+    let file_path = workspace.resolve_new(&folder, &filename)?;
+    let relative_path = workspace.relative(&file_path);
 
     // Create the directory structure if it doesn't exist
-    if !folder_path.exists() {
-        fs::create_dir_all(&folder_path)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    if let Some(folder_path) = file_path.parent() {
+        fs::create_dir_all(folder_path)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to create directory: {}", e)))?;
     }
 
-    let file_path = folder_path.join(&filename);
+    let format = format.unwrap_or_else(|| {
+        get_settings_section(&app, "editor")
+            .and_then(|editor| editor.get("formatOnSave").and_then(|v| v.as_bool()))
+            .unwrap_or(false)
+    });
+
+    let content = if format && formatter::is_formattable(&file_path) {
+        match formatter::format_script(&app, &content, Some(&relative_path)) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                log_ui!(&app, Warning, "Saved without formatting: {}", e);
+                content
+            }
+        }
+    } else {
+        content
+    };
 
     // Write the file
-    fs::write(&file_path, content).map_err(|e| format!("Failed to write file: {}", e))?;
-
-    // Return the relative path with forward slashes
-    let relative_path = file_path
-        .strip_prefix(&base_dir)
-        .map_err(|e| format!("Failed to get relative path: {}", e))?
-        .to_string_lossy()
-        .replace('\\', "/");
+    fs::write(&file_path, &content)
+        .map_err(|e| WorkspaceError::Io(format!("Failed to write file: {}", e)))?;
+    versions::record(&app, &relative_path, &content, VersionSource::Save);
 
     // Log successful file save
     log_ui!(&app, Success, "File saved: {}", relative_path);
 
-    Ok(relative_path)
+    Ok(SavedFile {
+        path: relative_path,
+        content,
+    })
 }
 
 /// Rename a file or folder
+///
+/// `new_name` must be a plain name; the item stays in the same folder.
 #[tauri::command]
 pub fn rename_file(
     app: AppHandle,
     relative_path: String,
     new_name: String,
-) -> Result<String, String> {
-    let base_dir = paths::get_base_directory(&app)?;
-    let old_path = base_dir.join(&relative_path);
+) -> Result<String, WorkspaceError> {
+    validate_name(&new_name)?;
+
+    let workspace = Workspace::load(&app)?;
+    let old_path = workspace.resolve_entry(&relative_path)?;
 
     // Build the new path (same parent directory, new name)
     let parent = old_path
         .parent()
-        .ok_or_else(|| "Invalid file path".to_string())?;
+        .ok_or_else(|| WorkspaceError::NotFound(relative_path.clone()))?;
     let new_path = parent.join(&new_name);
 
-    // Check if the old path exists
-    if !old_path.exists() {
-        return Err(format!("File or folder not found: {}", relative_path));
-    }
-
     // Check if a file/folder with the new name already exists
     if new_path.exists() {
-        return Err(format!(
-            "A file or folder named '{}' already exists",
-            new_name
-        ));
+        return Err(WorkspaceError::AlreadyExists(new_name));
     }
 
     // Perform the rename
-    fs::rename(&old_path, &new_path).map_err(|e| format!("Failed to rename: {}", e))?;
+    fs::rename(&old_path, &new_path)
+        .map_err(|e| WorkspaceError::Io(format!("Failed to rename: {}", e)))?;
 
-    // Get the new relative path
-    let new_relative_path = new_path
-        .strip_prefix(&base_dir)
-        .map_err(|e| format!("Failed to get relative path: {}", e))?
-        .to_string_lossy()
-        .replace('\\', "/");
-
-    // Emit file tree changed event
-    emit_or_log(&app, "file-tree-changed", ());
+    let new_relative_path = workspace.relative(&new_path);
+    versions::rename(&app, &workspace.relative(&old_path), &new_relative_path);
 
     // Log the rename operation
     log_ui!(
@@ -119,47 +131,46 @@ pub fn rename_file(
 }
 
 /// Delete a file or folder
+///
+/// The item is moved to the trash, from where `trash_restore` can bring it back.
 #[tauri::command]
-pub fn delete_file(app: AppHandle, relative_path: String, is_folder: bool) -> Result<(), String> {
-    let base_dir = paths::get_base_directory(&app)?;
-    let file_path = base_dir.join(&relative_path);
-
-    // Check if the path exists
-    if !file_path.exists() {
-        return Err(format!("File or folder not found: {}", relative_path));
-    }
-
-    // Delete the file or folder
-    if is_folder {
-        fs::remove_dir_all(&file_path).map_err(|e| format!("Failed to delete folder: {}", e))?;
-    } else {
-        fs::remove_file(&file_path).map_err(|e| format!("Failed to delete file: {}", e))?;
-    }
+pub fn delete_file(
+    app: AppHandle,
+    relative_path: String,
+    is_folder: bool,
+) -> Result<(), WorkspaceError> {
+    let workspace = Workspace::load(&app)?;
+    let file_path = workspace.resolve_entry(&relative_path)?;
 
-    // Emit file tree changed event
-    emit_or_log(&app, "file-tree-changed", ());
+    // Move the file or folder to the trash, under its resolved path
+    trash::with_trash(&app, |trash| {
+        trash.put(&file_path, workspace.relative(&file_path), is_folder)
+    })?;
+    trash::expire(&app);
 
     // Log the delete operation
     let item_type = if is_folder { "folder" } else { "file" };
-    log_ui!(&app, Success, "Deleted {}: {}", item_type, relative_path);
+    log_ui!(
+        &app,
+        Success,
+        "Moved {} to trash: {}",
+        item_type,
+        relative_path
+    );
 
     Ok(())
 }
 
 /// Open the file location in the system file explorer
 #[tauri::command]
-pub fn open_file_location(app: AppHandle, relative_path: String) -> Result<(), String> {
-    let base_dir = paths::get_base_directory(&app)?;
-
-    // Normalize path separators to backslashes on Windows
-    let normalized_path = relative_path.replace('/', "\\");
-    let file_path = base_dir.join(&normalized_path);
+pub fn open_file_location(app: AppHandle, relative_path: String) -> Result<(), WorkspaceError> {
+    let file_path = Workspace::load(&app)?.resolve_existing(&relative_path)?;
 
     // Get the parent directory
     let parent_dir = if file_path.is_file() {
         file_path
             .parent()
-            .ok_or_else(|| "Invalid file path".to_string())?
+            .ok_or_else(|| WorkspaceError::NotFound(relative_path.clone()))?
     } else {
         &file_path
     };
@@ -171,12 +182,14 @@ pub fn open_file_location(app: AppHandle, relative_path: String) -> Result<(), S
         Command::new("explorer")
             .arg(parent_dir)
             .spawn()
-            .map_err(|e| format!("Failed to open file location: {}", e))?;
+            .map_err(|e| WorkspaceError::Io(format!("Failed to open file location: {}", e)))?;
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        return Err("Open file location is only supported on Windows".to_string());
+        return Err(WorkspaceError::Io(
+            "Open file location is only supported on Windows".to_string(),
+        ));
     }
 
     // Log the operation
@@ -0,0 +1,15 @@
+use crate::services::formatter;
+use tauri::AppHandle;
+
+/// Format a script with the workspace's StyLua or `.editorconfig` style
+///
+/// `relative_path` picks the config closest to the file; unsaved scripts use the
+/// workspace style.
+#[tauri::command]
+pub fn format_script(
+    app: AppHandle,
+    content: String,
+    relative_path: Option<String>,
+) -> Result<String, String> {
+    formatter::format_script(&app, &content, relative_path.as_deref())
+}
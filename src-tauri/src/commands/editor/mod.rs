@@ -1,8 +1,20 @@
+mod diagnostics;
 mod file_tree;
 mod files;
+mod format;
+mod quick_open;
+mod search;
+mod trash;
+mod versions;
 
-pub use file_tree::read_file_tree;
+pub use diagnostics::get_diagnostics;
+pub use file_tree::{read_file_subtree, read_file_tree};
 pub use files::{
     delete_file, get_scripts_path, initialize_directories, open_file_location, read_file_content,
     rename_file, save_file,
 };
+pub use format::format_script;
+pub use quick_open::quick_open;
+pub use search::search_workspace;
+pub use trash::{trash_empty, trash_list, trash_restore};
+pub use versions::{version_diff, version_get, version_list, version_restore};
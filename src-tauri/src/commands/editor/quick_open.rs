@@ -0,0 +1,13 @@
+use crate::models::QuickOpenMatch;
+use crate::state::FileIndexState;
+use tauri::State;
+
+/// Fuzzy-find workspace files by path, best matches first
+#[tauri::command]
+pub async fn quick_open(
+    query: String,
+    limit: Option<usize>,
+    index: State<'_, FileIndexState>,
+) -> Result<Vec<QuickOpenMatch>, String> {
+    Ok(index.read().await.query(&query, limit))
+}
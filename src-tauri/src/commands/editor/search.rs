@@ -0,0 +1,15 @@
+use crate::models::{SearchRequest, SearchResults};
+use crate::services::search;
+use tauri::AppHandle;
+
+/// Search file contents under the scripts and autoexec folders
+/// This is an async command so large workspaces don't block the UI
+#[tauri::command]
+pub async fn search_workspace(
+    app: AppHandle,
+    request: SearchRequest,
+) -> Result<SearchResults, String> {
+    tauri::async_runtime::spawn_blocking(move || search::search_workspace(&app, &request))
+        .await
+        .map_err(|e| format!("Failed to spawn search task: {}", e))?
+}
@@ -0,0 +1,35 @@
+use crate::log_ui;
+use crate::models::TrashEntry;
+use crate::services::trash;
+use crate::services::workspace::{Workspace, WorkspaceError};
+use tauri::AppHandle;
+
+/// List deleted files and folders, newest first
+#[tauri::command]
+pub fn trash_list(app: AppHandle) -> Result<Vec<TrashEntry>, WorkspaceError> {
+    trash::with_trash(&app, |trash| Ok(trash.iter().cloned().collect()))
+}
+
+/// Move a deleted item back to where it was
+///
+/// # Returns
+/// The restored path, relative to the base directory
+#[tauri::command]
+pub fn trash_restore(app: AppHandle, id: String) -> Result<String, WorkspaceError> {
+    let workspace = Workspace::load(&app)?;
+    let relative_path = trash::with_trash(&app, |trash| trash.restore(&id, &workspace))?;
+
+    log_ui!(&app, Success, "Restored from trash: {}", relative_path);
+
+    Ok(relative_path)
+}
+
+/// Remove every item in the trash for good, returning the number removed
+#[tauri::command]
+pub fn trash_empty(app: AppHandle) -> Result<usize, WorkspaceError> {
+    let removed = trash::with_trash(&app, |trash| trash.empty())?;
+
+    log_ui!(&app, Success, "Emptied trash: {} item(s) removed", removed);
+
+    Ok(removed)
+}
@@ -0,0 +1,90 @@
+use crate::log_ui;
+use crate::models::{DiffLine, FileVersion, VersionSource};
+use crate::services::versions::{self, diff_lines};
+use crate::services::workspace::{Workspace, WorkspaceError};
+use std::fs;
+use tauri::AppHandle;
+
+/// List the saved versions of a file, newest first
+#[tauri::command]
+pub fn version_list(
+    app: AppHandle,
+    relative_path: String,
+) -> Result<Vec<FileVersion>, WorkspaceError> {
+    versions::with_store(&app, |store| Ok(store.list(&relative_path)))
+}
+
+/// Get the content of a version
+#[tauri::command]
+pub fn version_get(
+    app: AppHandle,
+    relative_path: String,
+    hash: String,
+) -> Result<String, WorkspaceError> {
+    versions::with_store(&app, |store| store.read(&relative_path, &hash))
+}
+
+/// Diff two versions of a file
+///
+/// # Arguments
+/// * `from` - Hash of the older version
+/// * `to` - Hash of the newer version, or `None` for the file as it is on disk
+#[tauri::command]
+pub fn version_diff(
+    app: AppHandle,
+    relative_path: String,
+    from: String,
+    to: Option<String>,
+) -> Result<Vec<DiffLine>, WorkspaceError> {
+    let (old, new) = versions::with_store(&app, |store| {
+        let old = store.read(&relative_path, &from)?;
+        let new = match &to {
+            Some(to) => store.read(&relative_path, to)?,
+            None => {
+                let file_path = Workspace::load(&app)?.resolve_existing(&relative_path)?;
+                fs::read_to_string(file_path)
+                    .map_err(|e| WorkspaceError::Io(format!("Failed to read file: {}", e)))?
+            }
+        };
+        Ok((old, new))
+    })?;
+
+    Ok(diff_lines(&old, &new))
+}
+
+/// Overwrite a file with one of its versions
+///
+/// The file is recreated if it was deleted.
+///
+/// # Returns
+/// The restored content
+#[tauri::command]
+pub fn version_restore(
+    app: AppHandle,
+    relative_path: String,
+    hash: String,
+) -> Result<String, WorkspaceError> {
+    let content = versions::with_store(&app, |store| store.read(&relative_path, &hash))?;
+
+    let (folder, filename) = relative_path
+        .rsplit_once('/')
+        .unwrap_or(("", relative_path.as_str()));
+    let file_path = Workspace::load(&app)?.resolve_new(folder, filename)?;
+    if let Some(folder_path) = file_path.parent() {
+        fs::create_dir_all(folder_path)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to create directory: {}", e)))?;
+    }
+    fs::write(&file_path, &content)
+        .map_err(|e| WorkspaceError::Io(format!("Failed to write file: {}", e)))?;
+    versions::record(&app, &relative_path, &content, VersionSource::Restore);
+
+    log_ui!(
+        &app,
+        Success,
+        "Restored {} to version {}",
+        relative_path,
+        &hash[..hash.len().min(8)]
+    );
+
+    Ok(content)
+}
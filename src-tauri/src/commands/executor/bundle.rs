@@ -0,0 +1,13 @@
+use crate::models::ScriptBundle;
+use crate::services::bundler;
+use tauri::AppHandle;
+
+/// Bundle a script's local `require` and `--#include` references without executing it
+#[tauri::command]
+pub async fn bundle_script(
+    app: AppHandle,
+    script: String,
+    path: Option<String>,
+) -> Result<ScriptBundle, String> {
+    bundler::bundle_script(&app, &script, path.as_deref())
+}
@@ -1,12 +1,10 @@
-use crate::models::ExecuteRequest;
-use crate::state::ClientRegistry;
-use tauri::State;
+use crate::models::{ExecuteRequest, ScriptPayload};
+use crate::services::history::{self, Execution};
+use crate::services::{bundler, diagnostics};
+use tauri::AppHandle;
 
 #[tauri::command]
-pub async fn exec(
-    request: ExecuteRequest,
-    clients: State<'_, ClientRegistry>,
-) -> Result<(), String> {
+pub async fn exec(app: AppHandle, request: ExecuteRequest) -> Result<(), String> {
     // Validate script is not empty
     if request.script.trim().is_empty() {
         return Err("Script cannot be empty".to_string());
@@ -17,12 +15,36 @@ pub async fn exec(
         return Err("No clients selected for execution".to_string());
     }
 
-    // Broadcast to selected clients
-    crate::services::websocket::broadcast_to_clients(
-        request.client_ids,
-        request.script,
-        request.redirect,
-        clients.inner().clone(),
+    // Catch syntax errors before a round trip to the game
+    if request.check_syntax {
+        diagnostics::check_syntax(&request.script)?;
+    }
+
+    let mut payload = ScriptPayload::new(request.script, request.redirect);
+    payload.template = request.template;
+    payload.args = request.args;
+    if let Some(id) = request.id {
+        payload.id = id;
+    }
+
+    // Resolve local modules into a single chunk
+    if request.bundle {
+        let bundle = bundler::bundle_script(&app, &payload.script, request.path.as_deref())?;
+        payload.script = bundle.script;
+        if !bundle.line_map.is_empty() {
+            payload.line_map = Some(bundle.line_map);
+        }
+    }
+
+    // Broadcast to selected clients and record in history
+    history::execute(
+        &app,
+        Execution {
+            source: request.source,
+            client_ids: request.client_ids,
+            payload,
+            rerun_of: None,
+        },
     )
     .await?;
 
@@ -0,0 +1,34 @@
+use crate::models::{ExecutionOutputLine, RunningExecution};
+use crate::services::executions;
+use crate::state::ExecutionsState;
+use tauri::{AppHandle, State};
+
+/// List executions still running on attached clients
+#[tauri::command]
+pub async fn executions_list(
+    executions: State<'_, ExecutionsState>,
+) -> Result<Vec<RunningExecution>, String> {
+    Ok(executions.read().await.list())
+}
+
+/// Get the redirected output of a recent execution, optionally from a single client
+#[tauri::command]
+pub async fn execution_output(
+    executions: State<'_, ExecutionsState>,
+    id: String,
+    client_id: Option<String>,
+) -> Result<Vec<ExecutionOutputLine>, String> {
+    Ok(executions.read().await.output(&id, client_id.as_deref()))
+}
+
+/// Cancel a running execution on one client, or on every client running it
+///
+/// Returns the IDs of the clients the cancellation was sent to.
+#[tauri::command]
+pub async fn execution_cancel(
+    app: AppHandle,
+    id: String,
+    client_id: Option<String>,
+) -> Result<Vec<String>, String> {
+    executions::cancel(&app, &id, client_id.as_deref()).await
+}
@@ -1,5 +1,9 @@
+mod bundle;
 mod execute;
+mod executions;
 mod status;
 
+pub use bundle::bundle_script;
 pub use execute::exec;
+pub use executions::{execution_cancel, execution_output, executions_list};
 pub use status::get_attached_clients;
@@ -0,0 +1,68 @@
+use crate::models::{HistoryEntry, HistorySummary};
+use crate::services::history::HistoryLimits;
+use crate::state::HistoryState;
+use tauri::{AppHandle, State};
+
+/// List history entries, newest first
+#[tauri::command]
+pub async fn history_list(
+    limit: Option<usize>,
+    offset: Option<usize>,
+    history: State<'_, HistoryState>,
+) -> Result<Vec<HistorySummary>, String> {
+    let history = history.read().await;
+    Ok(history
+        .iter()
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
+        .map(HistorySummary::from)
+        .collect())
+}
+
+/// Search history entries by script content, username, source, or ID/hash prefix
+#[tauri::command]
+pub async fn history_search(
+    query: String,
+    limit: Option<usize>,
+    history: State<'_, HistoryState>,
+) -> Result<Vec<HistorySummary>, String> {
+    let history = history.read().await;
+    Ok(history
+        .search(&query)
+        .take(limit.unwrap_or(usize::MAX))
+        .map(HistorySummary::from)
+        .collect())
+}
+
+/// Get a history entry with its full script
+#[tauri::command]
+pub async fn history_get(
+    id: String,
+    history: State<'_, HistoryState>,
+) -> Result<HistoryEntry, String> {
+    history
+        .read()
+        .await
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| format!("History entry not found: {}", id))
+}
+
+/// Prune history using the configured limits, returning the number of entries removed
+#[tauri::command]
+pub async fn history_prune(
+    app: AppHandle,
+    history: State<'_, HistoryState>,
+) -> Result<usize, String> {
+    let limits = HistoryLimits::load(&app);
+    let mut history = history.write().await;
+    let removed = history.prune(limits);
+    history.save();
+    Ok(removed)
+}
+
+#[tauri::command]
+pub async fn history_clear(history: State<'_, HistoryState>) -> Result<(), String> {
+    history.write().await.clear();
+    Ok(())
+}
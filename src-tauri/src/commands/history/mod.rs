@@ -0,0 +1,9 @@
+//! Execution history command handlers
+//!
+//! Commands for browsing, searching, pruning and re-running recorded executions.
+
+mod entries;
+mod rerun;
+
+pub use entries::{history_clear, history_get, history_list, history_prune, history_search};
+pub use rerun::history_rerun;
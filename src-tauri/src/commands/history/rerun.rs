@@ -0,0 +1,58 @@
+use crate::models::{ExecutionSource, ScriptPayload};
+use crate::services::history::{self, Execution};
+use crate::state::{ClientRegistry, HistoryState};
+use tauri::{AppHandle, State};
+
+/// Re-run a history entry
+///
+/// Targets the given clients, or the entry's original clients that are still attached.
+/// Returns the IDs of the clients the script was sent to.
+#[tauri::command]
+pub async fn history_rerun(
+    app: AppHandle,
+    id: String,
+    client_ids: Option<Vec<String>>,
+    history: State<'_, HistoryState>,
+    clients: State<'_, ClientRegistry>,
+) -> Result<Vec<String>, String> {
+    let entry = history
+        .read()
+        .await
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| format!("History entry not found: {}", id))?;
+
+    let client_ids = match client_ids {
+        Some(ids) => ids,
+        None => {
+            let clients = clients.read().await;
+            entry
+                .client_ids
+                .iter()
+                .filter(|id| clients.contains_key(*id))
+                .cloned()
+                .collect()
+        }
+    };
+
+    if client_ids.is_empty() {
+        return Err("None of the original clients are attached".to_string());
+    }
+
+    // A re-run is a new execution with its own ID
+    let mut payload = ScriptPayload::new(entry.script, entry.redirect);
+    payload.line_map = entry.line_map;
+    payload.template = entry.template;
+    payload.args = entry.args;
+
+    history::execute(
+        &app,
+        Execution {
+            source: ExecutionSource::History,
+            client_ids,
+            payload,
+            rerun_of: Some(entry.id),
+        },
+    )
+    .await
+}
@@ -10,7 +10,12 @@ pub fn add_log(app: AppHandle, level: u8, message: String) -> Result<(), String>
     }
 
     // Emit log-message event to frontend
-    let log_msg = LogMessage { level, message };
+    let log_msg = LogMessage {
+        level,
+        message,
+        execution_id: None,
+        client_id: None,
+    };
 
     emit_or_error(&app, "log-message", log_msg)?;
 
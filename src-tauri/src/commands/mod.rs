@@ -1,8 +1,12 @@
+pub mod autoexec;
 pub mod editor;
 pub mod executor;
 pub mod explorer;
+pub mod history;
 pub mod launcher;
 pub mod logs;
 pub mod remote_spy;
+pub mod scheduler;
 pub mod script_hub;
+pub mod servers;
 pub mod settings;
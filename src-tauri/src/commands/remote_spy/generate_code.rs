@@ -1,14 +1,8 @@
-use crate::services::websocket::send_generate_code_request;
-use crate::state::{ActiveClientsState, ClientRegistry};
-use crate::utils::clients::get_active_remote_spy;
-use tauri::State;
+use crate::services::remote_spy;
+use tauri::AppHandle;
 
 #[tauri::command]
-pub async fn rspy_generate_code(
-    call_id: u32,
-    active_clients: State<'_, ActiveClientsState>,
-    clients: State<'_, ClientRegistry>,
-) -> Result<(), String> {
-    let client_id = get_active_remote_spy(&active_clients).await?;
-    send_generate_code_request(&client_id, call_id, &clients).await
+pub async fn rspy_generate_code(call_id: u32, app: AppHandle) -> Result<(), String> {
+    remote_spy::send_generate_code_request(&app, call_id).await?;
+    Ok(())
 }
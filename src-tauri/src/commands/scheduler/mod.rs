@@ -0,0 +1,10 @@
+//! Scheduler command handlers
+//!
+//! Commands for managing scheduled and recurring script executions.
+
+mod schedules;
+
+pub use schedules::{
+    schedule_create, schedule_delete, schedule_list, schedule_run_now, schedule_set_enabled,
+    schedule_update,
+};
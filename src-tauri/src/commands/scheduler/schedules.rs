@@ -0,0 +1,71 @@
+use crate::models::{Schedule, ScheduleRequest};
+use crate::services::scheduler;
+use crate::state::SchedulerState;
+use crate::utils::events::emit_or_log;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn schedule_list(scheduler: State<'_, SchedulerState>) -> Result<Vec<Schedule>, String> {
+    Ok(scheduler.read().await.list())
+}
+
+#[tauri::command]
+pub async fn schedule_create(
+    app: AppHandle,
+    request: ScheduleRequest,
+    scheduler: State<'_, SchedulerState>,
+) -> Result<Schedule, String> {
+    let mut scheduler = scheduler.write().await;
+    let schedule = scheduler.create(request)?;
+
+    log_ui!(&app, Success, "Schedule created: {}", schedule.name);
+    emit_or_log(&app, "schedules-update", scheduler.list());
+    Ok(schedule)
+}
+
+#[tauri::command]
+pub async fn schedule_update(
+    app: AppHandle,
+    id: String,
+    request: ScheduleRequest,
+    scheduler: State<'_, SchedulerState>,
+) -> Result<Schedule, String> {
+    let mut scheduler = scheduler.write().await;
+    let schedule = scheduler.update(&id, request)?;
+
+    emit_or_log(&app, "schedules-update", scheduler.list());
+    Ok(schedule)
+}
+
+#[tauri::command]
+pub async fn schedule_set_enabled(
+    app: AppHandle,
+    id: String,
+    enabled: bool,
+    scheduler: State<'_, SchedulerState>,
+) -> Result<Schedule, String> {
+    let mut scheduler = scheduler.write().await;
+    let schedule = scheduler.set_enabled(&id, enabled)?;
+
+    emit_or_log(&app, "schedules-update", scheduler.list());
+    Ok(schedule)
+}
+
+#[tauri::command]
+pub async fn schedule_delete(
+    app: AppHandle,
+    id: String,
+    scheduler: State<'_, SchedulerState>,
+) -> Result<(), String> {
+    let mut scheduler = scheduler.write().await;
+    scheduler.delete(&id)?;
+
+    emit_or_log(&app, "schedules-update", scheduler.list());
+    Ok(())
+}
+
+/// Run a schedule immediately, returning the IDs of the clients it was sent to
+#[tauri::command]
+pub async fn schedule_run_now(app: AppHandle, id: String) -> Result<Vec<String>, String> {
+    scheduler::run_now(&app, &id).await
+}
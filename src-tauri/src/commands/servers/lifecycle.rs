@@ -0,0 +1,23 @@
+use crate::models::ServerKind;
+use crate::services::servers;
+use tauri::AppHandle;
+
+/// Start a stopped server, returning the port it bound to
+#[tauri::command]
+pub async fn server_start(app: AppHandle, server: ServerKind) -> Result<u16, String> {
+    let addr = servers::start_server(&app, server).await?;
+    Ok(addr.port())
+}
+
+#[tauri::command]
+pub async fn server_stop(app: AppHandle, server: ServerKind) -> Result<(), String> {
+    servers::stop_server(&app, server).await
+}
+
+/// Restart a server, re-reading its port from settings
+#[tauri::command]
+pub async fn server_restart(app: AppHandle, server: ServerKind) -> Result<u16, String> {
+    log::info!("Restarting {} server", server);
+    let addr = servers::restart_server(&app, server).await?;
+    Ok(addr.port())
+}
@@ -0,0 +1,10 @@
+//! Server command handlers
+//!
+//! Commands for stopping, starting and restarting the backend servers at runtime,
+//! and for reporting their status.
+
+mod lifecycle;
+mod status;
+
+pub use lifecycle::{server_restart, server_start, server_stop};
+pub use status::get_backend_status;
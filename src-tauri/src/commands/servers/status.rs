@@ -0,0 +1,9 @@
+use crate::models::BackendStatus;
+use crate::services::status;
+use tauri::AppHandle;
+
+/// Get server uptime and connections, and traffic counters for each client
+#[tauri::command]
+pub async fn get_backend_status(app: AppHandle) -> Result<BackendStatus, String> {
+    Ok(status::collect_status(&app).await)
+}
@@ -7,22 +7,38 @@ mod models;
 pub mod services;
 mod state;
 
+use commands::autoexec::{autoexec_dry_run, autoexec_list, autoexec_set_enabled};
 use commands::editor::{
-    delete_file, get_scripts_path, initialize_directories, open_file_location, read_file_content,
-    read_file_tree, rename_file, save_file,
+    delete_file, format_script, get_diagnostics, get_scripts_path, initialize_directories,
+    open_file_location, quick_open, read_file_content, read_file_subtree, read_file_tree,
+    rename_file, save_file, search_workspace, trash_empty, trash_list, trash_restore,
+    version_diff, version_get, version_list, version_restore,
+};
+use commands::executor::{
+    bundle_script, exec, execution_cancel, execution_output, executions_list, get_attached_clients,
 };
-use commands::executor::{exec, get_attached_clients};
 use commands::explorer::{
     exp_decompile, exp_get_properties, exp_get_tree, exp_search, exp_start, exp_stop,
 };
+use commands::history::{
+    history_clear, history_get, history_list, history_prune, history_rerun, history_search,
+};
 use commands::launcher::{launcher_register, launcher_launch, launcher_check_registration, launcher_fetch_past_versions};
 use commands::logs::add_log;
 use commands::remote_spy::{rspy_decompile, rspy_generate_code, rspy_start, rspy_stop};
+use commands::scheduler::{
+    schedule_create, schedule_delete, schedule_list, schedule_run_now, schedule_set_enabled,
+    schedule_update,
+};
+use commands::servers::{get_backend_status, server_restart, server_start, server_stop};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::Manager;
 use tokio::sync::RwLock;
 
+/// Events buffered for slow `GET /v1/events` subscribers before they miss some
+const EVENT_BUS_CAPACITY: usize = 1024;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -37,9 +53,13 @@ pub fn run() {
                     .build(),
             )?;
 
+            // Initialize the event bus before anything emits
+            let (event_bus, _) = tokio::sync::broadcast::channel(EVENT_BUS_CAPACITY);
+            app.manage::<state::EventBus>(event_bus);
+
             // Initialize client registry
             let clients: state::ClientRegistry = Arc::new(RwLock::new(HashMap::new()));
-            app.manage(clients.clone());
+            app.manage(clients);
 
             // Initialize active clients state
             let active_clients =
@@ -47,7 +67,7 @@ pub fn run() {
                     explorer: None,
                     remote_spy: None,
                 }));
-            app.manage(active_clients.clone());
+            app.manage(active_clients);
 
             // Initialize launcher queue registry
             let launcher_queue_registry: state::LauncherQueueRegistry = Arc::new(RwLock::new(HashMap::new()));
@@ -68,26 +88,33 @@ pub fn run() {
                 }
             });
 
-            // Start the WebSocket server
-            let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) = services::websocket::start_websocket_server(
-                    app_handle.clone(),
-                    clients,
-                    active_clients,
-                    api_dump_cache,
-                )
-                .await
-                {
-                    log::error!("Failed to start WebSocket server: {}", e);
-                    log_ui!(
-                        &app_handle,
-                        Error,
-                        "Failed to start WebSocket server: {}",
-                        e
-                    );
-                }
-            });
+            // Load execution history
+            let history: state::HistoryState = Arc::new(RwLock::new(
+                services::history::ExecutionHistory::load(app.handle()),
+            ));
+            app.manage(history);
+
+            // Initialize running executions
+            let executions: state::ExecutionsState = Arc::new(RwLock::new(Default::default()));
+            app.manage(executions);
+
+            // Load schedules and start the scheduler
+            let scheduler: state::SchedulerState = Arc::new(RwLock::new(
+                services::scheduler::Scheduler::load(app.handle()),
+            ));
+            app.manage(scheduler);
+            services::scheduler::start_scheduler(app.handle().clone());
+
+            // Remove expired items from the trash
+            services::trash::expire(app.handle());
+
+            // Initialize server registry
+            let server_registry: state::ServerRegistry = Arc::new(RwLock::new(HashMap::new()));
+            app.manage(server_registry);
+
+            // Initialize the quick open index, which the file watcher fills
+            let file_index: state::FileIndexState = Arc::new(RwLock::new(Default::default()));
+            app.manage(file_index);
 
             // Start the file watcher
             let app_handle = app.handle().clone();
@@ -96,28 +123,10 @@ pub fn run() {
                 log_ui!(&app_handle, Error, "Failed to start file watcher: {}", e);
             }
 
-            // Start the HTTP server
-            let app_handle_http = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) =
-                    services::http_server::start_http_server(app_handle_http.clone()).await
-                {
-                    log::error!("Failed to start HTTP server: {}", e);
-                    log_ui!(
-                        &app_handle_http,
-                        Error,
-                        "Failed to start HTTP server: {}",
-                        e
-                    );
-                }
-            });
-
-            // Start the launcher WebSocket server
-            let app_handle_launcher = app.handle().clone();
+            // Start the WebSocket, HTTP and launcher WebSocket servers
+            let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = services::launcher::start_launcher_websocket(app_handle_launcher).await {
-                    log::error!("Failed to start launcher WebSocket server: {}", e);
-                }
+                services::servers::start_all(&app_handle).await;
             });
 
             Ok(())
@@ -127,14 +136,43 @@ pub fn run() {
             get_scripts_path,
             initialize_directories,
             read_file_tree,
+            read_file_subtree,
             read_file_content,
             save_file,
             rename_file,
             delete_file,
             open_file_location,
+            get_diagnostics,
+            format_script,
+            search_workspace,
+            quick_open,
+            // Trash commands
+            trash_list,
+            trash_restore,
+            trash_empty,
+            // Version history commands
+            version_list,
+            version_get,
+            version_diff,
+            version_restore,
             // Executor commands
             exec,
+            bundle_script,
+            executions_list,
+            execution_cancel,
+            execution_output,
             get_attached_clients,
+            // Autoexec commands
+            autoexec_list,
+            autoexec_set_enabled,
+            autoexec_dry_run,
+            // History commands
+            history_list,
+            history_search,
+            history_get,
+            history_rerun,
+            history_prune,
+            history_clear,
             // Explorer commands
             exp_start,
             exp_stop,
@@ -147,11 +185,23 @@ pub fn run() {
             rspy_stop,
             rspy_decompile,
             rspy_generate_code,
+            // Scheduler commands
+            schedule_list,
+            schedule_create,
+            schedule_update,
+            schedule_set_enabled,
+            schedule_delete,
+            schedule_run_now,
             // Launcher commands
             launcher_register,
             launcher_launch,
             launcher_check_registration,
             launcher_fetch_past_versions,
+            // Server commands
+            server_start,
+            server_stop,
+            server_restart,
+            get_backend_status,
             // Logs commands
             add_log,
             // Script Hub commands (future)
@@ -159,6 +209,12 @@ pub fn run() {
             // Settings commands (future)
             // get_settings,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Close client connections with a reason before the process exits
+            if let tauri::RunEvent::Exit = event {
+                tauri::async_runtime::block_on(services::servers::shutdown_all(app));
+            }
+        });
 }
@@ -14,6 +14,11 @@ fn main() {
         return;
     }
 
+    // CLI subcommands talk to the running instance instead of starting one
+    if proxima_lib::services::cli::is_cli_command(&args) {
+        std::process::exit(proxima_lib::services::cli::run_cli(&args));
+    }
+
     // Otherwise, run the normal Tauri application
     proxima_lib::run();
 }
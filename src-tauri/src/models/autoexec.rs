@@ -0,0 +1,40 @@
+use serde::Serialize;
+
+/// Whether an autoexec script would run on a client
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoexecStatus {
+    Run,
+    /// Runs once the client registers its username
+    Deferred,
+    Skipped,
+}
+
+/// An autoexec script as listed in the UI
+#[derive(Serialize, Clone, Debug)]
+pub struct AutoexecEntry {
+    pub path: String,
+    pub enabled: bool,
+    pub priority: i32,
+    pub after: Vec<String>,
+}
+
+/// A script in an autoexec dry run
+#[derive(Serialize, Clone, Debug)]
+pub struct AutoexecPlanEntry {
+    pub path: String,
+    pub status: AutoexecStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub priority: i32,
+    pub delay: Option<f64>,
+    pub wait_for_load: bool,
+}
+
+/// What autoexec would do for a client
+#[derive(Serialize, Clone, Debug)]
+pub struct AutoexecDryRun {
+    /// Whether auto-execute is enabled in settings
+    pub auto_execute: bool,
+    pub scripts: Vec<AutoexecPlanEntry>,
+}
@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// A syntax error or lint reported for a script
+///
+/// Lines and columns are 1-based, matching the editor.
+#[derive(Serialize, Clone, Debug)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub severity: DiagnosticSeverity,
+    /// `syntax` for parse errors, otherwise the name of the lint
+    pub rule: String,
+    pub message: String,
+}
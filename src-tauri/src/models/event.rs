@@ -0,0 +1,14 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+/// An event emitted to the frontend, as streamed to external tools by `GET /v1/events`
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct BackendEvent {
+    /// Event name, e.g. `log-message`
+    pub event: String,
+    /// Client whose message caused the event, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    pub payload: Value,
+}
@@ -0,0 +1,132 @@
+use super::{ExecutionOutcome, ExecutionSource};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How a script ended on a client, as reported by the client
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    Running,
+    Finished,
+    Failed,
+    Cancelled,
+    /// The client disconnected before the script ended
+    Disconnected,
+}
+
+/// A script still running on a client
+///
+/// A script keeps running until its chunk, every thread it spawned and every signal
+/// connection it made have ended.
+#[derive(Serialize, Clone, Debug)]
+pub struct RunningExecution {
+    pub id: String,
+    pub client_id: String,
+    pub username: String,
+    pub source: ExecutionSource,
+    /// Milliseconds since the Unix epoch
+    pub started_at: u64,
+}
+
+/// How an execution went on one client
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct ClientExecution {
+    pub client_id: String,
+    pub username: String,
+    pub status: ExecutionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Milliseconds since the Unix epoch
+    pub started_at: u64,
+    /// Unset while the execution is running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+}
+
+/// An execution with its state on each client, returned by `GET /v1/executions/{id}`
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct ExecutionDetails {
+    pub id: String,
+    /// When the script was sent, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub source: ExecutionSource,
+    /// Whether the script reached its target clients
+    pub outcome: ExecutionOutcome,
+    /// Why the script couldn't be sent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub clients: Vec<ClientExecution>,
+    /// Redirected output, oldest first
+    pub output: Vec<ExecutionOutputLine>,
+}
+
+/// Result of an execution on one client, returned when waiting for an execution
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct ClientResult {
+    pub client_id: String,
+    pub username: String,
+    pub status: ExecutionStatus,
+    /// Whether the script finished without an error
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Unset if the script was still running when the wait timed out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Redirected output from this client, oldest first
+    pub output: Vec<ExecutionOutputLine>,
+}
+
+/// Result of an execution on every client it reached
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct ExecutionResult {
+    pub execution_id: String,
+    /// Whether the script reached a client and finished without an error on all of them
+    pub success: bool,
+    /// Whether the wait ended before the script ended on every client
+    pub timed_out: bool,
+    pub clients: Vec<ClientResult>,
+}
+
+/// A line of redirected output from an execution
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct ExecutionOutputLine {
+    pub execution_id: String,
+    pub client_id: String,
+    pub username: String,
+    /// Log level, as in `LogMessage`
+    pub level: u8,
+    pub message: String,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// A change to an execution, as streamed to HTTP callers
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    /// The script was sent to these clients
+    Started {
+        execution_id: String,
+        client_ids: Vec<String>,
+    },
+    Output(ExecutionOutputLine),
+    /// The script ended on a client
+    Done {
+        execution_id: String,
+        client_id: String,
+        status: ExecutionStatus,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+impl ExecutionEvent {
+    pub fn execution_id(&self) -> &str {
+        match self {
+            ExecutionEvent::Started { execution_id, .. } => execution_id,
+            ExecutionEvent::Output(line) => &line.execution_id,
+            ExecutionEvent::Done { execution_id, .. } => execution_id,
+        }
+    }
+}
@@ -1,4 +1,9 @@
+use super::ExecutionSource;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Client {
@@ -6,9 +11,100 @@ pub struct Client {
     pub username: String,
 }
 
+/// An attached client and the game it's in, returned by `GET /v1/clients`
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct AttachedClient {
+    pub id: String,
+    pub username: String,
+    pub place_id: Option<u64>,
+    pub game_id: Option<u64>,
+    pub tags: Vec<String>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ExecuteRequest {
     pub client_ids: Vec<String>,
     pub script: String,
     pub redirect: bool,
+    #[serde(default)]
+    pub source: ExecutionSource,
+    /// Bundle local `require("./x")` and `--#include` references before sending
+    #[serde(default)]
+    pub bundle: bool,
+    /// Path of the script relative to the base directory, for resolving `./` references
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Refuse to send the script if it doesn't parse
+    #[serde(default)]
+    pub check_syntax: bool,
+    /// Expand `{{placeholders}}` for each client before sending; omit to send the script as is
+    #[serde(default)]
+    pub template: Option<TemplateVariables>,
+    /// JSON passed to the script as `...`; an array is passed as separate values
+    #[serde(default)]
+    pub args: Option<Value>,
+    /// Execution ID to use instead of a generated one, so callers can follow the output
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+/// User-defined template variables, alongside the built-in `{{username}}`, `{{client_id}}`,
+/// `{{place_id}}`, `{{game_id}}`, `{{index}}` and `{{count}}`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct TemplateVariables {
+    /// Variables for every client
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Variables for specific clients, keyed by username or client ID, overriding `variables`
+    #[serde(default)]
+    pub clients: HashMap<String, HashMap<String, String>>,
+}
+
+/// Maps a range of lines in a bundled script back to the file they came from
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LineMapping {
+    /// First bundle line of the range (1-based)
+    pub start: u32,
+    /// Last bundle line of the range (inclusive)
+    pub end: u32,
+    /// Source file, relative to the base directory
+    pub path: String,
+    /// Line in the source file that `start` corresponds to
+    pub line: u32,
+}
+
+/// A script with its local modules bundled in
+#[derive(Serialize, Clone, Debug)]
+pub struct ScriptBundle {
+    pub script: String,
+    /// Empty if the script had no local references and was left unchanged
+    pub line_map: Vec<LineMapping>,
+}
+
+/// A script and the options it is sent to clients with
+#[derive(Clone, Debug)]
+pub struct ScriptPayload {
+    /// Execution ID, shared by every client the script is sent to
+    pub id: String,
+    pub script: String,
+    pub redirect: bool,
+    /// Present for bundled scripts, so clients can map error lines back to source files
+    pub line_map: Option<Vec<LineMapping>>,
+    /// Present when placeholders should be expanded for each client
+    pub template: Option<TemplateVariables>,
+    /// JSON passed to the script as `...`
+    pub args: Option<Value>,
+}
+
+impl ScriptPayload {
+    pub fn new(script: String, redirect: bool) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            script,
+            redirect,
+            line_map: None,
+            template: None,
+            args: None,
+        }
+    }
 }
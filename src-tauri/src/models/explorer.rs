@@ -1,8 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Represents a node in the explorer tree
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ExplorerNode {
     pub id: u32,
     pub n: String, // name
@@ -12,7 +13,7 @@ pub struct ExplorerNode {
 }
 
 /// Property data for an instance
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PropertyData {
     pub value: String,
     pub r#type: String,
@@ -28,7 +29,7 @@ pub struct PropertyData {
 }
 
 /// Search result item
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SearchResult {
     pub id: u32,
     pub n: String,
@@ -39,12 +40,12 @@ pub struct SearchResult {
 }
 
 /// Event payloads for frontend communication
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TreeEvent {
     pub nodes: Vec<ExplorerNode>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PropertiesEvent {
     pub id: u32,
     pub props: HashMap<String, PropertyData>,
@@ -52,7 +53,7 @@ pub struct PropertiesEvent {
     pub special_props: HashMap<String, PropertyData>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SearchResultsEvent {
     pub query: String,
     pub results: Vec<SearchResult>,
@@ -60,7 +61,7 @@ pub struct SearchResultsEvent {
     pub limited: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DecompiledScriptEvent {
     pub id: u32,
     pub source: String,
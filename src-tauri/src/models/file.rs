@@ -17,3 +17,41 @@ pub enum FileNode {
         children: Vec<FileNode>,
     },
 }
+
+/// A file written by `save_file`
+#[derive(Debug, Serialize, Clone)]
+pub struct SavedFile {
+    pub path: String, // Relative path from base directory
+    /// The content written, which differs from the content sent when it was formatted
+    pub content: String,
+}
+
+/// A workspace file matching a quick open query
+#[derive(Debug, Serialize, Clone)]
+pub struct QuickOpenMatch {
+    pub path: String, // Relative path from base directory
+    pub name: String,
+    pub score: i64,
+    /// Character indices in `path` that matched the query, for highlighting
+    pub positions: Vec<usize>,
+}
+
+/// How a watched file or folder changed
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Created,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+/// A change to the workspace picked up by the file watcher
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub kind: FileChangeKind,
+    pub path: String, // Relative path from base directory
+    /// Path before a rename
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+}
@@ -0,0 +1,111 @@
+use super::{LineMapping, TemplateVariables};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Where an execution was started from
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionSource {
+    #[default]
+    Editor,
+    Http,
+    Autoexec,
+    Schedule,
+    /// Re-run of an earlier history entry
+    History,
+}
+
+impl ExecutionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionSource::Editor => "editor",
+            ExecutionSource::Http => "http",
+            ExecutionSource::Autoexec => "autoexec",
+            ExecutionSource::Schedule => "schedule",
+            ExecutionSource::History => "history",
+        }
+    }
+}
+
+/// Whether a script reached its target clients
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionOutcome {
+    /// Sent to every target client
+    Sent,
+    /// Sent to some target clients
+    Partial,
+    /// Not sent to any client
+    Failed,
+}
+
+/// A recorded script execution
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryEntry {
+    pub id: String,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub source: ExecutionSource,
+    /// Target client IDs (empty for autoexec, which runs before the client registers)
+    pub client_ids: Vec<String>,
+    /// Usernames of the targets at the time of execution
+    pub usernames: Vec<String>,
+    /// Client IDs the script was delivered to
+    pub delivered: Vec<String>,
+    pub script_hash: String,
+    pub script: String,
+    pub redirect: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_map: Option<Vec<LineMapping>>,
+    /// Template variables the script was expanded with, per client
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<TemplateVariables>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Value>,
+    pub outcome: ExecutionOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// ID of the entry this execution re-ran
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerun_of: Option<String>,
+}
+
+/// History entry without the script content, for listing
+#[derive(Serialize, Clone, Debug)]
+pub struct HistorySummary {
+    pub id: String,
+    pub timestamp: u64,
+    pub source: ExecutionSource,
+    pub usernames: Vec<String>,
+    pub script_hash: String,
+    /// First non-empty line of the script, truncated
+    pub preview: String,
+    pub outcome: ExecutionOutcome,
+}
+
+const PREVIEW_LENGTH: usize = 80;
+
+impl From<&HistoryEntry> for HistorySummary {
+    fn from(entry: &HistoryEntry) -> Self {
+        let preview = entry
+            .script
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default()
+            .chars()
+            .take(PREVIEW_LENGTH)
+            .collect();
+
+        Self {
+            id: entry.id.clone(),
+            timestamp: entry.timestamp,
+            source: entry.source,
+            usernames: entry.usernames.clone(),
+            script_hash: entry.script_hash.clone(),
+            preview,
+            outcome: entry.outcome,
+        }
+    }
+}
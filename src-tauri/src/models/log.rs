@@ -11,4 +11,10 @@ use serde::{Deserialize, Serialize};
 pub struct LogMessage {
     pub level: u8,
     pub message: String,
+    /// Execution that printed this message, for redirected script output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_id: Option<String>,
+    /// Client that printed this message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
 }
@@ -1,9 +1,39 @@
+pub mod autoexec;
+pub mod diagnostics;
+pub mod event;
+pub mod execution;
 pub mod executor;
 pub mod explorer;
 pub mod file;
+pub mod history;
 pub mod log;
 pub mod remote_spy;
+pub mod schedule;
+pub mod search;
+pub mod selector;
+pub mod server;
+pub mod status;
+pub mod trash;
+pub mod version;
 
-pub use executor::{Client, ExecuteRequest};
-pub use file::FileNode;
+pub use autoexec::{AutoexecDryRun, AutoexecEntry, AutoexecPlanEntry, AutoexecStatus};
+pub use diagnostics::{Diagnostic, DiagnosticSeverity};
+pub use event::BackendEvent;
+pub use execution::{
+    ClientExecution, ClientResult, ExecutionDetails, ExecutionEvent, ExecutionOutputLine,
+    ExecutionResult, ExecutionStatus, RunningExecution,
+};
+pub use executor::{
+    AttachedClient, Client, ExecuteRequest, LineMapping, ScriptBundle, ScriptPayload,
+    TemplateVariables,
+};
+pub use file::{FileChange, FileChangeKind, FileNode, QuickOpenMatch, SavedFile};
+pub use history::{ExecutionOutcome, ExecutionSource, HistoryEntry, HistorySummary};
 pub use log::LogMessage;
+pub use schedule::{Schedule, ScheduleRequest, ScheduleTrigger, ScheduledScript};
+pub use search::{SearchMatch, SearchRequest, SearchResults};
+pub use selector::ClientSelector;
+pub use server::{InstanceInfo, ServerKind};
+pub use status::{BackendStatus, ClientStatus, ClientTraffic, ServerStatus};
+pub use trash::TrashEntry;
+pub use version::{DiffKind, DiffLine, FileVersion, VersionSource};
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Represents an argument or return value
@@ -31,3 +32,19 @@ pub struct RemoteCallEvent {
     #[serde(rename = "callingScriptPath")]
     pub calling_script_path: Option<String>,
 }
+
+/// Event payload for a decompiled calling script
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RemoteDecompiledEvent {
+    #[serde(rename = "callId")]
+    pub call_id: u32,
+    pub source: String,
+}
+
+/// Event payload for code generated to repeat a call
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RemoteGeneratedCodeEvent {
+    #[serde(rename = "callId")]
+    pub call_id: u32,
+    pub code: String,
+}
@@ -0,0 +1,63 @@
+use super::ClientSelector;
+use serde::{Deserialize, Serialize};
+
+/// When a schedule runs
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleTrigger {
+    /// Once, at a time in milliseconds since the Unix epoch
+    At { at: u64 },
+    /// Repeatedly, every `seconds`
+    Interval { seconds: u64 },
+    /// On a cron expression in local time (5 fields, or 6 with seconds)
+    Cron { expression: String },
+}
+
+/// The script a schedule runs
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduledScript {
+    Inline {
+        content: String,
+    },
+    /// A file in `scripts/`, read each time the schedule runs
+    File {
+        path: String,
+    },
+}
+
+/// A scheduled script execution
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    pub script: ScheduledScript,
+    pub selector: ClientSelector,
+    pub trigger: ScheduleTrigger,
+    pub redirect: bool,
+    pub enabled: bool,
+    /// Due, but none of the selected clients are attached
+    pub waiting: bool,
+    pub next_run: Option<u64>,
+    pub last_run: Option<u64>,
+    pub run_count: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Fields for creating or updating a schedule
+#[derive(Deserialize, Debug)]
+pub struct ScheduleRequest {
+    pub name: String,
+    pub script: ScheduledScript,
+    pub selector: ClientSelector,
+    pub trigger: ScheduleTrigger,
+    #[serde(default)]
+    pub redirect: bool,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// What to search the workspace for
+#[derive(Deserialize, Debug, Clone)]
+pub struct SearchRequest {
+    pub query: String,
+    /// Treat the query as a regular expression instead of literal text
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Only match the query where it isn't part of a longer word
+    #[serde(default)]
+    pub whole_word: bool,
+    /// Lines of context around each match, 2 by default
+    pub context_lines: Option<usize>,
+    /// Matches to return before stopping, 1000 by default
+    pub max_results: Option<usize>,
+}
+
+/// A match of the query in a file
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// Relative path from the base directory
+    pub path: String,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column of the first matched character
+    pub column: usize,
+    /// Matched characters
+    pub length: usize,
+    /// The full line containing the match
+    pub text: String,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+/// Matches across the workspace
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchResults {
+    pub matches: Vec<SearchMatch>,
+    pub files_searched: usize,
+    /// Whether the search stopped at `max_results`
+    pub truncated: bool,
+}
@@ -0,0 +1,15 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Selects a set of attached clients
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientSelector {
+    /// Every attached client
+    #[default]
+    All,
+    /// Clients with the given IDs
+    Ids { ids: Vec<String> },
+    /// Clients with the given usernames (case-insensitive)
+    Usernames { usernames: Vec<String> },
+}
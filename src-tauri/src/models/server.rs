@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Backend servers managed by Proxima
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerKind {
+    /// WebSocket server for Roblox executor clients
+    Websocket,
+    /// Local HTTP API
+    Http,
+    /// WebSocket server for launcher processes
+    Launcher,
+}
+
+impl ServerKind {
+    pub const ALL: [ServerKind; 3] = [
+        ServerKind::Websocket,
+        ServerKind::Http,
+        ServerKind::Launcher,
+    ];
+}
+
+impl fmt::Display for ServerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerKind::Websocket => write!(f, "WebSocket"),
+            ServerKind::Http => write!(f, "HTTP"),
+            ServerKind::Launcher => write!(f, "Launcher WebSocket"),
+        }
+    }
+}
+
+/// How to reach the running instance, written to the discovery file for the CLI
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InstanceInfo {
+    pub pid: u32,
+    pub version: String,
+    /// Port of the local HTTP API
+    pub http_port: u16,
+}
@@ -0,0 +1,59 @@
+use super::ServerKind;
+use crate::utils::time::now_millis;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Traffic counters for a single WebSocket client
+///
+/// Messages are counted per `type` field; frames without one are counted by frame kind.
+#[derive(Serialize, Clone, Debug, Default, JsonSchema)]
+pub struct ClientTraffic {
+    pub messages_in: HashMap<String, u64>,
+    pub messages_out: HashMap<String, u64>,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    /// When the last message was received, in milliseconds since the Unix epoch
+    pub last_message_at: Option<u64>,
+}
+
+impl ClientTraffic {
+    /// Record a message received from the client
+    pub fn record_in(&mut self, kind: &str, bytes: usize) {
+        *self.messages_in.entry(kind.to_string()).or_insert(0) += 1;
+        self.bytes_in += bytes as u64;
+        self.last_message_at = Some(now_millis());
+    }
+
+    /// Record a message sent to the client
+    pub fn record_out(&mut self, kind: &str, bytes: usize) {
+        *self.messages_out.entry(kind.to_string()).or_insert(0) += 1;
+        self.bytes_out += bytes as u64;
+    }
+}
+
+/// State of a running backend server
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct ServerStatus {
+    pub server: ServerKind,
+    pub address: String,
+    pub uptime_secs: u64,
+    /// Open connections (in-flight requests for the HTTP server)
+    pub connections: usize,
+}
+
+/// State of an attached WebSocket client
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct ClientStatus {
+    pub id: String,
+    pub username: String,
+    pub missed_pings: u32,
+    pub traffic: ClientTraffic,
+}
+
+/// Snapshot of the backend returned by the status command and `GET /status`
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct BackendStatus {
+    pub servers: Vec<ServerStatus>,
+    pub clients: Vec<ClientStatus>,
+}
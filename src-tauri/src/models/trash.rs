@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// A deleted file or folder kept in the trash
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrashEntry {
+    pub id: String,
+    /// Where the item was, relative to the base directory
+    pub original_path: String,
+    pub is_folder: bool,
+    /// Milliseconds since the Unix epoch
+    pub deleted_at: u64,
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// What created a file version
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionSource {
+    /// Saved from the editor
+    Save,
+    /// Changed by another program, picked up by the file watcher
+    External,
+    /// An earlier version restored from the history
+    Restore,
+}
+
+/// A snapshot of a file's content
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FileVersion {
+    /// SHA-256 of the content, which identifies the version within its file
+    pub hash: String,
+    /// Milliseconds since the Unix epoch
+    pub saved_at: u64,
+    pub size: u64,
+    pub source: VersionSource,
+}
+
+/// How a line changed between two versions
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    Equal,
+    Added,
+    Removed,
+}
+
+/// A line of a diff, with its 1-based line number on each side it appears on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+}
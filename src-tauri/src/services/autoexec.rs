@@ -1,11 +1,148 @@
+//! Autoexec script loading and per-game rules
+//!
+//! Scripts under `autoexec/` can be restricted to particular places, games, usernames
+//! or client tags, and delayed until the game has loaded. Rules come from the
+//! `autoexec/proxima.toml` manifest:
+//!
+//! ```toml
+//! disabled = ["old/teleport.lua"]
+//!
+//! [scripts."farm.lua"]
+//! place_ids = [920587237]
+//! usernames = ["MyAlt"]
+//! wait_for_load = true
+//! delay = 5.0
+//! priority = 10
+//! after = ["lib/utils.lua"]
+//! ```
+//!
+//! or from a front-matter block comment at the top of the script itself:
+//!
+//! ```lua
+//! --[[proxima
+//! game_ids = [383310974]
+//! tags = ["farm"]
+//! ]]
+//! ```
+//!
+//! Manifest entries take precedence over front-matter.
+//!
+//! Scripts run in order of `priority` (highest first, then by path), and after any
+//! scripts listed in their `after` rule. Scripts listed in the manifest's top-level
+//! `disabled` array are skipped, and files matched by `autoexec/.proximaignore` are
+//! never loaded.
+
+use crate::models::{AutoexecEntry, AutoexecPlanEntry, AutoexecStatus};
+use crate::utils::ignore::IgnorePatterns;
 use crate::utils::paths;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
+use toml_edit::{Array, DocumentMut, Item, Value};
+
+const MANIFEST_FILE: &str = "proxima.toml";
+const FRONT_MATTER_START: &str = "--[[proxima";
+const FRONT_MATTER_END: &str = "]]";
+
+/// Restrictions and timing for an autoexec script
+///
+/// Each non-empty list must match the client; a script with no lists runs everywhere.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutoexecRules {
+    pub place_ids: Vec<u64>,
+    pub game_ids: Vec<u64>,
+    /// Case-insensitive
+    pub usernames: Vec<String>,
+    /// Matches if the client has any of these tags
+    pub tags: Vec<String>,
+    /// Seconds to wait before running
+    pub delay: Option<f64>,
+    /// Wait for `game.Loaded` before running
+    pub wait_for_load: bool,
+    /// Higher runs first; ties run in path order
+    pub priority: i32,
+    /// Paths of scripts (relative to `autoexec/`) this one runs after
+    pub after: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Manifest {
+    /// Paths of disabled scripts
+    disabled: Vec<String>,
+    scripts: HashMap<String, AutoexecRules>,
+}
+
+/// What a client reports about itself when it connects
+#[derive(Clone, Debug, Default)]
+pub struct ClientContext {
+    pub place_id: Option<u64>,
+    pub game_id: Option<u64>,
+    /// Not known until the client registers
+    pub username: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// Whether a script's rules allow it to run on a client
+#[derive(Debug, PartialEq, Eq)]
+pub enum RuleMatch {
+    Run,
+    Skip(&'static str),
+    /// Depends on the username, which the client hasn't sent yet
+    NeedsUsername,
+}
+
+impl AutoexecRules {
+    pub fn matches(&self, client: &ClientContext) -> RuleMatch {
+        let id_matches =
+            |ids: &[u64], id: Option<u64>| ids.is_empty() || id.is_some_and(|id| ids.contains(&id));
+
+        if !id_matches(&self.place_ids, client.place_id)
+            || !id_matches(&self.game_ids, client.game_id)
+        {
+            return RuleMatch::Skip("different game");
+        }
+
+        if !self.tags.is_empty() && !self.tags.iter().any(|tag| client.tags.contains(tag)) {
+            return RuleMatch::Skip("client has no matching tag");
+        }
+
+        if self.usernames.is_empty() {
+            return RuleMatch::Run;
+        }
+
+        let Some(username) = &client.username else {
+            return RuleMatch::NeedsUsername;
+        };
+
+        if self
+            .usernames
+            .iter()
+            .any(|u| u.eq_ignore_ascii_case(username))
+        {
+            RuleMatch::Run
+        } else {
+            RuleMatch::Skip("different username")
+        }
+    }
+}
 
-/// Get all autoexec scripts with their contents
-pub fn get_autoexec_scripts(app: &AppHandle) -> Vec<String> {
-    let base_dir = match paths::get_base_directory(app) {
+/// An autoexec script with its rules
+#[derive(Clone, Debug)]
+pub struct AutoexecScript {
+    /// Path relative to `autoexec/`, with forward slashes
+    pub path: String,
+    pub content: String,
+    pub enabled: bool,
+    pub rules: AutoexecRules,
+}
+
+/// Get all autoexec scripts with their contents and rules, in run order
+pub fn get_autoexec_scripts(app: &AppHandle) -> Vec<AutoexecScript> {
+    let autoexec_dir = match get_autoexec_directory(app) {
         Ok(dir) => dir,
         Err(e) => {
             log::error!("Failed to get base directory for autoexec: {}", e);
@@ -13,24 +150,240 @@ pub fn get_autoexec_scripts(app: &AppHandle) -> Vec<String> {
         }
     };
 
-    let autoexec_dir = base_dir.join("autoexec");
-
     if !autoexec_dir.exists() {
         return vec![];
     }
 
+    let ignore_patterns = IgnorePatterns::from_file(&autoexec_dir.join(".proximaignore"))
+        .unwrap_or_else(|e| {
+            log::error!("Failed to load autoexec ignore patterns: {}", e);
+            IgnorePatterns::empty()
+        });
+
     let mut scripts = Vec::new();
-    collect_scripts(&autoexec_dir, &mut scripts);
+    collect_scripts(&autoexec_dir, &autoexec_dir, &ignore_patterns, &mut scripts);
+
+    let mut manifest = load_manifest(&autoexec_dir);
+
+    let scripts = scripts
+        .into_iter()
+        .map(|(path, content)| {
+            let relative = Path::new(&path)
+                .strip_prefix(&autoexec_dir)
+                .unwrap_or(Path::new(&path))
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            let rules = manifest
+                .scripts
+                .remove(&relative)
+                .or_else(|| parse_front_matter(&relative, &content))
+                .unwrap_or_default();
+
+            AutoexecScript {
+                enabled: !manifest.disabled.contains(&relative),
+                path: relative,
+                content,
+                rules,
+            }
+        })
+        .collect();
+
+    order_scripts(scripts)
+}
+
+fn get_autoexec_directory(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(paths::get_base_directory(app)?.join("autoexec"))
+}
+
+/// Order scripts by priority then path, moving each after the scripts it runs after
+///
+/// Dependencies on scripts that don't exist are ignored. Scripts in a dependency
+/// cycle keep their priority order.
+fn order_scripts(mut scripts: Vec<AutoexecScript>) -> Vec<AutoexecScript> {
+    scripts.sort_by(|a, b| {
+        b.rules
+            .priority
+            .cmp(&a.rules.priority)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let known: HashSet<String> = scripts.iter().map(|s| s.path.clone()).collect();
+    let mut placed = HashSet::new();
+    let mut ordered = Vec::with_capacity(scripts.len());
+
+    while !scripts.is_empty() {
+        let next = scripts
+            .iter()
+            .position(|script| {
+                script
+                    .rules
+                    .after
+                    .iter()
+                    .all(|dep| placed.contains(dep) || !known.contains(dep))
+            })
+            .unwrap_or_else(|| {
+                log::warn!(
+                    "Autoexec dependency cycle involving {}, ignoring its dependencies",
+                    scripts[0].path
+                );
+                0
+            });
+
+        let script = scripts.remove(next);
+        placed.insert(script.path.clone());
+        ordered.push(script);
+    }
+
+    ordered
+}
+
+/// Decide which scripts run on a client, in run order
+///
+/// Scripts restricted by username are deferred until the client registers, along with
+/// any script that runs after a deferred one.
+pub fn plan_autoexec(
+    scripts: Vec<AutoexecScript>,
+    client: &ClientContext,
+) -> Vec<(AutoexecScript, AutoexecStatus, Option<String>)> {
+    let mut deferred: HashSet<String> = HashSet::new();
 
-    // Sort for consistent order
-    scripts.sort_by(|a, b| a.0.cmp(&b.0));
+    scripts
+        .into_iter()
+        .map(|script| {
+            if !script.enabled {
+                return (
+                    script,
+                    AutoexecStatus::Skipped,
+                    Some("disabled".to_string()),
+                );
+            }
+
+            match script.rules.matches(client) {
+                RuleMatch::Skip(reason) => {
+                    (script, AutoexecStatus::Skipped, Some(reason.to_string()))
+                }
+                RuleMatch::NeedsUsername => {
+                    deferred.insert(script.path.clone());
+                    (
+                        script,
+                        AutoexecStatus::Deferred,
+                        Some("waiting for username".to_string()),
+                    )
+                }
+                RuleMatch::Run => {
+                    match script
+                        .rules
+                        .after
+                        .iter()
+                        .find(|dep| deferred.contains(*dep))
+                    {
+                        Some(dep) => {
+                            let reason = format!("runs after {}", dep);
+                            deferred.insert(script.path.clone());
+                            (script, AutoexecStatus::Deferred, Some(reason))
+                        }
+                        None => (script, AutoexecStatus::Run, None),
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+/// List autoexec scripts in run order
+pub fn list_autoexec(app: &AppHandle) -> Vec<AutoexecEntry> {
+    get_autoexec_scripts(app)
+        .into_iter()
+        .map(|script| AutoexecEntry {
+            path: script.path,
+            enabled: script.enabled,
+            priority: script.rules.priority,
+            after: script.rules.after,
+        })
+        .collect()
+}
 
-    // Return just the contents
-    scripts.into_iter().map(|(_, content)| content).collect()
+/// Describe what would run on a client, in run order
+pub fn dry_run(app: &AppHandle, client: &ClientContext) -> Vec<AutoexecPlanEntry> {
+    plan_autoexec(get_autoexec_scripts(app), client)
+        .into_iter()
+        .map(|(script, status, reason)| AutoexecPlanEntry {
+            path: script.path,
+            status,
+            reason,
+            priority: script.rules.priority,
+            delay: script.rules.delay,
+            wait_for_load: script.rules.wait_for_load,
+        })
+        .collect()
 }
 
-/// Recursively collect script files and their contents
-fn collect_scripts(dir: &Path, scripts: &mut Vec<(String, String)>) {
+/// Enable or disable a script by updating the manifest's `disabled` array
+///
+/// Comments and formatting in the manifest are preserved.
+pub fn set_enabled(app: &AppHandle, path: &str, enabled: bool) -> Result<(), String> {
+    if !get_autoexec_scripts(app).iter().any(|s| s.path == path) {
+        return Err(format!("Autoexec script not found: {}", path));
+    }
+
+    let manifest_path = get_autoexec_directory(app)?.join(MANIFEST_FILE);
+    let content = fs::read_to_string(&manifest_path).unwrap_or_default();
+    let mut document = content
+        .parse::<DocumentMut>()
+        .map_err(|e| format!("Failed to parse {}: {}", MANIFEST_FILE, e))?;
+
+    let disabled = document
+        .entry("disabled")
+        .or_insert(Item::Value(Value::Array(Array::new())))
+        .as_array_mut()
+        .ok_or_else(|| format!("\"disabled\" in {} must be an array", MANIFEST_FILE))?;
+
+    disabled.retain(|value| value.as_str() != Some(path));
+    if !enabled {
+        disabled.push(path);
+    }
+
+    fs::write(&manifest_path, document.to_string())
+        .map_err(|e| format!("Failed to write {}: {}", MANIFEST_FILE, e))
+}
+
+/// Load `autoexec/proxima.toml`, treating a missing or invalid manifest as empty
+fn load_manifest(autoexec_dir: &Path) -> Manifest {
+    let manifest_path = autoexec_dir.join(MANIFEST_FILE);
+
+    let content = match fs::read_to_string(&manifest_path) {
+        Ok(content) => content,
+        Err(_) => return Manifest::default(),
+    };
+
+    toml::from_str(&content).unwrap_or_else(|e| {
+        log::error!("Failed to parse {}: {}", MANIFEST_FILE, e);
+        Manifest::default()
+    })
+}
+
+/// Parse rules from a `--[[proxima ... ]]` block at the top of a script
+fn parse_front_matter(path: &str, content: &str) -> Option<AutoexecRules> {
+    let body = content.trim_start().strip_prefix(FRONT_MATTER_START)?;
+    let end = body.find(FRONT_MATTER_END)?;
+
+    match toml::from_str(&body[..end]) {
+        Ok(rules) => Some(rules),
+        Err(e) => {
+            log::error!("Failed to parse autoexec rules in {}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Recursively collect script files and their contents, skipping ignored paths
+fn collect_scripts(
+    root: &Path,
+    dir: &Path,
+    ignore_patterns: &IgnorePatterns,
+    scripts: &mut Vec<(String, String)>,
+) {
     let entries = match fs::read_dir(dir) {
         Ok(entries) => entries,
         Err(e) => {
@@ -41,10 +394,19 @@ fn collect_scripts(dir: &Path, scripts: &mut Vec<(String, String)>) {
 
     for entry in entries.filter_map(|e| e.ok()) {
         let path = entry.path();
+        let is_dir = path.is_dir();
 
-        if path.is_dir() {
+        // Match patterns against the entry name, as the file tree does, and the full relative path
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        if ignore_patterns.is_ignored(Path::new(&entry.file_name()), is_dir)
+            || ignore_patterns.is_ignored(relative, is_dir)
+        {
+            continue;
+        }
+
+        if is_dir {
             // Recursively collect from subdirectories
-            collect_scripts(&path, scripts);
+            collect_scripts(root, &path, ignore_patterns, scripts);
         } else if path.is_file() {
             // Check if file has a valid extension
             if let Some(extension) = path.extension() {
@@ -65,3 +427,52 @@ fn collect_scripts(dir: &Path, scripts: &mut Vec<(String, String)>) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_front_matter_rules() {
+        let script = "--[[proxima\nplace_ids = [1, 2]\nusernames = [\"Alt\"]\n]]\nprint('hi')";
+        let rules = parse_front_matter("farm.lua", script).unwrap();
+        assert_eq!(rules.place_ids, vec![1, 2]);
+
+        let mut client = ClientContext {
+            place_id: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(rules.matches(&client), RuleMatch::NeedsUsername);
+
+        client.username = Some("alt".to_string());
+        assert_eq!(rules.matches(&client), RuleMatch::Run);
+
+        client.place_id = Some(3);
+        assert_eq!(rules.matches(&client), RuleMatch::Skip("different game"));
+
+        assert!(parse_front_matter("plain.lua", "print('hi')").is_none());
+    }
+
+    #[test]
+    fn test_script_order() {
+        let script = |path: &str, priority: i32, after: &[&str]| AutoexecScript {
+            path: path.to_string(),
+            content: String::new(),
+            enabled: true,
+            rules: AutoexecRules {
+                priority,
+                after: after.iter().map(|s| s.to_string()).collect(),
+                ..Default::default()
+            },
+        };
+
+        let ordered = order_scripts(vec![
+            script("a.lua", 0, &["c.lua"]),
+            script("b.lua", 0, &["missing.lua"]),
+            script("c.lua", 0, &[]),
+            script("d.lua", 10, &[]),
+        ]);
+        let paths: Vec<&str> = ordered.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(paths, vec!["d.lua", "b.lua", "c.lua", "a.lua"]);
+    }
+}
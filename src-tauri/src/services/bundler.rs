@@ -0,0 +1,462 @@
+//! Local script bundler
+//!
+//! Resolves local module references in a script and bundles them into one chunk:
+//!
+//! - `require("./util")` loads a module relative to the requiring file, returning its
+//!   result (modules run once and are cached, like `require`)
+//! - `--#include "lib/esp.lua"` on its own line runs a file in place, relative to
+//!   `scripts/` (or to the including file when the path starts with `./` or `../`)
+//!
+//! References are found in full-moon's token stream, so `require` in strings and
+//! comments is left alone. Modules must be inside a workspace root.
+//!
+//! Each module is wrapped in its own function, and references are rewritten on the
+//! same line, so every source line keeps a single bundle line. The returned line map
+//! lets clients trace runtime errors back to the original file and line.
+
+use crate::models::{LineMapping, ScriptBundle};
+use full_moon::tokenizer::{Lexer, LexerResult, Symbol, Token, TokenType};
+use full_moon::LuaVersion;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use super::workspace::Workspace;
+
+/// Text of an include comment after the leading `--`
+static INCLUDE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^#include\s+["']([^"'\n]+)["']\s*$"#).expect("valid include regex"));
+
+// Extensions tried, in order, when a reference doesn't name a file directly
+const MODULE_SUFFIXES: [&str; 4] = [".lua", ".luau", "/init.lua", "/init.luau"];
+
+// Requiring a module while it is still loading raises an error instead of recursing
+const BUNDLE_PRELUDE: [&str; 2] = [
+    "local __proxima_modules, __proxima_cache, __proxima_loading = {}, {}, {}",
+    "local function __proxima_require(name) local cached = __proxima_cache[name] if cached then return cached[1] end if __proxima_loading[name] then error(\"cyclic require: \" .. name, 2) end __proxima_loading[name] = true local ok, result = pcall(__proxima_modules[name]) __proxima_loading[name] = nil if not ok then error(result, 0) end __proxima_cache[name] = { result } return result end",
+];
+
+/// Bundle a script's local module references
+///
+/// # Arguments
+/// * `script` - The entry script
+/// * `path` - Path of the entry script relative to the base directory, if it is a file;
+///   `./` references in an unsaved script resolve against `scripts/`
+pub fn bundle_script(
+    app: &AppHandle,
+    script: &str,
+    path: Option<&str>,
+) -> Result<ScriptBundle, String> {
+    let workspace = Workspace::load(app).map_err(|e| e.to_string())?;
+    Bundler::new(workspace)?.bundle(script, path)
+}
+
+struct Module {
+    name: String,
+    source: String,
+}
+
+struct Bundler {
+    workspace: Workspace,
+    scripts_dir: PathBuf,
+    /// Canonical file path to module name
+    names: HashMap<PathBuf, String>,
+    modules: Vec<Module>,
+}
+
+impl Bundler {
+    fn new(workspace: Workspace) -> Result<Self, String> {
+        let scripts_dir = workspace
+            .resolve_existing("scripts")
+            .map_err(|e| format!("Failed to resolve scripts directory: {}", e))?;
+
+        Ok(Self {
+            workspace,
+            scripts_dir,
+            names: HashMap::new(),
+            modules: Vec::new(),
+        })
+    }
+
+    fn bundle(mut self, script: &str, path: Option<&str>) -> Result<ScriptBundle, String> {
+        let entry_dir = match path {
+            Some(path) => {
+                let (folder, _) = path.rsplit_once('/').unwrap_or(("", path));
+                self.workspace
+                    .resolve_existing(folder)
+                    .map_err(|e| format!("Failed to resolve script directory: {}", e))?
+            }
+            None => self.scripts_dir.clone(),
+        };
+        let entry_name = path.unwrap_or("main").to_string();
+
+        let entry = self.rewrite(script, &entry_dir)?;
+
+        if self.modules.is_empty() {
+            return Ok(ScriptBundle {
+                script: script.to_string(),
+                line_map: Vec::new(),
+            });
+        }
+
+        let mut lines: Vec<String> = BUNDLE_PRELUDE.iter().map(|s| s.to_string()).collect();
+        let mut line_map = Vec::new();
+
+        for module in &self.modules {
+            lines.push(format!(
+                "__proxima_modules[{:?}] = function(...)",
+                module.name
+            ));
+            push_source(&mut lines, &mut line_map, &module.source, &module.name);
+            lines.push("end".to_string());
+        }
+
+        // The entry runs at the top level, so it keeps the chunk's `...`
+        push_source(&mut lines, &mut line_map, &entry, &entry_name);
+
+        Ok(ScriptBundle {
+            script: lines.join("\n"),
+            line_map,
+        })
+    }
+
+    /// Rewrite local references in a source file, bundling the modules they point to
+    fn rewrite(&mut self, source: &str, dir: &Path) -> Result<String, String> {
+        let mut rewritten = String::with_capacity(source.len());
+        let mut copied = 0;
+
+        for reference in find_references(source)? {
+            let name = self.add_module(&reference.path, dir, reference.is_include)?;
+            let replaced = &source[reference.start..reference.end];
+
+            rewritten.push_str(&source[copied..reference.start]);
+            if reference.is_include {
+                rewritten.push_str(&format!("__proxima_modules[{:?}]()", name));
+            } else {
+                rewritten.push_str(&format!("__proxima_require({:?})", name));
+            }
+            // Keep any line breaks inside a call so later lines don't shift
+            rewritten.extend(replaced.chars().filter(|&c| c == '\n'));
+            copied = reference.end;
+        }
+
+        rewritten.push_str(&source[copied..]);
+        Ok(rewritten)
+    }
+
+    /// Resolve a reference and bundle the module if it hasn't been already
+    ///
+    /// # Returns
+    /// * `Ok(String)` - The module's name in the bundle
+    /// * `Err(String)` - If the module can't be found, read or is outside the workspace
+    fn add_module(
+        &mut self,
+        reference: &str,
+        dir: &Path,
+        is_include: bool,
+    ) -> Result<String, String> {
+        let relative = reference.starts_with("./") || reference.starts_with("../");
+        let search_dir = if relative || !is_include {
+            dir
+        } else {
+            self.scripts_dir.as_path()
+        };
+
+        let file_path = self.resolve(reference, search_dir)?;
+
+        if let Some(name) = self.names.get(&file_path) {
+            return Ok(name.clone());
+        }
+
+        let name = self.workspace.relative(&file_path);
+
+        // Register before rewriting so a cycle refers back to this module instead of
+        // bundling it again; the prelude rejects the cyclic require when it runs
+        self.names.insert(file_path.clone(), name.clone());
+
+        let source = fs::read_to_string(&file_path)
+            .map_err(|e| format!("Failed to read module {}: {}", name, e))?;
+        let module_dir = file_path
+            .parent()
+            .unwrap_or(self.workspace.base_dir())
+            .to_path_buf();
+        let source = self.rewrite(&source, &module_dir)?;
+
+        self.modules.push(Module {
+            name: name.clone(),
+            source,
+        });
+
+        Ok(name)
+    }
+
+    fn resolve(&self, reference: &str, dir: &Path) -> Result<PathBuf, String> {
+        let candidate = dir.join(reference);
+
+        let file_path = std::iter::once(candidate.clone())
+            .chain(
+                MODULE_SUFFIXES
+                    .iter()
+                    .map(|suffix| PathBuf::from(format!("{}{}", candidate.display(), suffix))),
+            )
+            .find(|path| path.is_file())
+            .ok_or_else(|| format!("Module not found: {}", reference))?;
+
+        self.workspace
+            .resolve_path(&file_path, reference)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// A `require` call or `--#include` directive, as a byte range of the source
+struct Reference {
+    start: usize,
+    end: usize,
+    path: String,
+    is_include: bool,
+}
+
+/// Find local references in a source file's tokens
+///
+/// `require` must be called with a single string starting with `./` or `../`, either
+/// as `require("./x")` or `require "./x"`. Method and field calls such as
+/// `loader.require("./x")` are not references.
+fn find_references(source: &str) -> Result<Vec<Reference>, String> {
+    let tokens = match Lexer::new(source, LuaVersion::luau()).collect() {
+        LexerResult::Ok(tokens) | LexerResult::Recovered(tokens, _) => tokens,
+        LexerResult::Fatal(errors) => {
+            let message = errors
+                .first()
+                .map(|error| error.to_string())
+                .unwrap_or_default();
+            return Err(format!("Failed to tokenize script: {}", message));
+        }
+    };
+
+    let code: Vec<&Token> = tokens
+        .iter()
+        .filter(|token| !token.token_type().is_trivia())
+        .collect();
+    let mut references = Vec::new();
+
+    for token in &tokens {
+        let TokenType::SingleLineComment { comment } = token.token_type() else {
+            continue;
+        };
+        let start = token.start_position().bytes();
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        if !source[line_start..start].trim().is_empty() {
+            continue;
+        }
+
+        if let Some(captures) = INCLUDE_PATTERN.captures(comment.as_str()) {
+            references.push(Reference {
+                start,
+                end: token.end_position().bytes(),
+                path: captures[1].to_string(),
+                is_include: true,
+            });
+        }
+    }
+
+    for (index, token) in code.iter().enumerate() {
+        let is_require = matches!(
+            token.token_type(),
+            TokenType::Identifier { identifier } if identifier.as_str() == "require"
+        );
+        let is_member = index > 0
+            && (is_symbol(code[index - 1], Symbol::Dot)
+                || is_symbol(code[index - 1], Symbol::Colon));
+        if !is_require || is_member {
+            continue;
+        }
+
+        let rest = &code[index + 1..];
+        let (literal, last) = match rest {
+            [open, literal, close, ..]
+                if is_symbol(open, Symbol::LeftParen) && is_symbol(close, Symbol::RightParen) =>
+            {
+                (*literal, *close)
+            }
+            [literal, ..] => (*literal, *literal),
+            [] => continue,
+        };
+        let TokenType::StringLiteral {
+            literal: path,
+            multi_line_depth: 0,
+            ..
+        } = literal.token_type()
+        else {
+            continue;
+        };
+
+        if path.starts_with("./") || path.starts_with("../") {
+            references.push(Reference {
+                start: token.start_position().bytes(),
+                end: last.end_position().bytes(),
+                path: path.to_string(),
+                is_include: false,
+            });
+        }
+    }
+
+    references.sort_by_key(|reference| reference.start);
+    Ok(references)
+}
+
+fn is_symbol(token: &Token, symbol: Symbol) -> bool {
+    matches!(token.token_type(), TokenType::Symbol { symbol: s } if *s == symbol)
+}
+
+/// Append a source file's lines to the bundle and map them back to the file
+fn push_source(lines: &mut Vec<String>, line_map: &mut Vec<LineMapping>, source: &str, path: &str) {
+    let start = lines.len() as u32 + 1;
+    lines.extend(source.lines().map(str::to_string));
+    let end = lines.len() as u32;
+
+    if end >= start {
+        line_map.push(LineMapping {
+            start,
+            end,
+            path: path.to_string(),
+            line: 1,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::diagnostics;
+    use tempfile::TempDir;
+
+    /// A temporary base directory with the given files, and its canonical path
+    fn base_dir(files: &[(&str, &str)]) -> (TempDir, PathBuf) {
+        let temp = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let path = temp.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let base = temp.path().canonicalize().unwrap();
+        (temp, base)
+    }
+
+    fn bundle_in(base: &Path, script: &str) -> Result<ScriptBundle, String> {
+        let workspace = Workspace::new(base, ["scripts".to_string()]).unwrap();
+        Bundler::new(workspace)?.bundle(script, None)
+    }
+
+    #[test]
+    fn test_bundle() {
+        let (_temp, base) = base_dir(&[
+            (
+                "scripts/util.lua",
+                "local inner = require(\"./lib/inner\")\nreturn { value = inner }",
+            ),
+            ("scripts/lib/inner.lua", "return 1"),
+            ("scripts/pkg/init.lua", "return \"pkg\""),
+            ("scripts/esp.lua", "print(\"esp\")"),
+        ]);
+        let script = [
+            "local util = require(\"./util\")",
+            "local again = require './util'",
+            "local pkg = require(\"./pkg\")",
+            "  --#include \"esp.lua\"",
+            "print(\"require('./missing')\") -- require(\"./missing\")",
+        ]
+        .join("\n");
+
+        let bundle = bundle_in(&base, &script).unwrap();
+        let lines: Vec<&str> = bundle.script.lines().collect();
+
+        // Nested modules are bundled before the module requiring them, and each only once
+        assert_eq!(
+            lines[2],
+            "__proxima_modules[\"scripts/lib/inner.lua\"] = function(...)"
+        );
+        assert_eq!(
+            lines[6],
+            "local inner = __proxima_require(\"scripts/lib/inner.lua\")"
+        );
+        assert_eq!(
+            lines[9],
+            "__proxima_modules[\"scripts/pkg/init.lua\"] = function(...)"
+        );
+        assert_eq!(
+            lines[15],
+            "local util = __proxima_require(\"scripts/util.lua\")"
+        );
+        assert_eq!(
+            lines[16],
+            "local again = __proxima_require(\"scripts/util.lua\")"
+        );
+        assert_eq!(lines[18], "  __proxima_modules[\"scripts/esp.lua\"]()");
+        assert_eq!(lines[19], script.lines().last().unwrap());
+        assert_eq!(lines.len(), 20);
+
+        let ranges: Vec<_> = bundle
+            .line_map
+            .iter()
+            .map(|mapping| {
+                (
+                    mapping.start,
+                    mapping.end,
+                    mapping.path.as_str(),
+                    mapping.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                (4, 4, "scripts/lib/inner.lua", 1),
+                (7, 8, "scripts/util.lua", 1),
+                (11, 11, "scripts/pkg/init.lua", 1),
+                (14, 14, "scripts/esp.lua", 1),
+                (16, 20, "main", 1),
+            ]
+        );
+
+        // References in comments aren't followed
+        let commented = bundle_in(
+            &base,
+            "print(require(\"./util\").value)\n--require(\"./x\")",
+        );
+        assert_eq!(commented.unwrap().line_map.len(), 3);
+
+        // Without local references the script is unchanged
+        let plain = bundle_in(&base, "loader.require(\"./util\")").unwrap();
+        assert_eq!(plain.script, "loader.require(\"./util\")");
+        assert!(plain.line_map.is_empty());
+    }
+
+    #[test]
+    fn test_bundle_cycles_and_errors() {
+        let (_temp, base) = base_dir(&[
+            ("scripts/a.lua", "local b = require(\"./b\")\nreturn 1"),
+            ("scripts/b.lua", "return require(\"./a\")"),
+            ("private.lua", "return \"secret\""),
+        ]);
+
+        // A call split over lines keeps its line breaks
+        let bundle = bundle_in(&base, "local a = require(\n  \"./a\"\n)\nprint(a)").unwrap();
+        let lines: Vec<&str> = bundle.script.lines().collect();
+        assert!(lines[1].contains("error(\"cyclic require: \" .. name, 2)"));
+        assert_eq!(diagnostics::check_syntax(&bundle.script), Ok(()));
+        assert_eq!(lines[3], "return __proxima_require(\"scripts/a.lua\")");
+        assert_eq!(lines[6], "local b = __proxima_require(\"scripts/b.lua\")");
+        assert_eq!(lines[9], "local a = __proxima_require(\"scripts/a.lua\")");
+        assert_eq!(lines[12], "print(a)");
+        assert_eq!(bundle.line_map.last().unwrap().start, 10);
+        assert_eq!(bundle.line_map.last().unwrap().end, 13);
+
+        let outside = bundle_in(&base, "require(\"../private\")").unwrap_err();
+        assert!(outside.contains("outside the workspace"), "{}", outside);
+        let missing = bundle_in(&base, "require(\"./missing\")").unwrap_err();
+        assert!(missing.contains("Module not found"), "{}", missing);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::models::ClientSelector;
+use serde_json::Value;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: proxima <command> [options]
+
+Commands:
+  exec <file>       Run a script file on attached clients and print its output
+                    (needs HTTP Request Execution enabled in Proxima's settings)
+      --to <target>     all (default), id:<id>[,<id>...] or <username>[,<username>...]
+      --args <json>     Passed to the script as `...`; an array is passed as separate values
+      --no-redirect     Don't redirect print/warn/error back to Proxima
+      --check           Refuse scripts that don't parse
+      --detach          Print the execution ID instead of waiting for the script to end
+  clients           List attached clients
+  logs --follow     Print log messages as they arrive
+  logs <id>         Print the output of an execution
+      --client <c>      Only messages from this client, by ID or username
+  status            Show running servers and attached clients
+
+Options:
+  --port <port>     HTTP API port, instead of reading it from the running instance
+  --json            Print `clients` and `status` as JSON";
+
+/// Subcommands that are handled by the CLI instead of starting the app
+const COMMANDS: [&str; 5] = ["exec", "clients", "logs", "status", "help"];
+
+/// Parsed command line of a CLI subcommand
+#[derive(Debug, PartialEq)]
+pub struct Cli {
+    /// Port override for the HTTP API
+    pub port: Option<u16>,
+    pub command: Command,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Exec(ExecOptions),
+    Clients { json: bool },
+    Logs(LogsOptions),
+    Status { json: bool },
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ExecOptions {
+    pub file: PathBuf,
+    pub target: ClientSelector,
+    pub args: Option<Value>,
+    /// Unset to use the execution setting
+    pub redirect: Option<bool>,
+    pub check: bool,
+    pub detach: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LogsOptions {
+    /// Execution to print the output of; unset to follow live log messages
+    pub execution_id: Option<String>,
+    pub client: Option<String>,
+}
+
+/// Check whether the arguments start with a CLI subcommand
+pub fn is_cli_command(args: &[String]) -> bool {
+    args.get(1)
+        .is_some_and(|command| COMMANDS.contains(&command.as_str()))
+}
+
+/// Parse a CLI subcommand and its options
+///
+/// # Arguments
+/// * `args` - Every argument, including the executable
+///
+/// # Returns
+/// * `Ok(Cli)` - The parsed command
+/// * `Err(String)` - Why the arguments are invalid
+pub fn parse(args: &[String]) -> Result<Cli, String> {
+    let command = args.get(1).map(String::as_str).unwrap_or("help");
+
+    let mut port = None;
+    let mut json = false;
+    let mut positional = Vec::new();
+    let mut to = None;
+    let mut script_args = None;
+    let mut redirect = None;
+    let mut check = false;
+    let mut detach = false;
+    let mut follow = false;
+    let mut client = None;
+
+    let mut rest = args.iter().skip(2);
+    while let Some(arg) = rest.next() {
+        let mut value = |flag: &str| {
+            rest.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", flag))
+        };
+
+        match arg.as_str() {
+            "--port" => {
+                let value = value(arg)?;
+                port = Some(
+                    value
+                        .parse::<u16>()
+                        .map_err(|_| format!("Invalid port: {}", value))?,
+                );
+            }
+            "--json" => json = true,
+            "--to" => to = Some(value(arg)?),
+            "--args" => {
+                let value = value(arg)?;
+                script_args = Some(
+                    serde_json::from_str(&value)
+                        .map_err(|e| format!("Invalid --args JSON: {}", e))?,
+                );
+            }
+            "--no-redirect" => redirect = Some(false),
+            "--check" => check = true,
+            "--detach" => detach = true,
+            "--follow" | "-f" => follow = true,
+            "--client" => client = Some(value(arg)?),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    let command = match command {
+        "exec" => {
+            let [file] = positional.as_slice() else {
+                return Err("exec needs exactly one script file".to_string());
+            };
+            Command::Exec(ExecOptions {
+                file: PathBuf::from(file),
+                target: to.as_deref().map(parse_selector).unwrap_or_default(),
+                args: script_args,
+                redirect,
+                check,
+                detach,
+            })
+        }
+        "clients" => Command::Clients { json },
+        "logs" => {
+            let execution_id = match (positional.as_slice(), follow) {
+                ([], true) => None,
+                ([id], false) => Some(id.clone()),
+                _ => return Err("logs needs either --follow or an execution ID".to_string()),
+            };
+            Command::Logs(LogsOptions {
+                execution_id,
+                client,
+            })
+        }
+        "status" => Command::Status { json },
+        _ => Command::Help,
+    };
+
+    Ok(Cli { port, command })
+}
+
+/// Parse an `exec --to` target
+///
+/// `all`, `id:` followed by comma-separated client IDs, or comma-separated usernames.
+fn parse_selector(target: &str) -> ClientSelector {
+    let list = |values: &str| {
+        values
+            .split(',')
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect()
+    };
+
+    if target.eq_ignore_ascii_case("all") {
+        ClientSelector::All
+    } else if let Some(ids) = target.strip_prefix("id:") {
+        ClientSelector::Ids { ids: list(ids) }
+    } else {
+        ClientSelector::Usernames {
+            usernames: list(target),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        std::iter::once("proxima")
+            .chain(line.split_whitespace())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_exec() {
+        let cli = parse(&args("exec main.lua --to alice,bob --port 14000 --check")).unwrap();
+        assert_eq!(cli.port, Some(14000));
+        assert_eq!(
+            cli.command,
+            Command::Exec(ExecOptions {
+                file: PathBuf::from("main.lua"),
+                target: ClientSelector::Usernames {
+                    usernames: vec!["alice".to_string(), "bob".to_string()],
+                },
+                args: None,
+                redirect: None,
+                check: true,
+                detach: false,
+            })
+        );
+
+        let Command::Exec(exec) = parse(&args("exec main.lua --to id:1")).unwrap().command else {
+            panic!("expected exec");
+        };
+        assert_eq!(
+            exec.target,
+            ClientSelector::Ids {
+                ids: vec!["1".to_string()]
+            }
+        );
+
+        assert!(parse(&args("exec")).is_err());
+        assert!(parse(&args("exec main.lua --bogus")).is_err());
+    }
+
+    #[test]
+    fn test_parse_logs() {
+        let cli = parse(&args("logs -f --client alice")).unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Logs(LogsOptions {
+                execution_id: None,
+                client: Some("alice".to_string()),
+            })
+        );
+
+        assert!(parse(&args("logs")).is_err());
+        assert!(is_cli_command(&args("status")));
+        assert!(!is_cli_command(&args("--launch")));
+    }
+}
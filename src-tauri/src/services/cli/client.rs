@@ -0,0 +1,99 @@
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::time::Duration;
+
+use super::CliError;
+use crate::services::discovery;
+
+/// How long a request may take, except for streamed responses
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Client for the running instance's HTTP API
+pub struct ApiClient {
+    http: Client,
+    base_url: String,
+}
+
+impl ApiClient {
+    /// Connect to the running instance, on `port` or the one in its discovery file
+    pub fn new(port: Option<u16>) -> Result<Self, CliError> {
+        let port = match port {
+            Some(port) => port,
+            None => discovery::read().map_err(CliError::NotRunning)?.http_port,
+        };
+
+        // No client-wide timeout so streamed responses can stay open
+        let http = Client::builder()
+            .timeout(None)
+            .build()
+            .map_err(|e| CliError::Failed(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http,
+            base_url: format!("http://127.0.0.1:{}", port),
+        })
+    }
+
+    /// GET a JSON response
+    pub fn get(&self, path: &str) -> Result<Value, CliError> {
+        let request = self.http.get(self.url(path)).timeout(REQUEST_TIMEOUT);
+        json(send(request)?)
+    }
+
+    /// GET a response that stays open, such as an event stream
+    pub fn get_stream(&self, path: &str, query: &[(&str, &str)]) -> Result<Response, CliError> {
+        send(self.http.get(self.url(path)).query(query))
+    }
+
+    /// POST a JSON body and return the response, which may be streamed
+    pub fn post(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        body: &Value,
+    ) -> Result<Response, CliError> {
+        send(self.http.post(self.url(path)).query(query).json(body))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
+
+/// Send a request, turning failed statuses into errors with the API's message
+fn send(request: RequestBuilder) -> Result<Response, CliError> {
+    let response = request.send().map_err(|e| {
+        if e.is_connect() {
+            CliError::NotRunning("Proxima is not running (connection refused)".to_string())
+        } else {
+            CliError::Failed(format!("Request failed: {}", e))
+        }
+    })?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let error = response
+        .json::<Value>()
+        .ok()
+        .and_then(|body| body["error"].as_str().map(String::from))
+        .unwrap_or_else(|| status.to_string());
+
+    if status == StatusCode::FORBIDDEN {
+        Err(CliError::Forbidden(error))
+    } else if status.is_client_error() {
+        Err(CliError::Rejected(error))
+    } else {
+        Err(CliError::Failed(error))
+    }
+}
+
+/// Read a JSON response body
+pub fn json(response: Response) -> Result<Value, CliError> {
+    response
+        .json()
+        .map_err(|e| CliError::Failed(format!("Invalid response: {}", e)))
+}
@@ -0,0 +1,263 @@
+// CLI subcommands
+// Each talks to the running instance through its local HTTP API
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+
+use super::args::{ExecOptions, LogsOptions};
+use super::{self as cli, client, ApiClient, CliError};
+
+/// Run a script file and print its output until it has ended on every client
+pub(super) fn exec(api: &ApiClient, options: ExecOptions) -> Result<i32, CliError> {
+    let script = std::fs::read_to_string(&options.file).map_err(|e| {
+        CliError::Usage(format!("Failed to read {}: {}", options.file.display(), e))
+    })?;
+
+    let mut body = json!({
+        "script": script,
+        "target": options.target,
+        "check": options.check,
+    });
+    if let Some(redirect) = options.redirect {
+        body["redirect"] = json!(redirect);
+    }
+    if let Some(args) = options.args {
+        body["args"] = args;
+    }
+
+    if options.detach {
+        let response = client::json(
+            api.post("/v1/execute", &[], &body)
+                .map_err(execution_hint)?,
+        )?;
+        println!("{}", response["execution_id"].as_str().unwrap_or_default());
+        return Ok(cli::EXIT_SUCCESS);
+    }
+
+    let response = api
+        .post("/v1/execute", &[("stream", "true")], &body)
+        .map_err(execution_hint)?;
+
+    let mut execution_id = None;
+    let mut prefix_usernames = false;
+    for line in BufReader::new(response).lines() {
+        let line = line.map_err(|e| CliError::Failed(format!("Stream interrupted: {}", e)))?;
+        let Ok(event) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+
+        match event["type"].as_str().unwrap_or_default() {
+            "started" => {
+                execution_id = event["execution_id"].as_str().map(String::from);
+                prefix_usernames = event["client_ids"]
+                    .as_array()
+                    .is_some_and(|ids| ids.len() > 1);
+            }
+            "output" => print_output(&event, prefix_usernames),
+            "lagged" => eprintln!("warning: {} output line(s) were skipped", event["skipped"]),
+            "error" => {
+                return Err(CliError::Failed(
+                    event["error"]
+                        .as_str()
+                        .unwrap_or("Execution failed")
+                        .to_string(),
+                ))
+            }
+            "end" => break,
+            _ => {}
+        }
+    }
+
+    let Some(execution_id) = execution_id else {
+        return Err(CliError::Failed(
+            "Script did not reach any client".to_string(),
+        ));
+    };
+
+    // Statuses come from the execution so they include the username of each client
+    let details = api.get(&format!("/v1/executions/{}", execution_id))?;
+    let mut code = cli::EXIT_SUCCESS;
+    for client in details["clients"].as_array().into_iter().flatten() {
+        let status = client["status"].as_str().unwrap_or_default();
+        if status == "finished" {
+            continue;
+        }
+
+        code = cli::EXIT_FAILED;
+        match client["error"].as_str() {
+            Some(error) => eprintln!(
+                "{}: {}: {}",
+                client["username"].as_str().unwrap_or_default(),
+                status,
+                error
+            ),
+            None => eprintln!(
+                "{}: {}",
+                client["username"].as_str().unwrap_or_default(),
+                status
+            ),
+        }
+    }
+
+    Ok(code)
+}
+
+/// Point at the setting to change when the instance refuses to run scripts
+fn execution_hint(error: CliError) -> CliError {
+    match error {
+        CliError::Forbidden(message) => CliError::Forbidden(format!(
+            "{}\nhint: enable HTTP Request Execution in Proxima's settings \
+             (execution.httpRequestExecution)",
+            message
+        )),
+        error => error,
+    }
+}
+
+/// List attached clients
+pub(super) fn clients(api: &ApiClient, json: bool) -> Result<i32, CliError> {
+    let clients = api.get("/v1/clients")?;
+    if json {
+        println!("{}", clients);
+        return Ok(cli::EXIT_SUCCESS);
+    }
+
+    let clients = clients.as_array().cloned().unwrap_or_default();
+    if clients.is_empty() {
+        println!("No attached clients");
+    }
+    for client in clients {
+        let tags = client["tags"]
+            .as_array()
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .unwrap_or_default();
+
+        println!(
+            "{:<20} {:<38} place {:<14} {}",
+            client["username"].as_str().unwrap_or_default(),
+            client["id"].as_str().unwrap_or_default(),
+            client["place_id"]
+                .as_u64()
+                .map_or_else(|| "-".to_string(), |id| id.to_string()),
+            tags
+        );
+    }
+
+    Ok(cli::EXIT_SUCCESS)
+}
+
+/// Print an execution's output, or follow log messages
+pub(super) fn logs(api: &ApiClient, options: LogsOptions) -> Result<i32, CliError> {
+    let Some(execution_id) = options.execution_id else {
+        return follow_logs(api, options.client);
+    };
+
+    let details = api.get(&format!("/v1/executions/{}", execution_id))?;
+    let client = options.client.as_deref();
+    for line in details["output"].as_array().into_iter().flatten() {
+        let matches = client.map_or(true, |client| {
+            line["client_id"].as_str() == Some(client)
+                || line["username"]
+                    .as_str()
+                    .is_some_and(|username| username.eq_ignore_ascii_case(client))
+        });
+        if matches {
+            print_output(line, client.is_none());
+        }
+    }
+
+    Ok(cli::EXIT_SUCCESS)
+}
+
+/// Print log messages as they arrive, until the instance closes the stream
+fn follow_logs(api: &ApiClient, client: Option<String>) -> Result<i32, CliError> {
+    let mut query = vec![("events", "log-message")];
+    if let Some(client) = &client {
+        query.push(("client", client));
+    }
+
+    let response = api.get_stream("/v1/events", &query)?;
+    for line in BufReader::new(response).lines() {
+        let line = line.map_err(|e| CliError::Failed(format!("Stream interrupted: {}", e)))?;
+        let Some(data) = line.strip_prefix("data:") else {
+            continue;
+        };
+        let Ok(event) = serde_json::from_str::<Value>(data.trim()) else {
+            continue;
+        };
+        if event["event"] == "log-message" {
+            print_message(&event["payload"], None);
+        }
+    }
+
+    Ok(cli::EXIT_SUCCESS)
+}
+
+/// Show running servers and attached clients
+pub(super) fn status(api: &ApiClient, json: bool) -> Result<i32, CliError> {
+    let status = api.get("/v1/status")?;
+    if json {
+        println!("{}", status);
+        return Ok(cli::EXIT_SUCCESS);
+    }
+
+    println!("Servers:");
+    for server in status["servers"].as_array().into_iter().flatten() {
+        println!(
+            "  {:<10} {:<22} up {}s, {} connection(s)",
+            server["server"].as_str().unwrap_or_default(),
+            server["address"].as_str().unwrap_or_default(),
+            server["uptime_secs"],
+            server["connections"]
+        );
+    }
+
+    println!("Clients:");
+    let clients = status["clients"].as_array().cloned().unwrap_or_default();
+    if clients.is_empty() {
+        println!("  none");
+    }
+    for client in clients {
+        println!(
+            "  {:<20} {} message(s) in, {} out",
+            client["username"].as_str().unwrap_or_default(),
+            count_messages(&client["traffic"]["messages_in"]),
+            count_messages(&client["traffic"]["messages_out"])
+        );
+    }
+
+    Ok(cli::EXIT_SUCCESS)
+}
+
+fn count_messages(counts: &Value) -> u64 {
+    serde_json::from_value::<HashMap<String, u64>>(counts.clone())
+        .map(|counts| counts.values().sum())
+        .unwrap_or(0)
+}
+
+/// Print a line of redirected output
+fn print_output(line: &Value, prefix_username: bool) {
+    let username = line["username"].as_str().filter(|_| prefix_username);
+    print_message(line, username);
+}
+
+/// Print a log message, sending warnings and errors to stderr
+fn print_message(message: &Value, username: Option<&str>) {
+    let text = message["message"].as_str().unwrap_or_default();
+    let text = match username {
+        Some(username) => format!("[{}] {}", username, text),
+        None => text.to_string(),
+    };
+
+    // Log levels, as in `LogMessage`: 2 is a warning and 3 an error
+    match message["level"].as_u64() {
+        Some(2) | Some(3) => eprintln!("{}", text),
+        _ => println!("{}", text),
+    }
+}
@@ -0,0 +1,99 @@
+// CLI services module
+//
+// Runs the `proxima <command>` subcommands:
+// - Args: Subcommand and option parsing
+// - Client: Blocking client for the running instance's HTTP API
+// - Commands: The subcommands themselves
+
+pub mod args;
+pub mod client;
+mod commands;
+
+pub use args::{is_cli_command, Cli, Command};
+pub use client::ApiClient;
+
+use args::USAGE;
+
+/// The command succeeded; for `exec`, the script finished on every client
+pub const EXIT_SUCCESS: i32 = 0;
+/// The script failed or didn't finish, or the request failed
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+/// No running instance could be reached
+pub const EXIT_NOT_RUNNING: i32 = 3;
+/// The instance refused the request, e.g. no client matches the target
+pub const EXIT_REJECTED: i32 = 4;
+
+/// Why a CLI command failed
+#[derive(Debug)]
+pub enum CliError {
+    Usage(String),
+    NotRunning(String),
+    Rejected(String),
+    /// The instance refused the request because a setting disables it
+    Forbidden(String),
+    Failed(String),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => EXIT_USAGE,
+            CliError::NotRunning(_) => EXIT_NOT_RUNNING,
+            CliError::Rejected(_) | CliError::Forbidden(_) => EXIT_REJECTED,
+            CliError::Failed(_) => EXIT_FAILED,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            CliError::Usage(message)
+            | CliError::NotRunning(message)
+            | CliError::Rejected(message)
+            | CliError::Forbidden(message)
+            | CliError::Failed(message) => message,
+        }
+    }
+}
+
+/// Main CLI entry point
+///
+/// # Returns
+/// The process exit code
+pub fn run_cli(args: &[String]) -> i32 {
+    // The app is built as a GUI program on Windows, so it has no console of its own
+    #[cfg(windows)]
+    unsafe {
+        winapi::um::wincon::AttachConsole(winapi::um::wincon::ATTACH_PARENT_PROCESS);
+    }
+
+    let result = args::parse(args).map_err(CliError::Usage).and_then(run);
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e.message());
+            if matches!(e, CliError::Usage(_)) {
+                eprintln!("\n{}", USAGE);
+            }
+            e.exit_code()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<i32, CliError> {
+    if cli.command == Command::Help {
+        println!("{}", USAGE);
+        return Ok(EXIT_SUCCESS);
+    }
+
+    let api = ApiClient::new(cli.port)?;
+
+    match cli.command {
+        Command::Exec(options) => commands::exec(&api, options),
+        Command::Clients { json } => commands::clients(&api, json),
+        Command::Logs(options) => commands::logs(&api, options),
+        Command::Status { json } => commands::status(&api, json),
+        Command::Help => Ok(EXIT_SUCCESS),
+    }
+}
@@ -0,0 +1,209 @@
+//! Luau syntax and lint diagnostics
+//!
+//! Scripts are parsed with full-moon's Luau grammar, which recovers from errors so every
+//! syntax error in a buffer is reported at once. When a script parses, a small set of
+//! lints runs over the AST.
+
+use crate::models::{Diagnostic, DiagnosticSeverity};
+use full_moon::ast::{self, Expression};
+use full_moon::node::Node;
+use full_moon::tokenizer::Position;
+use full_moon::visitors::Visitor;
+use full_moon::LuaVersion;
+
+/// Parse and lint a script
+///
+/// # Returns
+/// Diagnostics ordered by position; syntax errors only if the script doesn't parse
+pub fn diagnose(source: &str) -> Vec<Diagnostic> {
+    let result = full_moon::parse_fallible(source, LuaVersion::luau());
+
+    let mut diagnostics: Vec<Diagnostic> = result
+        .errors()
+        .iter()
+        .map(|error| {
+            let (start, end) = error.range();
+            diagnostic(
+                start,
+                end,
+                DiagnosticSeverity::Error,
+                "syntax",
+                error.error_message().to_string(),
+            )
+        })
+        .collect();
+
+    // Lints on a partially parsed tree would point at code that isn't there
+    if diagnostics.is_empty() {
+        let mut linter = Linter::default();
+        linter.visit_ast(result.ast());
+        diagnostics = linter.diagnostics;
+    }
+
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
+
+/// Check that a script parses
+///
+/// # Returns
+/// * `Ok(())` - If the script has no syntax errors
+/// * `Err(String)` - The first syntax error and the number of others
+pub fn check_syntax(source: &str) -> Result<(), String> {
+    let errors: Vec<Diagnostic> = diagnose(source)
+        .into_iter()
+        .filter(|d| d.severity == DiagnosticSeverity::Error)
+        .collect();
+
+    let Some(first) = errors.first() else {
+        return Ok(());
+    };
+
+    let others = match errors.len() - 1 {
+        0 => String::new(),
+        1 => " (and 1 other error)".to_string(),
+        n => format!(" (and {} other errors)", n),
+    };
+
+    Err(format!(
+        "Syntax error at line {}, column {}: {}{}",
+        first.line, first.column, first.message, others
+    ))
+}
+
+fn diagnostic(
+    start: Position,
+    end: Position,
+    severity: DiagnosticSeverity,
+    rule: &str,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        line: start.line(),
+        column: start.character(),
+        end_line: end.line(),
+        end_column: end.character(),
+        severity,
+        rule: rule.to_string(),
+        message,
+    }
+}
+
+#[derive(Default)]
+struct Linter {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter {
+    fn warn(&mut self, node: &impl Node, rule: &str, message: &str) {
+        let (Some(start), Some(end)) = (node.start_position(), node.end_position()) else {
+            return;
+        };
+
+        self.diagnostics.push(diagnostic(
+            start,
+            end,
+            DiagnosticSeverity::Warning,
+            rule,
+            message.to_string(),
+        ));
+    }
+}
+
+impl Visitor for Linter {
+    fn visit_if(&mut self, node: &ast::If) {
+        let block = node.block();
+        if block.stmts().next().is_none() && block.last_stmt().is_none() {
+            self.warn(node, "empty_if", "This if statement has an empty body");
+        }
+    }
+
+    fn visit_local_assignment(&mut self, node: &ast::LocalAssignment) {
+        let names = node.names().len();
+        let values = node.expressions().len();
+
+        if values > names {
+            self.warn(
+                node,
+                "unbalanced_assignment",
+                "More values are assigned than there are variables; the extra values are discarded",
+            );
+            return;
+        }
+
+        // A call or `...` as the last value can expand to fill the remaining names
+        let expands = match node.expressions().iter().last() {
+            Some(Expression::FunctionCall(_)) => true,
+            Some(Expression::Symbol(symbol)) => symbol.token().to_string() == "...",
+            _ => false,
+        };
+
+        if values > 0 && values < names && !expands {
+            self.warn(
+                node,
+                "unbalanced_assignment",
+                "Fewer values are assigned than there are variables; the rest will be nil",
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(source: &str) -> Vec<(usize, usize, String)> {
+        diagnose(source)
+            .into_iter()
+            .map(|d| (d.line, d.column, d.rule))
+            .collect()
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let diagnostics = diagnose("local a = 1\nprint(a\n");
+        assert_eq!(diagnostics.len(), 1);
+
+        let error = &diagnostics[0];
+        assert_eq!((error.line, error.column), (2, 6));
+        assert_eq!((error.end_line, error.end_column), (2, 7));
+        assert_eq!(error.severity, DiagnosticSeverity::Error);
+        assert_eq!(error.rule, "syntax");
+
+        assert_eq!(
+            check_syntax("local a = 1\nprint(a\n"),
+            Err(format!(
+                "Syntax error at line 2, column 6: {}",
+                error.message
+            ))
+        );
+    }
+
+    #[test]
+    fn test_lints() {
+        let diagnostics = diagnose("if true then\nend\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, "empty_if");
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!((diagnostics[0].end_line, diagnostics[0].end_column), (2, 4));
+
+        // Calls and `...` can fill the remaining names
+        let source = "local a, b = 1, 2, 3\nlocal c, d = 1\nlocal e, f = g()\nlocal h, i = ...\n";
+        assert_eq!(
+            rules(source),
+            vec![
+                (1, 1, "unbalanced_assignment".to_string()),
+                (2, 1, "unbalanced_assignment".to_string()),
+            ]
+        );
+        // Lints are still warnings, so the script passes the syntax check
+        assert_eq!(check_syntax(source), Ok(()));
+    }
+
+    #[test]
+    fn test_clean_script() {
+        let source = "local a, b = 1, 2\nif a then\n    print(a + b)\nend\n";
+        assert!(diagnose(source).is_empty());
+        assert_eq!(check_syntax(source), Ok(()));
+    }
+}
@@ -0,0 +1,64 @@
+//! Discovery file for the running instance
+//!
+//! Written while the HTTP server is running so the CLI can find its port without reading
+//! settings. It lives in the user's local data directory rather than the base directory,
+//! which the CLI can't resolve in development builds.
+//!
+//! Only the port is discovered: the HTTP API listens on localhost without an access token.
+
+use crate::models::InstanceInfo;
+use std::path::PathBuf;
+
+const DISCOVERY_FILE_NAME: &str = "instance.json";
+
+/// Path of the discovery file
+pub fn discovery_file() -> Result<PathBuf, String> {
+    dirs::data_local_dir()
+        .map(|dir| dir.join("Proxima").join(DISCOVERY_FILE_NAME))
+        .ok_or_else(|| "Failed to find the local data directory".to_string())
+}
+
+/// Record the HTTP port of this instance
+pub fn write(http_port: u16) -> Result<(), String> {
+    let path = discovery_file()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create discovery directory: {}", e))?;
+    }
+
+    let info = InstanceInfo {
+        pid: std::process::id(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        http_port,
+    };
+    let json = serde_json::to_string_pretty(&info)
+        .map_err(|e| format!("Failed to serialize discovery file: {}", e))?;
+
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write discovery file: {}", e))
+}
+
+/// Remove the discovery file, unless another instance has replaced it
+pub fn remove() {
+    let Ok(path) = discovery_file() else {
+        return;
+    };
+
+    if read().is_ok_and(|info| info.pid == std::process::id()) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            log::warn!("Failed to remove discovery file: {}", e);
+        }
+    }
+}
+
+/// Read the discovery file of the running instance
+///
+/// # Returns
+/// * `Ok(InstanceInfo)` - How to reach the instance
+/// * `Err(String)` - If no instance has written the file
+pub fn read() -> Result<InstanceInfo, String> {
+    let path = discovery_file()?;
+    let json = std::fs::read_to_string(&path)
+        .map_err(|_| "Proxima is not running (no discovery file)".to_string())?;
+
+    serde_json::from_str(&json).map_err(|e| format!("Invalid discovery file: {}", e))
+}
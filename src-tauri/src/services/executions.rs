@@ -0,0 +1,536 @@
+//! Tracking and cancellation of running executions
+//!
+//! Every script sent to a client gets an execution ID. The client tracks the threads
+//! the chunk spawns (through `task`, `spawn`, `delay` and `coroutine`) and the signals it
+//! connects to, and reports back once they have all ended, failed or been cancelled; until
+//! then the execution is listed as running on that client. Connections are only tracked
+//! on executors that support `hookfunction`.
+//!
+//! Redirected output is tagged with its execution and client, and the output of recent
+//! executions is kept so it can be fetched or streamed back to whoever submitted the script.
+
+use crate::models::{
+    ClientExecution, ClientResult, ExecutionDetails, ExecutionEvent, ExecutionOutputLine,
+    ExecutionResult, ExecutionSource, ExecutionStatus, RunningExecution,
+};
+use crate::state::{ClientRegistry, ExecutionsState, HistoryState};
+use crate::utils::events::emit_or_log;
+use crate::utils::time::now_millis;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+
+use super::websocket;
+
+// Executions whose output is kept, and the lines kept for each
+const MAX_OUTPUT_EXECUTIONS: usize = 100;
+const MAX_OUTPUT_LINES: usize = 1000;
+
+// Per-client results of ended executions that are kept
+const MAX_FINISHED: usize = 1000;
+
+const EVENT_CAPACITY: usize = 256;
+
+/// Executions running on each client
+pub struct ExecutionTracker {
+    /// Client ID to the executions running on it, oldest first
+    running: HashMap<String, Vec<RunningExecution>>,
+    /// Execution ID and result of executions that ended on a client, oldest first
+    finished: VecDeque<(String, ClientExecution)>,
+    /// Output of recent executions, oldest first
+    output: VecDeque<(String, Vec<ExecutionOutputLine>)>,
+    events: broadcast::Sender<ExecutionEvent>,
+}
+
+impl Default for ExecutionTracker {
+    fn default() -> Self {
+        Self {
+            running: HashMap::new(),
+            finished: VecDeque::new(),
+            output: VecDeque::new(),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+}
+
+impl ExecutionTracker {
+    fn start(&mut self, execution: RunningExecution) {
+        self.running
+            .entry(execution.client_id.clone())
+            .or_default()
+            .push(execution);
+    }
+
+    fn finish(&mut self, client_id: &str, id: &str) -> Option<RunningExecution> {
+        let executions = self.running.get_mut(client_id)?;
+        let index = executions.iter().position(|e| e.id == id)?;
+        let execution = executions.remove(index);
+
+        if executions.is_empty() {
+            self.running.remove(client_id);
+        }
+        Some(execution)
+    }
+
+    /// Keep the result of an execution that ended on a client
+    fn complete(
+        &mut self,
+        execution: RunningExecution,
+        status: ExecutionStatus,
+        error: Option<String>,
+    ) {
+        if self.finished.len() >= MAX_FINISHED {
+            self.finished.pop_front();
+        }
+
+        self.finished.push_back((
+            execution.id,
+            ClientExecution {
+                client_id: execution.client_id,
+                username: execution.username,
+                status,
+                error,
+                started_at: execution.started_at,
+                finished_at: Some(now_millis()),
+            },
+        ));
+    }
+
+    /// State of an execution on each client it reached, running or recently ended
+    pub fn clients(&self, id: &str) -> Vec<ClientExecution> {
+        let running = self
+            .running
+            .values()
+            .flatten()
+            .filter(|e| e.id == id)
+            .map(|e| ClientExecution {
+                client_id: e.client_id.clone(),
+                username: e.username.clone(),
+                status: ExecutionStatus::Running,
+                error: None,
+                started_at: e.started_at,
+                finished_at: None,
+            });
+
+        let finished = self
+            .finished
+            .iter()
+            .filter(|(execution_id, _)| execution_id == id)
+            .map(|(_, client)| client.clone());
+
+        let mut clients: Vec<ClientExecution> = running.chain(finished).collect();
+        clients.sort_by(|a, b| a.username.cmp(&b.username));
+        clients
+    }
+
+    fn record(&mut self, line: ExecutionOutputLine) {
+        let index = match self
+            .output
+            .iter()
+            .position(|(id, _)| *id == line.execution_id)
+        {
+            Some(index) => index,
+            None => {
+                if self.output.len() >= MAX_OUTPUT_EXECUTIONS {
+                    self.output.pop_front();
+                }
+                self.output
+                    .push_back((line.execution_id.clone(), Vec::new()));
+                self.output.len() - 1
+            }
+        };
+
+        let lines = &mut self.output[index].1;
+        if lines.len() >= MAX_OUTPUT_LINES {
+            lines.remove(0);
+        }
+        lines.push(line);
+    }
+
+    /// Redirected output of an execution, optionally from a single client
+    pub fn output(&self, id: &str, client_id: Option<&str>) -> Vec<ExecutionOutputLine> {
+        self.output
+            .iter()
+            .find(|(execution_id, _)| execution_id == id)
+            .map(|(_, lines)| {
+                lines
+                    .iter()
+                    .filter(|line| client_id.map_or(true, |c| line.client_id == c))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether an execution is still running on any client
+    pub fn is_running(&self, id: &str) -> bool {
+        self.running
+            .values()
+            .any(|executions| executions.iter().any(|e| e.id == id))
+    }
+
+    /// Receive execution events from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ExecutionEvent> {
+        self.events.subscribe()
+    }
+
+    fn publish(&self, event: ExecutionEvent) {
+        // No receivers is fine; nobody is streaming
+        let _ = self.events.send(event);
+    }
+
+    /// Running executions across all clients, oldest first
+    pub fn list(&self) -> Vec<RunningExecution> {
+        let mut executions: Vec<RunningExecution> =
+            self.running.values().flatten().cloned().collect();
+        executions.sort_by_key(|e| e.started_at);
+        executions
+    }
+
+    /// IDs of the clients an execution is still running on
+    pub fn clients_running(&self, id: &str) -> Vec<String> {
+        let mut client_ids: Vec<String> = self
+            .running
+            .iter()
+            .filter(|(_, executions)| executions.iter().any(|e| e.id == id))
+            .map(|(client_id, _)| client_id.clone())
+            .collect();
+        client_ids.sort();
+        client_ids
+    }
+}
+
+/// Start tracking an execution on the clients it was delivered, or is about to be sent, to
+pub async fn track(app: &AppHandle, id: &str, source: ExecutionSource, client_ids: &[String]) {
+    if client_ids.is_empty() {
+        return;
+    }
+
+    let clients = app.state::<ClientRegistry>().inner().clone();
+    let clients = clients.read().await;
+    let started_at = now_millis();
+
+    let executions = app.state::<ExecutionsState>().inner().clone();
+    let mut executions = executions.write().await;
+
+    for client_id in client_ids {
+        let Some(client) = clients.get(client_id) else {
+            continue;
+        };
+
+        executions.start(RunningExecution {
+            id: id.to_string(),
+            client_id: client_id.clone(),
+            username: client.username.clone(),
+            source,
+            started_at,
+        });
+    }
+
+    executions.publish(ExecutionEvent::Started {
+        execution_id: id.to_string(),
+        client_ids: client_ids.to_vec(),
+    });
+
+    emit_or_log(app, "executions-update", executions.list());
+}
+
+/// Stop tracking an execution on clients it was tracked on but never reached
+///
+/// Nothing is recorded for those clients, as the script never ran on them.
+pub async fn untrack(app: &AppHandle, id: &str, client_ids: &[String]) {
+    if client_ids.is_empty() {
+        return;
+    }
+
+    let executions = app.state::<ExecutionsState>().inner().clone();
+    let mut executions = executions.write().await;
+
+    for client_id in client_ids {
+        executions.finish(client_id, id);
+    }
+
+    emit_or_log(app, "executions-update", executions.list());
+}
+
+/// Record that an execution ended on a client
+pub async fn finish(
+    app: &AppHandle,
+    client_id: &str,
+    id: &str,
+    status: ExecutionStatus,
+    error: Option<String>,
+) {
+    let executions = app.state::<ExecutionsState>().inner().clone();
+    let mut executions = executions.write().await;
+
+    let Some(execution) = executions.finish(client_id, id) else {
+        return;
+    };
+    let username = execution.username.clone();
+    executions.complete(execution, status, error.clone());
+
+    executions.publish(ExecutionEvent::Done {
+        execution_id: id.to_string(),
+        client_id: client_id.to_string(),
+        status,
+        error: error.clone(),
+    });
+
+    match status {
+        ExecutionStatus::Cancelled => {
+            log_ui!(app, Info, "Execution cancelled on {}", username)
+        }
+        ExecutionStatus::Failed => log::debug!(
+            "Execution {} failed on {}: {}",
+            id,
+            username,
+            error.unwrap_or_default()
+        ),
+        _ => log::debug!("Execution {} finished on {}", id, username),
+    }
+
+    emit_or_log(app, "executions-update", executions.list());
+}
+
+/// Stop tracking every execution on a client, when it disconnects
+pub async fn remove_client(app: &AppHandle, client_id: &str) {
+    let executions = app.state::<ExecutionsState>().inner().clone();
+    let mut executions = executions.write().await;
+
+    let Some(removed) = executions.running.remove(client_id) else {
+        return;
+    };
+
+    for execution in removed {
+        executions.publish(ExecutionEvent::Done {
+            execution_id: execution.id.clone(),
+            client_id: client_id.to_string(),
+            status: ExecutionStatus::Disconnected,
+            error: None,
+        });
+        executions.complete(execution, ExecutionStatus::Disconnected, None);
+    }
+
+    emit_or_log(app, "executions-update", executions.list());
+}
+
+/// Record a line of redirected output from an execution
+pub async fn record_output(app: &AppHandle, id: &str, client_id: &str, level: u8, message: &str) {
+    let username = app
+        .state::<ClientRegistry>()
+        .read()
+        .await
+        .get(client_id)
+        .map(|client| client.username.clone())
+        .unwrap_or_default();
+
+    let line = ExecutionOutputLine {
+        execution_id: id.to_string(),
+        client_id: client_id.to_string(),
+        username,
+        level,
+        message: message.to_string(),
+        timestamp: now_millis(),
+    };
+
+    let executions = app.state::<ExecutionsState>().inner().clone();
+    let mut executions = executions.write().await;
+    executions.publish(ExecutionEvent::Output(line.clone()));
+    executions.record(line);
+}
+
+/// Get an execution from the history with its state on each client and its output
+///
+/// # Returns
+/// * `Some(ExecutionDetails)` - The execution
+/// * `None` - If the execution isn't in the history
+pub async fn details(app: &AppHandle, id: &str) -> Option<ExecutionDetails> {
+    let entry = app.state::<HistoryState>().read().await.get(id).cloned()?;
+    let executions = app.state::<ExecutionsState>().inner().clone();
+    let executions = executions.read().await;
+
+    Some(ExecutionDetails {
+        id: entry.id,
+        timestamp: entry.timestamp,
+        source: entry.source,
+        outcome: entry.outcome,
+        error: entry.error,
+        clients: executions.clients(id),
+        output: executions.output(id, None),
+    })
+}
+
+/// Wait until an execution has ended on every client it reached, or `timeout` passes
+///
+/// `events` must be subscribed before the script is sent, so its end isn't missed.
+/// An execution that never reaches a client times out.
+pub async fn wait(
+    app: &AppHandle,
+    id: &str,
+    mut events: broadcast::Receiver<ExecutionEvent>,
+    timeout: Duration,
+) -> ExecutionResult {
+    let executions = app.state::<ExecutionsState>().inner().clone();
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        {
+            let executions = executions.read().await;
+            let started = !executions.clients(id).is_empty();
+            if started && !executions.is_running(id) {
+                break;
+            }
+        }
+
+        // Re-check whenever something happens to this execution
+        let changed = loop {
+            match tokio::time::timeout_at(deadline, events.recv()).await {
+                Ok(Ok(event)) if event.execution_id() != id => continue,
+                Ok(Ok(_)) | Ok(Err(broadcast::error::RecvError::Lagged(_))) => break true,
+                Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => break false,
+            }
+        };
+
+        if !changed {
+            break;
+        }
+    }
+
+    let executions = executions.read().await;
+    let clients = executions.clients(id);
+    let timed_out = clients.is_empty() || executions.is_running(id);
+    let clients: Vec<ClientResult> = clients
+        .into_iter()
+        .map(|client| ClientResult {
+            success: client.status == ExecutionStatus::Finished,
+            duration_ms: client
+                .finished_at
+                .map(|finished_at| finished_at.saturating_sub(client.started_at)),
+            output: executions.output(id, Some(&client.client_id)),
+            client_id: client.client_id,
+            username: client.username,
+            status: client.status,
+            error: client.error,
+        })
+        .collect();
+
+    ExecutionResult {
+        execution_id: id.to_string(),
+        success: !timed_out && !clients.is_empty() && clients.iter().all(|c| c.success),
+        timed_out,
+        clients,
+    }
+}
+
+/// Ask clients to cancel a running execution
+///
+/// # Arguments
+/// * `client_id` - The client to cancel on, or `None` for every client it's running on
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - IDs of the clients the cancellation was sent to
+/// * `Err(String)` - If the execution isn't running on the given clients
+pub async fn cancel(
+    app: &AppHandle,
+    id: &str,
+    client_id: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let running = app
+        .state::<ExecutionsState>()
+        .read()
+        .await
+        .clients_running(id);
+
+    let targets: Vec<String> = match client_id {
+        Some(client_id) => running.into_iter().filter(|c| c == client_id).collect(),
+        None => running,
+    };
+
+    if targets.is_empty() {
+        return Err(format!("Execution is not running: {}", id));
+    }
+
+    let clients = app.state::<ClientRegistry>().inner().clone();
+    let mut sent = Vec::new();
+
+    for target in targets {
+        match websocket::send_exec_cancel(&target, id, &clients).await {
+            Ok(()) => sent.push(target),
+            Err(e) => log::error!("Failed to cancel execution on {}: {}", target, e),
+        }
+    }
+
+    if sent.is_empty() {
+        return Err("Failed to reach the clients running this execution".to_string());
+    }
+
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn running(id: &str, client_id: &str, started_at: u64) -> RunningExecution {
+        RunningExecution {
+            id: id.to_string(),
+            client_id: client_id.to_string(),
+            username: client_id.to_string(),
+            source: ExecutionSource::Editor,
+            started_at,
+        }
+    }
+
+    #[test]
+    fn test_tracker() {
+        let mut tracker = ExecutionTracker::default();
+        tracker.start(running("a", "client-2", 1));
+        tracker.start(running("a", "client-1", 1));
+        tracker.start(running("b", "client-1", 2));
+
+        assert_eq!(tracker.clients_running("a"), vec!["client-1", "client-2"]);
+        let execution = tracker.finish("client-2", "a").unwrap();
+        tracker.complete(execution, ExecutionStatus::Finished, None);
+        assert!(tracker.finish("client-2", "a").is_none());
+        assert_eq!(tracker.clients_running("a"), vec!["client-1"]);
+
+        let statuses: Vec<ExecutionStatus> =
+            tracker.clients("a").into_iter().map(|c| c.status).collect();
+        assert_eq!(
+            statuses,
+            vec![ExecutionStatus::Running, ExecutionStatus::Finished]
+        );
+
+        let ids: Vec<String> = tracker.list().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_output() {
+        let mut tracker = ExecutionTracker::default();
+        for (id, client_id, message) in [
+            ("a", "client-1", "one"),
+            ("b", "client-1", "x"),
+            ("a", "client-2", "two"),
+        ] {
+            tracker.record(ExecutionOutputLine {
+                execution_id: id.to_string(),
+                client_id: client_id.to_string(),
+                username: client_id.to_string(),
+                level: 0,
+                message: message.to_string(),
+                timestamp: 0,
+            });
+        }
+
+        let messages = |lines: Vec<ExecutionOutputLine>| -> Vec<String> {
+            lines.into_iter().map(|l| l.message).collect()
+        };
+        assert_eq!(messages(tracker.output("a", None)), vec!["one", "two"]);
+        assert_eq!(messages(tracker.output("a", Some("client-2"))), vec!["two"]);
+        assert!(tracker.output("c", None).is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+//! Instance explorer sessions
+//!
+//! One client at a time can run the explorer. Requests are sent to the active explorer
+//! client, which replies with events; the `request_*` functions wait for the reply so
+//! callers outside the webview, like the HTTP API, get the result directly.
+
+use crate::models::explorer::{
+    DecompiledScriptEvent, PropertiesEvent, SearchResultsEvent, TreeEvent,
+};
+use crate::state::{ActiveClientsState, ApiDumpCache, ClientRegistry};
+use crate::utils::clients::{get_active_explorer, verify_client_exists, ClientRequestError};
+use crate::utils::events::{self, emit_or_error};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::websocket::{
+    send_decompile_script, send_get_explorer_properties, send_get_explorer_tree,
+    send_search_explorer, send_start_explorer, send_stop_explorer,
+};
+
+/// How long to wait for the explorer client to reply
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Start the explorer on a client
+///
+/// # Returns
+/// * `Ok(())` - If the explorer started, or was already running on this client
+/// * `Err(ClientRequestError)` - If it's running on another client, or the client isn't
+///   attached or can't be reached
+pub async fn start(app: &AppHandle, client_id: &str) -> Result<(), ClientRequestError> {
+    log::info!("Starting explorer for client: {}", client_id);
+
+    let active_clients = app.state::<ActiveClientsState>();
+    let clients = app.state::<ClientRegistry>();
+
+    // Check if another client already has explorer active
+    {
+        let active = active_clients.read().await;
+        if let Some(existing_id) = &active.explorer {
+            if existing_id != client_id {
+                return Err(ClientRequestError::InUse(format!(
+                    "Explorer is already active for another client: {}",
+                    existing_id
+                )));
+            }
+            // Already active for this client
+            return Ok(());
+        }
+    }
+
+    // Verify client exists
+    verify_client_exists(client_id, &clients).await?;
+
+    // Set as active explorer client
+    {
+        let mut active = active_clients.write().await;
+        active.explorer = Some(client_id.to_string());
+    }
+
+    // Send start_explorer message to client
+    send_start_explorer(client_id, &clients).await?;
+
+    log_ui!(app, Info, "Starting explorer for client: {}", client_id);
+
+    // Emit event to frontend
+    emit_or_error(app, "explorer-started", client_id.to_string())?;
+
+    Ok(())
+}
+
+/// Stop the explorer, if it's running
+pub async fn stop(app: &AppHandle) -> Result<(), ClientRequestError> {
+    log::info!("Stopping explorer");
+
+    let active = app.state::<ActiveClientsState>();
+    let client_id = active.write().await.explorer.take();
+
+    if let Some(id) = client_id {
+        // Send stop_explorer message to client (ignore errors if client disconnected)
+        let _ = send_stop_explorer(&id, &app.state::<ClientRegistry>()).await;
+
+        // Emit event to frontend
+        emit_or_error(app, "explorer-stopped", ())?;
+    }
+
+    Ok(())
+}
+
+/// Ask the explorer client for the tree, with the given instances expanded
+pub async fn send_tree_request(
+    app: &AppHandle,
+    expanded_ids: Vec<u32>,
+) -> Result<String, ClientRequestError> {
+    let client_id = get_active_explorer(&app.state::<ActiveClientsState>()).await?;
+    send_get_explorer_tree(&client_id, expanded_ids, &app.state::<ClientRegistry>()).await?;
+    Ok(client_id)
+}
+
+/// Ask the explorer client for an instance's properties
+pub async fn send_properties_request(
+    app: &AppHandle,
+    id: u32,
+    class_name: &str,
+) -> Result<String, ClientRequestError> {
+    let client_id = get_active_explorer(&app.state::<ActiveClientsState>()).await?;
+
+    // Get properties from API dump
+    let (properties, special_properties) = {
+        let service = app.state::<ApiDumpCache>();
+        let service = service.read().await;
+        if service.is_loaded() {
+            service.get_class_properties(class_name)
+        } else {
+            log::warn!("API dump not loaded yet, sending empty property lists");
+            (vec![], vec![])
+        }
+    };
+
+    send_get_explorer_properties(
+        &client_id,
+        id,
+        properties,
+        special_properties,
+        &app.state::<ClientRegistry>(),
+    )
+    .await?;
+    Ok(client_id)
+}
+
+/// Ask the explorer client to search for instances
+pub async fn send_search_request(
+    app: &AppHandle,
+    query: String,
+    search_by: String,
+    limit: u32,
+) -> Result<String, ClientRequestError> {
+    let client_id = get_active_explorer(&app.state::<ActiveClientsState>()).await?;
+    send_search_explorer(
+        &client_id,
+        query,
+        search_by,
+        limit,
+        &app.state::<ClientRegistry>(),
+    )
+    .await?;
+    Ok(client_id)
+}
+
+/// Ask the explorer client to decompile a script
+pub async fn send_decompile_request(
+    app: &AppHandle,
+    id: u32,
+) -> Result<String, ClientRequestError> {
+    let client_id = get_active_explorer(&app.state::<ActiveClientsState>()).await?;
+    send_decompile_script(&client_id, id, &app.state::<ClientRegistry>()).await?;
+    Ok(client_id)
+}
+
+/// Get the tree, with the given instances expanded
+pub async fn request_tree(
+    app: &AppHandle,
+    expanded_ids: Vec<u32>,
+) -> Result<TreeEvent, ClientRequestError> {
+    let events = events::subscribe(app);
+    let client_id = send_tree_request(app, expanded_ids).await?;
+    events::wait_for_client_event(events, &client_id, "explorer-tree", REPLY_TIMEOUT, |_| true)
+        .await
+}
+
+/// Get an instance's properties
+pub async fn request_properties(
+    app: &AppHandle,
+    id: u32,
+    class_name: &str,
+) -> Result<PropertiesEvent, ClientRequestError> {
+    let events = events::subscribe(app);
+    let client_id = send_properties_request(app, id, class_name).await?;
+    events::wait_for_client_event(
+        events,
+        &client_id,
+        "explorer-properties",
+        REPLY_TIMEOUT,
+        |reply: &PropertiesEvent| reply.id == id,
+    )
+    .await
+}
+
+/// Search for instances
+pub async fn request_search(
+    app: &AppHandle,
+    query: String,
+    search_by: String,
+    limit: u32,
+) -> Result<SearchResultsEvent, ClientRequestError> {
+    let events = events::subscribe(app);
+    let client_id = send_search_request(app, query.clone(), search_by, limit).await?;
+    events::wait_for_client_event(
+        events,
+        &client_id,
+        "explorer-search-results",
+        REPLY_TIMEOUT,
+        |reply: &SearchResultsEvent| reply.query == query,
+    )
+    .await
+}
+
+/// Decompile a script
+pub async fn request_decompile(
+    app: &AppHandle,
+    id: u32,
+) -> Result<DecompiledScriptEvent, ClientRequestError> {
+    let events = events::subscribe(app);
+    let client_id = send_decompile_request(app, id).await?;
+    events::wait_for_client_event(
+        events,
+        &client_id,
+        "explorer-decompiled-script",
+        REPLY_TIMEOUT,
+        |reply: &DecompiledScriptEvent| reply.id == id,
+    )
+    .await
+}
@@ -1,26 +1,37 @@
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, FileIdMap};
-use std::path::PathBuf;
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify_debouncer_full::{
+    new_debouncer, DebounceEventResult, DebouncedEvent, Debouncer, FileIdMap,
+};
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 
 use crate::log_ui;
+use crate::models::{FileChange, FileChangeKind};
+use crate::utils::events::emit_or_log;
+use crate::utils::ignore::IgnorePatterns;
 use crate::utils::paths;
 
+use super::{quick_open, versions};
+
 const DEBOUNCE_DURATION_MS: u64 = 500;
+const WATCHED_ROOTS: [&str; 2] = ["scripts", "autoexec"];
 
 /// Start watching scripts and autoexec directories for file changes
+///
+/// Changes that aren't ignored by `.proximaignore` are emitted as a `file-changes` event
+/// with a list of `FileChange`s.
 pub fn start_file_watcher(app: AppHandle) -> Result<(), String> {
     let base_dir = paths::get_base_directory(&app)?;
-    let scripts_dir = base_dir.join("scripts");
-    let autoexec_dir = base_dir.join("autoexec");
 
     log::info!("File watcher initialized");
 
     let app_clone = app.clone();
     std::thread::spawn(move || {
-        if let Err(e) = watch_directories(app_clone, scripts_dir, autoexec_dir) {
+        quick_open::refresh(&app_clone);
+        if let Err(e) = watch_directories(app_clone, base_dir) {
             log::error!("File watcher error: {}", e);
         }
     });
@@ -28,11 +39,7 @@ pub fn start_file_watcher(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-fn watch_directories(
-    app: AppHandle,
-    scripts_dir: PathBuf,
-    autoexec_dir: PathBuf,
-) -> Result<(), String> {
+fn watch_directories(app: AppHandle, base_dir: PathBuf) -> Result<(), String> {
     let (tx, rx) = channel();
 
     let mut debouncer: Debouncer<RecommendedWatcher, FileIdMap> = new_debouncer(
@@ -41,7 +48,7 @@ fn watch_directories(
         move |result: DebounceEventResult| {
             if let Ok(events) = result {
                 if !events.is_empty() {
-                    let _ = tx.send(());
+                    let _ = tx.send(events);
                 }
             }
         },
@@ -49,31 +56,139 @@ fn watch_directories(
     .map_err(|e| format!("Failed to create file watcher: {}", e))?;
 
     // Watch directories if they exist
-    for (dir, name) in [(&scripts_dir, "Scripts"), (&autoexec_dir, "AutoExec")] {
+    for (root, name) in WATCHED_ROOTS.iter().zip(["Scripts", "AutoExec"]) {
+        let dir = base_dir.join(root);
         if dir.exists() {
             debouncer
                 .watcher()
-                .watch(dir, RecursiveMode::Recursive)
+                .watch(&dir, RecursiveMode::Recursive)
                 .map_err(|e| format!("Failed to watch {} directory: {}", name, e))?;
         }
     }
 
     // Event loop - emit to frontend when files change
-    while rx.recv().is_ok() {
-        // Emit to main window
-        if let Err(e) = app.emit_to("main", "file-tree-changed", ()) {
-            log::error!(
-                "Failed to emit file tree change event to main window: {}",
-                e
-            );
-        } else {
+    while let Ok(events) = rx.recv() {
+        let changes = collect_changes(&base_dir, &events);
+        if changes.is_empty() {
+            continue;
+        }
+
+        // Renamed files keep their versions, and created and modified files are snapshotted
+        versions::record_changes(&app, &base_dir, &changes);
+
+        // Edits to existing files don't change the tree
+        if changes
+            .iter()
+            .any(|change| change.kind != FileChangeKind::Modified)
+        {
+            quick_open::apply_changes(&app, &base_dir, &changes);
             log_ui!(
                 &app,
                 Info,
                 "File watcher detected an update to the file tree"
             );
         }
+
+        emit_or_log(&app, "file-changes", changes);
     }
 
     Ok(())
 }
+
+/// Turn debounced events into changes relative to the base directory
+///
+/// Changes to ignored paths are dropped. A rename into or out of an ignored folder becomes
+/// a create or delete of the path that is visible.
+fn collect_changes(base_dir: &Path, events: &[DebouncedEvent]) -> Vec<FileChange> {
+    let ignore_patterns: Vec<(&str, IgnorePatterns)> = WATCHED_ROOTS
+        .iter()
+        .map(|root| {
+            let ignore_file = base_dir.join(root).join(".proximaignore");
+            let patterns = IgnorePatterns::from_file(&ignore_file).unwrap_or_else(|e| {
+                log::warn!("{}", e);
+                IgnorePatterns::empty()
+            });
+            (*root, patterns)
+        })
+        .collect();
+
+    // Relative path of a watched path, if it isn't ignored
+    let visible = |path: &PathBuf| -> Option<String> {
+        let relative = path
+            .strip_prefix(base_dir)
+            .ok()?
+            .to_string_lossy()
+            .replace('\\', "/");
+        let Some((root, rest)) = relative.split_once('/') else {
+            // A watched folder itself
+            return Some(relative);
+        };
+        let (_, patterns) = ignore_patterns.iter().find(|(name, _)| *name == root)?;
+
+        if patterns.is_ignored_within(Path::new(rest), path.is_dir()) {
+            None
+        } else {
+            Some(relative)
+        }
+    };
+
+    let mut changes = Vec::new();
+    for event in events {
+        let kind = match event.kind {
+            EventKind::Create(_) => FileChangeKind::Created,
+            EventKind::Remove(_) => FileChangeKind::Deleted,
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                FileChangeKind::Renamed
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => FileChangeKind::Deleted,
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => FileChangeKind::Created,
+            // Renames the platform couldn't pair up
+            EventKind::Modify(ModifyKind::Name(_)) => {
+                if event.paths.first().is_some_and(|path| path.exists()) {
+                    FileChangeKind::Created
+                } else {
+                    FileChangeKind::Deleted
+                }
+            }
+            EventKind::Modify(ModifyKind::Metadata(_)) => continue,
+            EventKind::Modify(_) => FileChangeKind::Modified,
+            _ => continue,
+        };
+
+        let change = if kind == FileChangeKind::Renamed {
+            match (visible(&event.paths[0]), visible(&event.paths[1])) {
+                (Some(old_path), Some(path)) => FileChange {
+                    kind,
+                    path,
+                    old_path: Some(old_path),
+                },
+                (None, Some(path)) => FileChange {
+                    kind: FileChangeKind::Created,
+                    path,
+                    old_path: None,
+                },
+                (Some(old_path), None) => FileChange {
+                    kind: FileChangeKind::Deleted,
+                    path: old_path,
+                    old_path: None,
+                },
+                (None, None) => continue,
+            }
+        } else {
+            let Some(path) = event.paths.first().and_then(visible) else {
+                continue;
+            };
+            FileChange {
+                kind,
+                path,
+                old_path: None,
+            }
+        };
+
+        if !changes.contains(&change) {
+            changes.push(change);
+        }
+    }
+
+    changes
+}
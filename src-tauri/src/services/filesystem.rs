@@ -1,7 +1,7 @@
 use crate::models::FileNode;
 use crate::utils::ignore::{ensure_ignore_file, IgnorePatterns};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 
 use crate::utils::paths;
@@ -78,6 +78,114 @@ pub fn build_file_tree(app: &AppHandle) -> Result<Vec<FileNode>, String> {
     Ok(nodes)
 }
 
+/// Build the tree for one folder inside the scripts or autoexec directory
+///
+/// Child IDs are derived from `id` as in the full tree, so the result can replace the
+/// folder's node in a tree from `build_file_tree`.
+///
+/// # Arguments
+/// * `base_dir` - The base directory, resolved
+/// * `folder` - The folder, resolved
+/// * `id` - The folder's node ID
+pub fn build_subtree(base_dir: &Path, folder: &Path, id: &str) -> Result<FileNode, String> {
+    let relative = folder
+        .strip_prefix(base_dir)
+        .map_err(|e| format!("Failed to get relative path: {}", e))?;
+    let root = relative
+        .components()
+        .next()
+        .map(|root| root.as_os_str().to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let root_name = match root.as_str() {
+        "scripts" => "Scripts",
+        "autoexec" => "AutoExec",
+        _ => return Err(format!("Not in the file tree: {}", relative.display())),
+    };
+    let name = if relative.components().count() == 1 {
+        root_name.to_string()
+    } else {
+        folder
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+
+    let ignore_patterns = IgnorePatterns::from_file(&base_dir.join(&root).join(".proximaignore"))?;
+    read_directory(folder, base_dir, id, &name, &ignore_patterns)
+}
+
+/// List the files under the scripts and autoexec directories that aren't ignored
+///
+/// Each folder's `.proximaignore` applies to paths relative to it. Symlinks are skipped so
+/// links back up the tree can't loop.
+pub fn list_workspace_files(base_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+
+    for root in ["scripts", "autoexec"] {
+        let root_dir = base_dir.join(root);
+        if !root_dir.exists() {
+            continue;
+        }
+
+        let ignore_patterns = IgnorePatterns::from_file(&root_dir.join(".proximaignore"))?;
+        collect_files(&root_dir, &root_dir, &ignore_patterns, &mut files);
+    }
+
+    Ok(files)
+}
+
+/// List the files under a folder inside the scripts or autoexec directory that aren't
+/// ignored
+///
+/// # Arguments
+/// * `base_dir` - The base directory
+/// * `folder` - The folder, under `base_dir`
+pub fn list_folder_files(base_dir: &Path, folder: &Path) -> Result<Vec<PathBuf>, String> {
+    let root = folder
+        .strip_prefix(base_dir)
+        .ok()
+        .and_then(|relative| relative.components().next())
+        .ok_or_else(|| format!("Not in the workspace: {}", folder.display()))?;
+    let root_dir = base_dir.join(root);
+
+    let ignore_patterns = IgnorePatterns::from_file(&root_dir.join(".proximaignore"))?;
+    let mut files = Vec::new();
+    collect_files(folder, &root_dir, &ignore_patterns, &mut files);
+    Ok(files)
+}
+
+/// Collect the files under `dir` that aren't ignored, in name order
+fn collect_files(
+    dir: &Path,
+    root_dir: &Path,
+    ignore_patterns: &IgnorePatterns,
+    files: &mut Vec<PathBuf>,
+) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        let relative = path.strip_prefix(root_dir).unwrap_or(&path);
+        if ignore_patterns.is_ignored(relative, file_type.is_dir()) {
+            continue;
+        }
+
+        if file_type.is_dir() {
+            collect_files(&path, root_dir, ignore_patterns, files);
+        } else if file_type.is_file() {
+            files.push(path);
+        }
+    }
+}
+
 /// Recursively read a directory and build a FileNode tree
 fn read_directory(
     path: &Path,
@@ -0,0 +1,217 @@
+//! Luau code formatting with StyLua
+//!
+//! The style comes from the nearest `stylua.toml` / `.stylua.toml` above the script,
+//! falling back to the indentation, line length, line ending and quote settings of the
+//! nearest `.editorconfig`, then to StyLua's defaults. Lookups stop at the base directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use stylua_lib::{Config, IndentType, LineEndings, OutputVerification, QuoteStyle};
+use tauri::AppHandle;
+
+use super::workspace::Workspace;
+
+const STYLUA_FILES: [&str; 2] = ["stylua.toml", ".stylua.toml"];
+const EDITORCONFIG_FILE: &str = ".editorconfig";
+
+/// Format a script with the style configured for its location
+///
+/// # Arguments
+/// * `source` - The script to format
+/// * `path` - Path of the script relative to the base directory; unsaved scripts use the
+///   workspace style
+pub fn format_script(app: &AppHandle, source: &str, path: Option<&str>) -> Result<String, String> {
+    let config = load_config(app, path)?;
+    format_source(source, config)
+}
+
+/// Format a script with an explicit style
+pub fn format_source(source: &str, config: Config) -> Result<String, String> {
+    stylua_lib::format_code(source, config, None, OutputVerification::None)
+        .map_err(|e| format!("Failed to format script: {}", e))
+}
+
+/// Whether a file is a Luau script that can be formatted
+pub fn is_formattable(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("lua") || ext.eq_ignore_ascii_case("luau"))
+}
+
+/// Resolve the style for a script
+fn load_config(app: &AppHandle, path: Option<&str>) -> Result<Config, String> {
+    let workspace = Workspace::load(app).map_err(|e| e.to_string())?;
+    let base_dir = workspace.base_dir();
+    let start_dir = start_directory(&workspace, path);
+
+    if let Some(stylua_path) = find_upwards(&start_dir, base_dir, &STYLUA_FILES) {
+        let content = fs::read_to_string(&stylua_path)
+            .map_err(|e| format!("Failed to read {}: {}", stylua_path.display(), e))?;
+        return toml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", stylua_path.display(), e));
+    }
+
+    if let Some(editorconfig_path) = find_upwards(&start_dir, base_dir, &[EDITORCONFIG_FILE]) {
+        let content = fs::read_to_string(&editorconfig_path)
+            .map_err(|e| format!("Failed to read {}: {}", editorconfig_path.display(), e))?;
+        return Ok(apply_editorconfig(Config::default(), &content));
+    }
+
+    Ok(Config::default())
+}
+
+/// Resolve the folder a script's style lookup starts from
+///
+/// Paths whose folder doesn't resolve inside the workspace start from the base directory.
+fn start_directory(workspace: &Workspace, path: Option<&str>) -> PathBuf {
+    path.and_then(|path| {
+        let (folder, _) = path.rsplit_once('/')?;
+        workspace.resolve_existing(folder).ok()
+    })
+    .unwrap_or_else(|| workspace.base_dir().to_path_buf())
+}
+
+/// Find the nearest of `names` in `start` or its ancestors, up to and including `root`
+///
+/// Both paths must be canonical.
+fn find_upwards(start: &Path, root: &Path, names: &[&str]) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .take_while(|dir| dir.starts_with(root))
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|path| path.is_file())
+}
+
+/// Apply the properties of `.editorconfig` sections that match Luau files
+fn apply_editorconfig(mut config: Config, content: &str) -> Config {
+    let mut matches = false;
+    let mut indent_size = None;
+    let mut tab_width = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            matches = section_matches_luau(section);
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if !matches {
+            continue;
+        }
+
+        let value = value.trim().to_ascii_lowercase();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "indent_style" => match value.as_str() {
+                "space" => config.indent_type = IndentType::Spaces,
+                "tab" => config.indent_type = IndentType::Tabs,
+                _ => {}
+            },
+            "indent_size" => indent_size = value.parse().ok(),
+            "tab_width" => tab_width = value.parse().ok(),
+            "max_line_length" => {
+                if let Ok(width) = value.parse() {
+                    config.column_width = width;
+                }
+            }
+            "end_of_line" => match value.as_str() {
+                "lf" => config.line_endings = LineEndings::Unix,
+                "crlf" => config.line_endings = LineEndings::Windows,
+                _ => {}
+            },
+            "quote_type" => match value.as_str() {
+                "double" => config.quote_style = QuoteStyle::AutoPreferDouble,
+                "single" => config.quote_style = QuoteStyle::AutoPreferSingle,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    // `indent_size = tab` defers to `tab_width`
+    if let Some(width) = indent_size.or(tab_width) {
+        config.indent_width = width;
+    }
+
+    config
+}
+
+/// Whether an `.editorconfig` section glob applies to `.lua` / `.luau` files
+fn section_matches_luau(section: &str) -> bool {
+    let section = section
+        .trim()
+        .trim_start_matches("**/")
+        .trim_start_matches('/');
+
+    if section == "*" {
+        return true;
+    }
+
+    let Some(extensions) = section.strip_prefix("*.") else {
+        return false;
+    };
+
+    let extensions = extensions
+        .strip_prefix('{')
+        .and_then(|e| e.strip_suffix('}'))
+        .unwrap_or(extensions);
+
+    extensions
+        .split(',')
+        .any(|ext| matches!(ext.trim(), "lua" | "luau"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_editorconfig() {
+        let content = "root = true\n\n[*.md]\nindent_size = 8\n\n[*.{lua,luau}]\nindent_style = space\nindent_size = 2\nmax_line_length = 100\nend_of_line = crlf\n";
+        let config = apply_editorconfig(Config::default(), content);
+
+        assert_eq!(config.indent_type, IndentType::Spaces);
+        assert_eq!(config.indent_width, 2);
+        assert_eq!(config.column_width, 100);
+        assert_eq!(config.line_endings, LineEndings::Windows);
+
+        assert!(section_matches_luau("*"));
+        assert!(section_matches_luau("**/*.luau"));
+        assert!(!section_matches_luau("*.json"));
+    }
+
+    #[test]
+    fn test_start_directory() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let base = root.join("proxima");
+        fs::create_dir_all(base.join("scripts/sub")).unwrap();
+        fs::write(root.join(".stylua.toml"), "").unwrap();
+        let base = base.canonicalize().unwrap();
+        let workspace = Workspace::new(&base, ["scripts".to_string()]).unwrap();
+
+        assert_eq!(
+            start_directory(&workspace, Some("scripts/sub/a.lua")),
+            base.join("scripts/sub")
+        );
+        assert_eq!(start_directory(&workspace, None), base);
+        for escape in [
+            "../a.lua",
+            "scripts/../../a.lua",
+            "scripts/missing/a.lua",
+            "a.lua",
+        ] {
+            assert_eq!(start_directory(&workspace, Some(escape)), base);
+        }
+
+        // A style file above the base directory is never used
+        let start = start_directory(&workspace, Some("scripts/../../a.lua"));
+        assert_eq!(find_upwards(&start, &base, &STYLUA_FILES), None);
+    }
+}
@@ -0,0 +1,353 @@
+//! Persistent execution history
+//!
+//! Records every script sent to clients (editor, HTTP, autoexec, schedules and re-runs)
+//! in `.proxima/history.json`. Entries are pruned by count, age and total script size,
+//! as configured in the "history" settings section. The file is written from a background
+//! task so executions never wait on the disk.
+
+use crate::models::{ExecutionOutcome, ExecutionSource, HistoryEntry, ScriptPayload};
+use crate::state::{ClientRegistry, HistoryState};
+use crate::utils::time::now_millis;
+use crate::utils::{paths, settings};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::{executions, websocket};
+
+const HISTORY_FILE: &str = "history.json";
+const DEFAULT_MAX_ENTRIES: usize = 500;
+const DEFAULT_MAX_AGE_DAYS: u64 = 30;
+const DEFAULT_MAX_SCRIPT_BYTES: usize = 16 * 1024 * 1024;
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// Number of the latest snapshot handed to a background write
+static SAVE_GENERATION: AtomicU64 = AtomicU64::new(0);
+/// Number of the snapshot last written to disk, held while writing
+static WRITTEN_GENERATION: Mutex<u64> = Mutex::new(0);
+
+/// Pruning limits from the "history" settings section
+#[derive(Clone, Copy, Debug)]
+pub struct HistoryLimits {
+    pub max_entries: usize,
+    pub max_age_days: u64,
+    /// Total size of the stored scripts
+    pub max_script_bytes: usize,
+}
+
+impl HistoryLimits {
+    pub fn load(app: &AppHandle) -> Self {
+        let history = settings::get_settings_section(app, "history");
+        let get = |key: &str| {
+            history
+                .as_ref()
+                .and_then(|h| h.get(key))
+                .and_then(|v| v.as_u64())
+        };
+
+        Self {
+            max_entries: get("maxEntries")
+                .map(|v| v as usize)
+                .unwrap_or(DEFAULT_MAX_ENTRIES),
+            max_age_days: get("maxAgeDays").unwrap_or(DEFAULT_MAX_AGE_DAYS),
+            max_script_bytes: get("maxScriptBytes")
+                .map(|v| v as usize)
+                .unwrap_or(DEFAULT_MAX_SCRIPT_BYTES),
+        }
+    }
+}
+
+/// Execution history backed by a JSON file
+///
+/// Entries are kept oldest first, shared so a save can snapshot them without copying
+/// the scripts.
+#[derive(Default)]
+pub struct ExecutionHistory {
+    path: Option<PathBuf>,
+    entries: Vec<Arc<HistoryEntry>>,
+}
+
+impl ExecutionHistory {
+    /// Load the history file, starting empty if it is missing or unreadable
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match paths::get_data_directory(app) {
+            Ok(dir) => dir.join(HISTORY_FILE),
+            Err(e) => {
+                log::error!("Failed to get data directory for history: {}", e);
+                return Self::default();
+            }
+        };
+
+        let entries: Vec<HistoryEntry> = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::error!("Failed to parse execution history: {}", e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+
+        Self {
+            path: Some(path),
+            entries: entries.into_iter().map(Arc::new).collect(),
+        }
+    }
+
+    /// Entries, newest first
+    pub fn iter(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().rev().map(Arc::as_ref)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&HistoryEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(Arc::as_ref)
+    }
+
+    /// Entries matching a case-insensitive query, newest first
+    ///
+    /// Matches script content, usernames, source, and ID or hash prefixes.
+    pub fn search<'a>(&'a self, query: &str) -> impl Iterator<Item = &'a HistoryEntry> {
+        let query = query.trim().to_lowercase();
+
+        self.iter().filter(move |entry| {
+            entry.script.to_lowercase().contains(&query)
+                || entry
+                    .usernames
+                    .iter()
+                    .any(|name| name.to_lowercase().contains(&query))
+                || entry.source.as_str() == query
+                || entry.id.starts_with(&query)
+                || entry.script_hash.starts_with(&query)
+        })
+    }
+
+    /// Add an entry, prune and save
+    pub fn push(&mut self, entry: HistoryEntry, limits: HistoryLimits) {
+        self.entries.push(Arc::new(entry));
+        self.prune(limits);
+        self.save();
+    }
+
+    /// Remove entries older than the age limit, then the oldest entries over the count
+    /// and script size limits
+    ///
+    /// # Returns
+    /// The number of entries removed
+    pub fn prune(&mut self, limits: HistoryLimits) -> usize {
+        let before = self.entries.len();
+        let cutoff = now_millis().saturating_sub(limits.max_age_days * MILLIS_PER_DAY);
+
+        self.entries.retain(|entry| entry.timestamp >= cutoff);
+        if self.entries.len() > limits.max_entries {
+            let excess = self.entries.len() - limits.max_entries;
+            self.entries.drain(..excess);
+        }
+
+        let mut script_bytes: usize = self.entries.iter().map(|entry| entry.script.len()).sum();
+        let mut excess = 0;
+        for entry in &self.entries {
+            if script_bytes <= limits.max_script_bytes {
+                break;
+            }
+            script_bytes -= entry.script.len();
+            excess += 1;
+        }
+        self.entries.drain(..excess);
+
+        before - self.entries.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.save();
+    }
+
+    /// Write the entries to disk from a background task
+    ///
+    /// Snapshots are taken in order while the caller holds the history lock, and are
+    /// serialized in the background task. A write is skipped if a newer snapshot has
+    /// already been handed off.
+    pub fn save(&self) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+
+        let entries = self.entries.clone();
+        let generation = SAVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut written = WRITTEN_GENERATION
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if *written > generation || SAVE_GENERATION.load(Ordering::SeqCst) > generation {
+                return;
+            }
+
+            let entries: Vec<&HistoryEntry> = entries.iter().map(Arc::as_ref).collect();
+            let json = match serde_json::to_string(&entries) {
+                Ok(json) => json,
+                Err(e) => {
+                    log::error!("Failed to serialize execution history: {}", e);
+                    return;
+                }
+            };
+            if let Err(e) = fs::write(&path, json) {
+                log::error!("Failed to save execution history: {}", e);
+            }
+            *written = generation;
+        });
+    }
+}
+
+/// A script about to be sent to clients
+pub struct Execution {
+    pub source: ExecutionSource,
+    pub client_ids: Vec<String>,
+    pub payload: ScriptPayload,
+    pub rerun_of: Option<String>,
+}
+
+/// Broadcast a script to its target clients and record it in the history
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - IDs of the clients the script was sent to
+/// * `Err(String)` - If the script could not be broadcast
+pub async fn execute(app: &AppHandle, execution: Execution) -> Result<Vec<String>, String> {
+    let id = execution.payload.id.clone();
+
+    // Track before sending, so a client that ends the script straight away is heard
+    executions::track(app, &id, execution.source, &execution.client_ids).await;
+
+    let clients = app.state::<ClientRegistry>().inner().clone();
+    let result = websocket::broadcast_to_clients(
+        execution.client_ids.clone(),
+        execution.payload.clone(),
+        clients,
+    )
+    .await;
+
+    let undelivered: Vec<String> = match &result {
+        Ok(delivered) => execution
+            .client_ids
+            .iter()
+            .filter(|client_id| !delivered.contains(client_id))
+            .cloned()
+            .collect(),
+        Err(_) => execution.client_ids.clone(),
+    };
+    executions::untrack(app, &id, &undelivered).await;
+
+    record(app, execution, &result).await;
+    result
+}
+
+/// Record an execution and its result in the history
+pub async fn record(app: &AppHandle, execution: Execution, result: &Result<Vec<String>, String>) {
+    let usernames = {
+        let clients = app.state::<ClientRegistry>();
+        let clients = clients.read().await;
+        execution
+            .client_ids
+            .iter()
+            .filter_map(|id| clients.get(id).map(|info| info.username.clone()))
+            .collect()
+    };
+
+    let (delivered, error) = match result {
+        Ok(delivered) => (delivered.clone(), None),
+        Err(e) => (Vec::new(), Some(e.clone())),
+    };
+
+    let outcome = if error.is_some() || (delivered.is_empty() && !execution.client_ids.is_empty()) {
+        ExecutionOutcome::Failed
+    } else if delivered.len() < execution.client_ids.len() {
+        ExecutionOutcome::Partial
+    } else {
+        ExecutionOutcome::Sent
+    };
+
+    let entry = HistoryEntry {
+        id: execution.payload.id.clone(),
+        timestamp: now_millis(),
+        source: execution.source,
+        client_ids: execution.client_ids,
+        usernames,
+        delivered,
+        script_hash: hash_script(&execution.payload.script),
+        script: execution.payload.script,
+        redirect: execution.payload.redirect,
+        line_map: execution.payload.line_map,
+        template: execution.payload.template,
+        args: execution.payload.args,
+        outcome,
+        error,
+        rerun_of: execution.rerun_of,
+    };
+
+    let limits = HistoryLimits::load(app);
+    app.state::<HistoryState>()
+        .write()
+        .await
+        .push(entry, limits);
+}
+
+/// SHA-256 of a script as lowercase hex
+pub fn hash_script(script: &str) -> String {
+    format!("{:x}", Sha256::digest(script.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, script: &str, timestamp: u64) -> Arc<HistoryEntry> {
+        Arc::new(HistoryEntry {
+            id: id.to_string(),
+            timestamp,
+            source: ExecutionSource::Editor,
+            client_ids: Vec::new(),
+            usernames: Vec::new(),
+            delivered: Vec::new(),
+            script_hash: hash_script(script),
+            script: script.to_string(),
+            redirect: false,
+            line_map: None,
+            template: None,
+            args: None,
+            outcome: ExecutionOutcome::Sent,
+            error: None,
+            rerun_of: None,
+        })
+    }
+
+    #[test]
+    fn test_prune() {
+        let now = now_millis();
+        let mut history = ExecutionHistory {
+            path: None,
+            entries: vec![
+                entry("old", "print(0)", now - 2 * MILLIS_PER_DAY),
+                entry("a", "print(1)", now),
+                entry("b", "print(22)", now),
+                entry("c", "print(333)", now),
+                entry("d", "print(4444)", now),
+            ],
+        };
+        let limits = HistoryLimits {
+            max_entries: 3,
+            max_age_days: 1,
+            max_script_bytes: 22,
+        };
+
+        // "old" is past the age limit, "a" is over the count limit and "b" puts the
+        // remaining scripts over 22 bytes
+        assert_eq!(history.prune(limits), 3);
+        let ids: Vec<_> = history.iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(ids, vec!["d", "c"]);
+    }
+}
@@ -0,0 +1,320 @@
+//! Versioned REST API under `/v1`
+//!
+//! Unlike the legacy `/execute` and `/execute_file` endpoints, which hand scripts to the
+//! frontend, scripts sent here are dispatched by the backend, so they run even when the
+//! window is minimised or the frontend hasn't loaded.
+
+use crate::models::{AttachedClient, ClientSelector, ExecutionSource, ScriptPayload};
+use crate::services::history::{self, Execution};
+use crate::services::workspace::Workspace;
+use crate::services::{diagnostics, executions, status};
+use crate::state::{ClientRegistry, ConnectionCounter, ConnectionGuard};
+use crate::utils::clients::resolve_selector;
+use crate::utils::settings::{get_execution_settings, get_settings_section};
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tokio::sync::watch;
+use warp::filters::BoxedFilter;
+use warp::http::StatusCode;
+use warp::reply::{json, Response};
+use warp::{Filter, Reply};
+
+use super::events::{self, EventsQuery};
+use super::{error_reply, inspection, routes, Follow};
+
+/// JSON body for `POST /v1/execute`
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct ApiExecuteRequest {
+    /// Script to run; give either this or `file`
+    #[serde(default)]
+    script: Option<String>,
+    /// Script file to run, relative to `scripts/`
+    #[serde(default)]
+    file: Option<String>,
+    /// Clients to run on; every attached client if omitted
+    #[serde(default)]
+    target: ClientSelector,
+    /// Redirect `print`/`warn`/`error` to Proxima; defaults to the execution setting
+    #[serde(default)]
+    redirect: Option<bool>,
+    /// Passed to the script as `...`; an array is passed as separate values
+    #[serde(default)]
+    args: Option<Value>,
+    /// Refuse scripts that don't parse
+    #[serde(default)]
+    check: bool,
+}
+
+/// Query options for `POST /v1/execute`
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct ApiExecuteQuery {
+    /// Stream the execution's events and output back as newline-delimited JSON
+    #[serde(default)]
+    stream: bool,
+    /// Respond once the script has ended on every client, with the result from each
+    #[serde(default)]
+    wait: bool,
+    /// Seconds to wait for with `wait`
+    timeout: Option<u64>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(super) struct ApiExecuteResponse {
+    success: bool,
+    execution_id: String,
+    /// IDs of the clients the script was sent to
+    client_ids: Vec<String>,
+}
+
+/// Build the `/v1` routes
+///
+/// Requests are counted as connections of the HTTP server while they are handled.
+pub(super) fn routes(
+    app: AppHandle,
+    connections: ConnectionCounter,
+    shutdown: watch::Receiver<bool>,
+) -> BoxedFilter<(Response,)> {
+    let inspection_routes = inspection::routes(app.clone(), connections.clone());
+    let with_app = warp::any().map(move || app.clone());
+    let tracked = warp::any().map(move || connections.track());
+
+    // GET /v1/clients
+    let clients_route = routes::V1_CLIENTS
+        .filter()
+        .and(with_app.clone())
+        .and(tracked.clone())
+        .and_then(|app: AppHandle, connection: ConnectionGuard| async move {
+            let _connection = connection;
+            handle_clients(app).await
+        });
+
+    // POST /v1/execute
+    let execute_route =
+        routes::V1_EXECUTE
+            .filter()
+            .and(warp::query::<ApiExecuteQuery>())
+            .and(warp::body::bytes())
+            .and(with_app.clone())
+            .and(tracked.clone())
+            .and_then(
+                |query: ApiExecuteQuery,
+                 body: Bytes,
+                 app: AppHandle,
+                 connection: ConnectionGuard| async move {
+                    let _connection = connection;
+                    handle_execute(query, body, app).await
+                },
+            );
+
+    // GET /v1/executions/{id}
+    let execution_route = routes::V1_EXECUTION
+        .filter_param::<String>()
+        .and(with_app.clone())
+        .and(tracked.clone())
+        .and_then(
+            |id: String, app: AppHandle, connection: ConnectionGuard| async move {
+                let _connection = connection;
+                handle_execution(id, app).await
+            },
+        );
+
+    // GET /v1/events
+    let events_route = routes::V1_EVENTS
+        .filter()
+        .and(warp::query::<EventsQuery>())
+        .and(with_app.clone())
+        .and(tracked.clone())
+        .map(
+            move |query: EventsQuery, app: AppHandle, connection: ConnectionGuard| {
+                events::handle_events(query, app, connection, shutdown.clone())
+            },
+        );
+
+    // GET /v1/status
+    let status_route = routes::V1_STATUS
+        .filter()
+        .and(with_app)
+        .and(tracked)
+        .and_then(|app: AppHandle, connection: ConnectionGuard| async move {
+            let _connection = connection;
+            handle_status(app).await
+        });
+
+    clients_route
+        .or(execute_route)
+        .unify()
+        .or(execution_route)
+        .unify()
+        .or(events_route)
+        .unify()
+        .or(status_route)
+        .unify()
+        .or(inspection_routes)
+        .unify()
+        .boxed()
+}
+
+/// Handle `GET /v1/clients`
+async fn handle_clients(app: AppHandle) -> Result<Response, warp::Rejection> {
+    let registry = app.state::<ClientRegistry>();
+    let mut clients: Vec<AttachedClient> = registry
+        .read()
+        .await
+        .iter()
+        .map(|(id, info)| AttachedClient {
+            id: id.clone(),
+            username: info.username.clone(),
+            place_id: info.place_id,
+            game_id: info.game_id,
+            tags: info.tags.clone(),
+        })
+        .collect();
+    clients.sort_by(|a, b| a.username.cmp(&b.username));
+
+    Ok(json(&clients).into_response())
+}
+
+/// Handle `POST /v1/execute`
+///
+/// Sends the script to the target clients and returns the execution ID. With
+/// `?stream=true`, the response streams the execution's events and output instead; with
+/// `?wait=true`, it is sent once the script has ended, with the result from each client.
+async fn handle_execute(
+    query: ApiExecuteQuery,
+    body: Bytes,
+    app: AppHandle,
+) -> Result<Response, warp::Rejection> {
+    let enabled = get_settings_section(&app, "execution")
+        .and_then(|execution| {
+            execution
+                .get("httpRequestExecution")
+                .and_then(|v| v.as_bool())
+        })
+        .unwrap_or(false);
+
+    if !enabled {
+        log_ui!(
+            &app,
+            Warning,
+            "An HTTP script execution request was made, but HTTP request execution is disabled"
+        );
+        return Ok(error_reply(
+            StatusCode::FORBIDDEN,
+            "HTTP request execution is disabled".to_string(),
+        ));
+    }
+
+    let request = match serde_json::from_slice::<ApiExecuteRequest>(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            return Ok(error_reply(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid JSON body: {}", e),
+            ))
+        }
+    };
+
+    let script = match (request.script, request.file) {
+        (Some(script), None) => script,
+        (None, Some(file)) => {
+            match Workspace::load(&app)
+                .and_then(|workspace| workspace.resolve_script(&file))
+                .map_err(|e| e.to_string())
+                .and_then(|path| std::fs::read_to_string(path).map_err(|e| e.to_string()))
+            {
+                Ok(script) => script,
+                Err(e) => return Ok(error_reply(StatusCode::NOT_FOUND, e)),
+            }
+        }
+        _ => {
+            return Ok(error_reply(
+                StatusCode::BAD_REQUEST,
+                "Provide either `script` or `file`".to_string(),
+            ))
+        }
+    };
+
+    if script.trim().is_empty() {
+        return Ok(error_reply(
+            StatusCode::BAD_REQUEST,
+            "Script cannot be empty".to_string(),
+        ));
+    }
+
+    if request.check {
+        if let Err(e) = diagnostics::check_syntax(&script) {
+            return Ok(error_reply(StatusCode::UNPROCESSABLE_ENTITY, e));
+        }
+    }
+
+    let clients = app.state::<ClientRegistry>().inner().clone();
+    let targets = resolve_selector(&request.target, &clients).await;
+    if targets.is_empty() {
+        return Ok(error_reply(
+            StatusCode::NOT_FOUND,
+            "No attached clients match the target".to_string(),
+        ));
+    }
+
+    let redirect = request
+        .redirect
+        .unwrap_or_else(|| get_execution_settings(&app).1);
+    let mut payload = ScriptPayload::new(script, redirect);
+    payload.args = request.args;
+    let execution_id = payload.id.clone();
+
+    let follow = Follow::subscribe(&app, query.stream, query.wait, query.timeout).await;
+
+    log::info!(
+        "HTTP request received: v1 execute ({} client(s))",
+        targets.len()
+    );
+
+    let delivered = match history::execute(
+        &app,
+        Execution {
+            source: ExecutionSource::Http,
+            client_ids: targets,
+            payload,
+            rerun_of: None,
+        },
+    )
+    .await
+    {
+        Ok(delivered) => delivered,
+        Err(e) => return Ok(error_reply(StatusCode::BAD_GATEWAY, e)),
+    };
+
+    if let Some(follow) = follow {
+        if !delivered.is_empty() {
+            return Ok(follow.reply(app, execution_id).await);
+        }
+    }
+
+    Ok(json(&ApiExecuteResponse {
+        success: true,
+        execution_id,
+        client_ids: delivered,
+    })
+    .into_response())
+}
+
+/// Handle `GET /v1/executions/{id}`
+async fn handle_execution(id: String, app: AppHandle) -> Result<Response, warp::Rejection> {
+    match executions::details(&app, &id).await {
+        Some(details) => Ok(json(&details).into_response()),
+        None => Ok(error_reply(
+            StatusCode::NOT_FOUND,
+            format!("Execution not found: {}", id),
+        )),
+    }
+}
+
+/// Handle `GET /v1/status`
+async fn handle_status(app: AppHandle) -> Result<Response, warp::Rejection> {
+    let status = status::collect_status(&app).await;
+    Ok(json(&status).into_response())
+}
@@ -0,0 +1,172 @@
+//! Live backend events over Server-Sent Events, for `GET /v1/events`
+//!
+//! Streams the events the frontend receives (logs, client updates, remote spy calls,
+//! explorer changes, ...) so external tools can follow Proxima without its window.
+
+use crate::models::BackendEvent;
+use crate::state::{ClientRegistry, ConnectionGuard, EventBus};
+use futures_util::stream;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::convert::Infallible;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{broadcast, watch};
+use warp::reply::Response;
+use warp::sse::Event;
+use warp::Reply;
+
+/// Query options for `GET /v1/events`
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct EventsQuery {
+    /// Comma-separated event names; a trailing `*` matches a prefix, e.g. `explorer-*`
+    events: Option<String>,
+    /// Only events caused by this client, by ID or username
+    client: Option<String>,
+}
+
+/// Which events a subscriber wants
+struct EventFilter {
+    /// Empty for every event
+    names: Vec<String>,
+    client: Option<String>,
+}
+
+impl EventFilter {
+    fn new(query: EventsQuery) -> Self {
+        let names = query
+            .events
+            .unwrap_or_default()
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        Self {
+            names,
+            client: query.client.filter(|client| !client.is_empty()),
+        }
+    }
+
+    fn matches_name(&self, event: &str) -> bool {
+        self.names.is_empty()
+            || self.names.iter().any(|name| match name.strip_suffix('*') {
+                Some(prefix) => event.starts_with(prefix),
+                None => event == name,
+            })
+    }
+
+    /// Events that weren't caused by a client only match without a client filter
+    async fn matches_client(&self, app: &AppHandle, client_id: Option<&str>) -> bool {
+        let Some(client) = &self.client else {
+            return true;
+        };
+        let Some(client_id) = client_id else {
+            return false;
+        };
+
+        client_id == client
+            || app
+                .state::<ClientRegistry>()
+                .read()
+                .await
+                .get(client_id)
+                .is_some_and(|info| info.username.eq_ignore_ascii_case(client))
+    }
+}
+
+struct EventStream {
+    app: AppHandle,
+    events: broadcast::Receiver<BackendEvent>,
+    filter: EventFilter,
+    shutdown: watch::Receiver<bool>,
+    /// Counts the stream as an open connection of the HTTP server
+    _connection: ConnectionGuard,
+}
+
+impl EventStream {
+    /// Wait for the next event to send, or `None` once the server shuts down
+    async fn next_event(&mut self) -> Option<Event> {
+        loop {
+            let received = tokio::select! {
+                _ = self.shutdown.changed() => return None,
+                received = self.events.recv() => received,
+            };
+
+            match received {
+                Ok(event) => {
+                    if !self.filter.matches_name(&event.event)
+                        || !self
+                            .filter
+                            .matches_client(&self.app, event.client_id.as_deref())
+                            .await
+                    {
+                        continue;
+                    }
+
+                    match Event::default()
+                        .event(event.event.clone())
+                        .json_data(&event)
+                    {
+                        Ok(sse_event) => return Some(sse_event),
+                        Err(e) => log::error!("Failed to serialize {} event: {}", event.event, e),
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    return Some(Event::default().event("lagged").data(skipped.to_string()));
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Handle `GET /v1/events`
+///
+/// Each event is sent with its name as the SSE event type and a `BackendEvent` as data.
+/// A `lagged` event reports how many events a slow subscriber missed.
+pub(super) fn handle_events(
+    query: EventsQuery,
+    app: AppHandle,
+    connection: ConnectionGuard,
+    shutdown: watch::Receiver<bool>,
+) -> Response {
+    let events = app.state::<EventBus>().subscribe();
+    let state = EventStream {
+        app,
+        events,
+        filter: EventFilter::new(query),
+        shutdown,
+        _connection: connection,
+    };
+
+    let events = stream::unfold(state, |mut state| async move {
+        let event = state.next_event().await?;
+        Some((Ok::<_, Infallible>(event), state))
+    });
+
+    warp::sse::reply(warp::sse::keep_alive().stream(events)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_name() {
+        let filter = EventFilter::new(EventsQuery {
+            events: Some("log-message, explorer-*".to_string()),
+            client: None,
+        });
+
+        assert!(filter.matches_name("log-message"));
+        assert!(filter.matches_name("explorer-tree-changed"));
+        assert!(!filter.matches_name("remote-spy-call"));
+
+        let all = EventFilter::new(EventsQuery {
+            events: None,
+            client: Some(String::new()),
+        });
+        assert!(all.matches_name("clients-update"));
+        assert!(all.client.is_none());
+    }
+}
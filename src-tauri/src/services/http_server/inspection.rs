@@ -0,0 +1,278 @@
+//! Explorer and remote spy endpoints under `/v1`
+//!
+//! The equivalents of the `exp_*` and `rspy_*` commands. Instead of emitting the client's
+//! reply as an event, each request waits for it and returns it as the response.
+
+use crate::services::{explorer, remote_spy};
+use crate::state::{ConnectionCounter, ConnectionGuard};
+use crate::utils::clients::ClientRequestError;
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use warp::filters::BoxedFilter;
+use warp::http::StatusCode;
+use warp::reply::{json, Response};
+use warp::{Filter, Rejection, Reply};
+
+use super::{error_reply, routes, SuccessResponse};
+
+/// JSON body for starting a session
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct StartRequest {
+    client_id: String,
+}
+
+/// JSON body for `POST /v1/explorer/tree`
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct TreeRequest {
+    /// Instances whose children should be included
+    #[serde(default)]
+    expanded_ids: Vec<u32>,
+}
+
+/// JSON body for `POST /v1/explorer/properties`
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct PropertiesRequest {
+    id: u32,
+    class_name: String,
+}
+
+/// JSON body for `POST /v1/explorer/search`
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct SearchRequest {
+    query: String,
+    /// What to match the query against, as in the explorer search box
+    search_by: String,
+    limit: u32,
+}
+
+/// JSON body for `POST /v1/explorer/decompile`
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct DecompileRequest {
+    id: u32,
+}
+
+/// Build the explorer and remote spy routes
+pub(super) fn routes(app: AppHandle, connections: ConnectionCounter) -> BoxedFilter<(Response,)> {
+    let context = warp::any()
+        .map(move || app.clone())
+        .and(warp::any().map(move || connections.track()));
+
+    // POST /v1/explorer/start
+    let explorer_start = routes::EXPLORER_START
+        .filter()
+        .and(warp::body::bytes())
+        .and(context.clone())
+        .and_then(
+            |body: Bytes, app: AppHandle, connection: ConnectionGuard| async move {
+                let _connection = connection;
+                let request: StartRequest = match parse_body(&body) {
+                    Ok(request) => request,
+                    Err(e) => return Ok::<_, Rejection>(error_reply(StatusCode::BAD_REQUEST, e)),
+                };
+                let result = explorer::start(&app, &request.client_id).await;
+                Ok(session_reply(result, "Explorer started"))
+            },
+        );
+
+    // POST /v1/explorer/stop
+    let explorer_stop = routes::EXPLORER_STOP
+        .filter()
+        .and(context.clone())
+        .and_then(|app: AppHandle, connection: ConnectionGuard| async move {
+            let _connection = connection;
+            Ok::<_, Rejection>(session_reply(
+                explorer::stop(&app).await,
+                "Explorer stopped",
+            ))
+        });
+
+    // POST /v1/explorer/tree
+    let explorer_tree = routes::EXPLORER_TREE
+        .filter()
+        .and(warp::body::bytes())
+        .and(context.clone())
+        .and_then(
+            |body: Bytes, app: AppHandle, connection: ConnectionGuard| async move {
+                let _connection = connection;
+                let request: TreeRequest = match parse_body(&body) {
+                    Ok(request) => request,
+                    Err(e) => return Ok::<_, Rejection>(error_reply(StatusCode::BAD_REQUEST, e)),
+                };
+                Ok(result_reply(
+                    explorer::request_tree(&app, request.expanded_ids).await,
+                ))
+            },
+        );
+
+    // POST /v1/explorer/properties
+    let explorer_properties = routes::EXPLORER_PROPERTIES
+        .filter()
+        .and(warp::body::bytes())
+        .and(context.clone())
+        .and_then(
+            |body: Bytes, app: AppHandle, connection: ConnectionGuard| async move {
+                let _connection = connection;
+                let request: PropertiesRequest = match parse_body(&body) {
+                    Ok(request) => request,
+                    Err(e) => return Ok::<_, Rejection>(error_reply(StatusCode::BAD_REQUEST, e)),
+                };
+                Ok(result_reply(
+                    explorer::request_properties(&app, request.id, &request.class_name).await,
+                ))
+            },
+        );
+
+    // POST /v1/explorer/search
+    let explorer_search = routes::EXPLORER_SEARCH
+        .filter()
+        .and(warp::body::bytes())
+        .and(context.clone())
+        .and_then(
+            |body: Bytes, app: AppHandle, connection: ConnectionGuard| async move {
+                let _connection = connection;
+                let request: SearchRequest = match parse_body(&body) {
+                    Ok(request) => request,
+                    Err(e) => return Ok::<_, Rejection>(error_reply(StatusCode::BAD_REQUEST, e)),
+                };
+                Ok(result_reply(
+                    explorer::request_search(&app, request.query, request.search_by, request.limit)
+                        .await,
+                ))
+            },
+        );
+
+    // POST /v1/explorer/decompile
+    let explorer_decompile = routes::EXPLORER_DECOMPILE
+        .filter()
+        .and(warp::body::bytes())
+        .and(context.clone())
+        .and_then(
+            |body: Bytes, app: AppHandle, connection: ConnectionGuard| async move {
+                let _connection = connection;
+                let request: DecompileRequest = match parse_body(&body) {
+                    Ok(request) => request,
+                    Err(e) => return Ok::<_, Rejection>(error_reply(StatusCode::BAD_REQUEST, e)),
+                };
+                Ok(result_reply(
+                    explorer::request_decompile(&app, request.id).await,
+                ))
+            },
+        );
+
+    // POST /v1/remote-spy/start
+    let remote_spy_start = routes::REMOTE_SPY_START
+        .filter()
+        .and(warp::body::bytes())
+        .and(context.clone())
+        .and_then(
+            |body: Bytes, app: AppHandle, connection: ConnectionGuard| async move {
+                let _connection = connection;
+                let request: StartRequest = match parse_body(&body) {
+                    Ok(request) => request,
+                    Err(e) => return Ok::<_, Rejection>(error_reply(StatusCode::BAD_REQUEST, e)),
+                };
+                let result = remote_spy::start(&app, &request.client_id).await;
+                Ok(session_reply(result, "Remote spy started"))
+            },
+        );
+
+    // POST /v1/remote-spy/stop
+    let remote_spy_stop = routes::REMOTE_SPY_STOP
+        .filter()
+        .and(context.clone())
+        .and_then(|app: AppHandle, connection: ConnectionGuard| async move {
+            let _connection = connection;
+            Ok::<_, Rejection>(session_reply(
+                remote_spy::stop(&app).await,
+                "Remote spy stopped",
+            ))
+        });
+
+    // GET /v1/remote-spy/calls/{call_id}/decompile
+    let remote_spy_decompile = routes::REMOTE_SPY_DECOMPILE
+        .filter_param::<u32>()
+        .and(context.clone())
+        .and_then(
+            |call_id: u32, app: AppHandle, connection: ConnectionGuard| async move {
+                let _connection = connection;
+                Ok::<_, Rejection>(result_reply(
+                    remote_spy::request_decompile(&app, call_id).await,
+                ))
+            },
+        );
+
+    // GET /v1/remote-spy/calls/{call_id}/code
+    let remote_spy_code = routes::REMOTE_SPY_CODE
+        .filter_param::<u32>()
+        .and(context)
+        .and_then(
+            |call_id: u32, app: AppHandle, connection: ConnectionGuard| async move {
+                let _connection = connection;
+                Ok::<_, Rejection>(result_reply(
+                    remote_spy::request_generated_code(&app, call_id).await,
+                ))
+            },
+        );
+
+    explorer_start
+        .or(explorer_stop)
+        .unify()
+        .or(explorer_tree)
+        .unify()
+        .or(explorer_properties)
+        .unify()
+        .or(explorer_search)
+        .unify()
+        .or(explorer_decompile)
+        .unify()
+        .or(remote_spy_start)
+        .unify()
+        .or(remote_spy_stop)
+        .unify()
+        .or(remote_spy_decompile)
+        .unify()
+        .or(remote_spy_code)
+        .unify()
+        .boxed()
+}
+
+/// Parse a JSON request body
+fn parse_body<T: DeserializeOwned>(body: &Bytes) -> Result<T, String> {
+    serde_json::from_slice(body).map_err(|e| format!("Invalid JSON body: {}", e))
+}
+
+/// Reply to a session start or stop
+fn session_reply(result: Result<(), ClientRequestError>, message: &str) -> Response {
+    match result {
+        Ok(()) => json(&SuccessResponse {
+            success: true,
+            message: message.to_string(),
+            execution_id: None,
+        })
+        .into_response(),
+        Err(e) => request_error_reply(e),
+    }
+}
+
+/// Reply with a client's reply to a request
+fn result_reply<T: Serialize>(result: Result<T, ClientRequestError>) -> Response {
+    match result {
+        Ok(reply) => json(&reply).into_response(),
+        Err(e) => request_error_reply(e),
+    }
+}
+
+/// Reply with the status code for a failed client request
+fn request_error_reply(error: ClientRequestError) -> Response {
+    let status = match error {
+        ClientRequestError::ClientNotFound(_) => StatusCode::NOT_FOUND,
+        ClientRequestError::NotRunning(_) | ClientRequestError::InUse(_) => StatusCode::CONFLICT,
+        ClientRequestError::SendFailed(_) => StatusCode::BAD_GATEWAY,
+        ClientRequestError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+        ClientRequestError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    error_reply(status, error.to_string())
+}
@@ -0,0 +1,609 @@
+use crate::models::ExecutionEvent;
+use crate::services::{diagnostics, executions, formatter, status};
+use crate::state::{ConnectionCounter, ExecutionsState};
+use bytes::Bytes;
+use futures_util::stream;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json as json_value, Value};
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{broadcast, watch};
+use tokio::time::Instant;
+use uuid::Uuid;
+use warp::http::{header, Response, StatusCode};
+use warp::hyper::Body;
+use warp::reply::{json, with_status};
+use warp::{Filter, Reply};
+
+mod api;
+mod events;
+mod inspection;
+mod openapi;
+mod routes;
+
+/// How long a streamed execution may take to reach a client before the stream gives up
+const STREAM_START_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long `?wait=true` waits by default, and at most, in seconds
+const DEFAULT_WAIT_TIMEOUT_SECS: u64 = 30;
+const MAX_WAIT_TIMEOUT_SECS: u64 = 600;
+
+#[derive(Serialize, Deserialize, Clone)]
+struct HttpExecutePayload {
+    script: String,
+    source: String, // "http_file" or "http_direct"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    args: Option<Value>,
+    /// Execution ID the frontend runs the script with
+    execution_id: String,
+}
+
+/// JSON body for /execute, sent with `Content-Type: application/json`
+#[derive(Deserialize, JsonSchema)]
+struct ExecuteBody {
+    script: String,
+    /// Passed to the script as `...`
+    #[serde(default)]
+    args: Option<Value>,
+}
+
+/// Query options for /execute
+#[derive(Deserialize, JsonSchema)]
+struct ExecuteQuery {
+    /// Refuse scripts that don't parse
+    #[serde(default)]
+    check: bool,
+    /// Stream the execution's events and output back as newline-delimited JSON
+    #[serde(default)]
+    stream: bool,
+    /// Respond once the script has ended on every client, with the result from each
+    #[serde(default)]
+    wait: bool,
+    /// Seconds to wait for with `wait`
+    timeout: Option<u64>,
+}
+
+/// Query options for /format
+#[derive(Deserialize, JsonSchema)]
+struct FormatQuery {
+    /// Path of the script relative to the Proxima folder, to pick the closest style config
+    path: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct FormatResponse {
+    success: bool,
+    script: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct SuccessResponse {
+    success: bool,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    execution_id: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ErrorResponse {
+    success: bool,
+    error: String,
+}
+
+/// Reply with an `ErrorResponse`
+fn error_reply(status: StatusCode, error: String) -> warp::reply::Response {
+    with_status(
+        json(&ErrorResponse {
+            success: false,
+            error,
+        }),
+        status,
+    )
+    .into_response()
+}
+
+/// Start the HTTP server
+///
+/// Binds the server and spawns it; it finishes in-flight requests and stops once
+/// `shutdown` is signalled. In-flight requests are counted as the server's connections.
+pub async fn start_http_server(
+    app_handle: AppHandle,
+    port: u16,
+    connections: ConnectionCounter,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<(SocketAddr, JoinHandle<()>), Box<dyn std::error::Error>> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+
+    // /execute_file endpoint
+    let app_execute_file = app_handle.clone();
+    let connections_execute_file = connections.clone();
+    let execute_file_route = routes::EXECUTE_FILE
+        .filter()
+        .and(warp::body::bytes())
+        .and_then(move |body: Bytes| {
+            let app = app_execute_file.clone();
+            let connection = connections_execute_file.track();
+            async move {
+                let _connection = connection;
+                handle_execute_file(body, app).await
+            }
+        });
+
+    // /execute endpoint
+    let app_execute = app_handle.clone();
+    let connections_execute = connections.clone();
+    let execute_route = routes::EXECUTE
+        .filter()
+        .and(warp::query::<ExecuteQuery>())
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::body::bytes())
+        .and_then(
+            move |query: ExecuteQuery, content_type: Option<String>, body: Bytes| {
+                let app = app_execute.clone();
+                let connection = connections_execute.track();
+                async move {
+                    let _connection = connection;
+                    handle_execute(query, content_type, body, app).await
+                }
+            },
+        );
+
+    // /format endpoint
+    let app_format = app_handle.clone();
+    let connections_format = connections.clone();
+    let format_route = routes::FORMAT
+        .filter()
+        .and(warp::query::<FormatQuery>())
+        .and(warp::body::bytes())
+        .and_then(move |query: FormatQuery, body: Bytes| {
+            let app = app_format.clone();
+            let connection = connections_format.track();
+            async move {
+                let _connection = connection;
+                handle_format(query, body, app).await
+            }
+        });
+
+    // Versioned REST API
+    let api_routes = api::routes(app_handle.clone(), connections.clone(), shutdown.clone());
+
+    // /status endpoint
+    let app_status = app_handle.clone();
+    let status_route = routes::STATUS.filter().and_then(move || {
+        let app = app_status.clone();
+        let connection = connections.track();
+        async move {
+            let _connection = connection;
+            handle_status(app).await
+        }
+    });
+
+    // /openapi.json endpoint
+    let openapi_route = routes::OPENAPI.filter().map(|| json(&*openapi::DOCUMENT));
+
+    let routes = execute_file_route
+        .or(execute_route)
+        .or(format_route)
+        .or(status_route)
+        .or(openapi_route)
+        .or(api_routes);
+
+    let (local_addr, server) =
+        warp::serve(routes).try_bind_with_graceful_shutdown(addr, async move {
+            let _ = shutdown.changed().await;
+        })?;
+
+    log_ui!(
+        &app_handle,
+        Success,
+        "HTTP server started on port {}",
+        local_addr.port()
+    );
+
+    let task = tauri::async_runtime::spawn(server);
+
+    Ok((local_addr, task))
+}
+
+/// Handle /execute_file endpoint
+async fn handle_execute_file(body: Bytes, app: AppHandle) -> Result<impl Reply, warp::Rejection> {
+    let path_str = String::from_utf8_lossy(&body).trim().to_string();
+
+    if path_str.is_empty() {
+        return Ok(with_status(
+            json(&ErrorResponse {
+                success: false,
+                error: "File path cannot be empty".to_string(),
+            }),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    // Parse path (no security restrictions - allow any file path)
+    let file_path = Path::new(&path_str);
+
+    // Check if file exists
+    if !file_path.exists() {
+        return Ok(with_status(
+            json(&ErrorResponse {
+                success: false,
+                error: format!("File not found: {}", path_str),
+            }),
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    // Validate extension
+    if let Err(e) = validate_extension(file_path) {
+        return Ok(with_status(
+            json(&ErrorResponse {
+                success: false,
+                error: e,
+            }),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    // Read file content
+    let script = match std::fs::read_to_string(&file_path) {
+        Ok(content) => content,
+        Err(e) => {
+            return Ok(with_status(
+                json(&ErrorResponse {
+                    success: false,
+                    error: format!("Failed to read file: {}", e),
+                }),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    };
+
+    // Emit event to frontend
+    let execution_id = Uuid::new_v4().to_string();
+    let payload = HttpExecutePayload {
+        script,
+        source: "http_file".to_string(),
+        args: None,
+        execution_id: execution_id.clone(),
+    };
+
+    if let Err(e) = app.emit("http-execute-script", payload) {
+        log::error!("Failed to emit http-execute-script event: {}", e);
+        return Ok(with_status(
+            json(&ErrorResponse {
+                success: false,
+                error: "Internal server error".to_string(),
+            }),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ));
+    }
+
+    log::info!("HTTP request received: execute_file ({})", path_str);
+
+    Ok(with_status(
+        json(&SuccessResponse {
+            success: true,
+            message: "Script sent for execution".to_string(),
+            execution_id: Some(execution_id),
+        }),
+        StatusCode::OK,
+    ))
+}
+
+/// Handle /execute endpoint
+///
+/// The body is either the raw script, or a JSON `{ "script", "args" }` object when sent
+/// as `application/json`. With `?stream=true`, the response streams the execution's
+/// events and output until it has ended on every client it was sent to; with `?wait=true`,
+/// it is sent once the execution has ended, with the result from each client.
+async fn handle_execute(
+    query: ExecuteQuery,
+    content_type: Option<String>,
+    body: Bytes,
+    app: AppHandle,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let is_json = content_type.is_some_and(|c| c.starts_with("application/json"));
+
+    let (script, args) = if is_json {
+        match serde_json::from_slice::<ExecuteBody>(&body) {
+            Ok(body) => (body.script.trim().to_string(), body.args),
+            Err(e) => {
+                return Ok(with_status(
+                    json(&ErrorResponse {
+                        success: false,
+                        error: format!("Invalid JSON body: {}", e),
+                    }),
+                    StatusCode::BAD_REQUEST,
+                )
+                .into_response())
+            }
+        }
+    } else {
+        (String::from_utf8_lossy(&body).trim().to_string(), None)
+    };
+
+    if script.is_empty() {
+        return Ok(with_status(
+            json(&ErrorResponse {
+                success: false,
+                error: "Script cannot be empty".to_string(),
+            }),
+            StatusCode::BAD_REQUEST,
+        )
+        .into_response());
+    }
+
+    if query.check {
+        if let Err(e) = diagnostics::check_syntax(&script) {
+            return Ok(with_status(
+                json(&ErrorResponse {
+                    success: false,
+                    error: e,
+                }),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            )
+            .into_response());
+        }
+    }
+
+    // Emit event to frontend
+    let execution_id = Uuid::new_v4().to_string();
+    let payload = HttpExecutePayload {
+        script,
+        source: "http_direct".to_string(),
+        args,
+        execution_id: execution_id.clone(),
+    };
+
+    let follow = Follow::subscribe(&app, query.stream, query.wait, query.timeout).await;
+
+    if let Err(e) = app.emit("http-execute-script", payload) {
+        log::error!("Failed to emit http-execute-script event: {}", e);
+        return Ok(with_status(
+            json(&ErrorResponse {
+                success: false,
+                error: "Internal server error".to_string(),
+            }),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .into_response());
+    }
+
+    log::info!("HTTP request received: execute");
+
+    if let Some(follow) = follow {
+        return Ok(follow.reply(app, execution_id).await);
+    }
+
+    Ok(with_status(
+        json(&SuccessResponse {
+            success: true,
+            message: "Script sent for execution".to_string(),
+            execution_id: Some(execution_id),
+        }),
+        StatusCode::OK,
+    )
+    .into_response())
+}
+
+/// How a response follows the execution it started, instead of returning right away
+enum FollowMode {
+    Stream,
+    Wait(Duration),
+}
+
+/// A response that follows an execution
+struct Follow {
+    mode: FollowMode,
+    events: broadcast::Receiver<ExecutionEvent>,
+}
+
+impl Follow {
+    /// Start following an execution if the request asked to stream or wait
+    ///
+    /// Must be called before the script is sent so no events are missed.
+    /// Streaming takes precedence over waiting.
+    async fn subscribe(
+        app: &AppHandle,
+        stream: bool,
+        wait: bool,
+        timeout: Option<u64>,
+    ) -> Option<Self> {
+        let mode = if stream {
+            FollowMode::Stream
+        } else if wait {
+            let secs = timeout
+                .unwrap_or(DEFAULT_WAIT_TIMEOUT_SECS)
+                .min(MAX_WAIT_TIMEOUT_SECS);
+            FollowMode::Wait(Duration::from_secs(secs))
+        } else {
+            return None;
+        };
+
+        let events = app.state::<ExecutionsState>().read().await.subscribe();
+        Some(Self { mode, events })
+    }
+
+    async fn reply(self, app: AppHandle, execution_id: String) -> warp::reply::Response {
+        match self.mode {
+            FollowMode::Stream => stream_execution(app, execution_id, self.events),
+            FollowMode::Wait(timeout) => {
+                let result = executions::wait(&app, &execution_id, self.events, timeout).await;
+                let status = if result.timed_out {
+                    StatusCode::GATEWAY_TIMEOUT
+                } else {
+                    StatusCode::OK
+                };
+                with_status(json(&result), status).into_response()
+            }
+        }
+    }
+}
+
+/// State of a streamed execution response
+struct ExecutionStream {
+    app: AppHandle,
+    execution_id: String,
+    events: broadcast::Receiver<ExecutionEvent>,
+    /// Lines ready to be sent
+    pending: VecDeque<Value>,
+    /// Set once the execution reached a client; until then the stream times out
+    started: bool,
+    ended: bool,
+}
+
+impl ExecutionStream {
+    /// Wait for the next line to send, or `None` once the stream has ended
+    async fn next_line(&mut self) -> Option<Value> {
+        let deadline = Instant::now() + STREAM_START_TIMEOUT;
+
+        loop {
+            if let Some(line) = self.pending.pop_front() {
+                return Some(line);
+            }
+            if self.ended {
+                return None;
+            }
+
+            let event = if self.started {
+                self.events.recv().await
+            } else {
+                match tokio::time::timeout_at(deadline, self.events.recv()).await {
+                    Ok(event) => event,
+                    Err(_) => {
+                        self.end(json_value!({
+                            "type": "error",
+                            "error": "Script did not reach any client",
+                        }));
+                        continue;
+                    }
+                }
+            };
+
+            match event {
+                Ok(event) if event.execution_id() == self.execution_id => self.push(event).await,
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    self.pending
+                        .push_back(json_value!({ "type": "lagged", "skipped": skipped }));
+                }
+                Err(broadcast::error::RecvError::Closed) => self.ended = true,
+            }
+        }
+    }
+
+    async fn push(&mut self, event: ExecutionEvent) {
+        let done = matches!(event, ExecutionEvent::Done { .. });
+        if matches!(event, ExecutionEvent::Started { .. }) {
+            self.started = true;
+        }
+
+        match serde_json::to_value(&event) {
+            Ok(line) => self.pending.push_back(line),
+            Err(e) => log::error!("Failed to serialize execution event: {}", e),
+        }
+
+        if done {
+            let running = self
+                .app
+                .state::<ExecutionsState>()
+                .read()
+                .await
+                .is_running(&self.execution_id);
+            if !running {
+                self.end(json_value!({ "type": "end" }));
+            }
+        }
+    }
+
+    fn end(&mut self, line: Value) {
+        self.pending.push_back(line);
+        self.ended = true;
+    }
+}
+
+/// Stream an execution's events and output as newline-delimited JSON
+///
+/// Ends with an `end` line once the execution has ended on every client it reached, or an
+/// `error` line if it never reached one. Dropping the connection stops the stream.
+fn stream_execution(
+    app: AppHandle,
+    execution_id: String,
+    events: broadcast::Receiver<ExecutionEvent>,
+) -> warp::reply::Response {
+    let state = ExecutionStream {
+        app,
+        execution_id,
+        events,
+        pending: VecDeque::new(),
+        started: false,
+        ended: false,
+    };
+
+    let body = stream::unfold(state, |mut state| async move {
+        let line = state.next_line().await?;
+        let chunk = Bytes::from(format!("{}\n", line));
+        Some((Ok::<_, Infallible>(chunk), state))
+    });
+
+    let mut response = Response::new(Body::wrap_stream(body));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/x-ndjson"),
+    );
+    response
+}
+
+/// Handle /format endpoint
+async fn handle_format(
+    query: FormatQuery,
+    body: Bytes,
+    app: AppHandle,
+) -> Result<impl Reply, warp::Rejection> {
+    let script = String::from_utf8_lossy(&body).to_string();
+
+    match formatter::format_script(&app, &script, query.path.as_deref()) {
+        Ok(script) => Ok(with_status(
+            json(&FormatResponse {
+                success: true,
+                script,
+            }),
+            StatusCode::OK,
+        )),
+        Err(e) => Ok(with_status(
+            json(&ErrorResponse {
+                success: false,
+                error: e,
+            }),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        )),
+    }
+}
+
+/// Handle /status endpoint
+async fn handle_status(app: AppHandle) -> Result<impl Reply, warp::Rejection> {
+    let status = status::collect_status(&app).await;
+    Ok(with_status(json(&status), StatusCode::OK))
+}
+
+/// Validate file extension
+fn validate_extension(path: &Path) -> Result<(), String> {
+    let valid_extensions = ["lua", "luau", "txt"];
+
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => {
+            if valid_extensions.contains(&ext) {
+                Ok(())
+            } else {
+                Err(format!("Invalid file extension: .{}", ext))
+            }
+        }
+        None => Err("File has no extension".to_string()),
+    }
+}
@@ -0,0 +1,439 @@
+//! OpenAPI description of the HTTP API, served at `/openapi.json`
+//!
+//! Request and response schemas are generated from the Rust types the handlers use, so
+//! they can't drift from the server. Paths come from the route table the server mounts its
+//! handlers on, and each route names the function here that builds its operation.
+
+use crate::models::explorer::{
+    DecompiledScriptEvent, PropertiesEvent, SearchResultsEvent, TreeEvent,
+};
+use crate::models::remote_spy::{RemoteDecompiledEvent, RemoteGeneratedCodeEvent};
+use crate::models::{
+    AttachedClient, BackendEvent, BackendStatus, ExecutionDetails, ExecutionEvent, ExecutionResult,
+};
+use once_cell::sync::Lazy;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+use super::api::{ApiExecuteQuery, ApiExecuteRequest, ApiExecuteResponse};
+use super::events::EventsQuery;
+use super::inspection::{
+    DecompileRequest, PropertiesRequest, SearchRequest, StartRequest, TreeRequest,
+};
+use super::routes::ROUTES;
+use super::{
+    ErrorResponse, ExecuteBody, ExecuteQuery, FormatQuery, FormatResponse, SuccessResponse,
+};
+
+/// The document, generated on first request
+pub(super) static DOCUMENT: Lazy<Value> = Lazy::new(document);
+
+/// An OpenAPI document being built
+pub(super) struct Builder {
+    gen: SchemaGenerator,
+    paths: Map<String, Value>,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Self {
+            gen: SchemaSettings::openapi3().into_generator(),
+            paths: Map::new(),
+        }
+    }
+
+    /// Add an operation to a path
+    fn route(&mut self, method: &str, path: &str, operation: Value) {
+        let item = self
+            .paths
+            .entry(path.to_string())
+            .or_insert_with(|| json!({}));
+        item[method] = operation;
+    }
+
+    /// Reference to the schema of `T`
+    fn schema<T: JsonSchema>(&mut self) -> Value {
+        serde_json::to_value(self.gen.subschema_for::<T>()).unwrap_or(Value::Null)
+    }
+
+    /// A JSON request body of type `T`
+    fn json_body<T: JsonSchema>(&mut self) -> Value {
+        json!({
+            "required": true,
+            "content": { "application/json": { "schema": self.schema::<T>() } },
+        })
+    }
+
+    /// A response with a body of type `T`
+    fn response<T: JsonSchema>(&mut self, content_type: &str, description: &str) -> Value {
+        json!({
+            "description": description,
+            "content": { content_type: { "schema": self.schema::<T>() } },
+        })
+    }
+
+    /// A JSON response of type `T`
+    fn json_response<T: JsonSchema>(&mut self, description: &str) -> Value {
+        self.response::<T>("application/json", description)
+    }
+
+    /// Response of an execute endpoint: `T`, or streamed `ExecutionEvent` lines with `stream`
+    fn execute_response<T: JsonSchema>(&mut self, description: &str) -> Value {
+        let mut response = self.json_response::<T>(description);
+        response["content"]["application/x-ndjson"] =
+            json!({ "schema": self.schema::<ExecutionEvent>() });
+        response
+    }
+
+    /// An `ErrorResponse`
+    fn error(&mut self, description: &str) -> Value {
+        self.json_response::<ErrorResponse>(description)
+    }
+
+    /// Query parameters, one per field of `T`
+    fn query<T: JsonSchema>(&mut self) -> Vec<Value> {
+        let schema = serde_json::to_value(T::json_schema(&mut self.gen)).unwrap_or(Value::Null);
+        let required = schema["required"].as_array().cloned().unwrap_or_default();
+        let Some(properties) = schema["properties"].as_object() else {
+            return Vec::new();
+        };
+
+        properties
+            .iter()
+            .map(|(name, property)| {
+                let mut property = property.clone();
+                let description = property
+                    .as_object_mut()
+                    .and_then(|property| property.remove("description"))
+                    .unwrap_or(Value::Null);
+
+                let mut parameter = json!({
+                    "name": name,
+                    "in": "query",
+                    "required": required.contains(&Value::String(name.clone())),
+                    "schema": property,
+                });
+                if !description.is_null() {
+                    parameter["description"] = description;
+                }
+                parameter
+            })
+            .collect()
+    }
+
+    /// Finish the document
+    fn build(mut self) -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "Proxima",
+                "description": "Local HTTP API of Proxima. Every endpoint listens on 127.0.0.1 only.",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "paths": self.paths,
+            "components": { "schemas": self.gen.take_definitions() },
+        })
+    }
+}
+
+/// A path parameter
+fn path_parameter(name: &str, schema: Value, description: &str) -> Value {
+    json!({
+        "name": name,
+        "in": "path",
+        "required": true,
+        "schema": schema,
+        "description": description,
+    })
+}
+
+/// Generate the OpenAPI document for every route of the HTTP server
+fn document() -> Value {
+    let mut doc = Builder::new();
+    for route in ROUTES {
+        let mut operation = (route.operation)(&mut doc);
+        operation["summary"] = json!(route.summary);
+        doc.route(route.method.name(), route.path, operation);
+    }
+    doc.build()
+}
+
+/// A plain text request body
+fn plain_text(description: &str) -> Value {
+    json!({
+        "required": true,
+        "description": description,
+        "content": { "text/plain": { "schema": { "type": "string" } } },
+    })
+}
+
+// Legacy endpoints
+
+pub(super) fn execute(doc: &mut Builder) -> Value {
+    json!({
+        "parameters": doc.query::<ExecuteQuery>(),
+        "requestBody": {
+            "required": true,
+            "content": {
+                "text/plain": { "schema": { "type": "string" } },
+                "application/json": { "schema": doc.schema::<ExecuteBody>() },
+            },
+        },
+        "responses": {
+            "200": doc.execute_response::<SuccessResponse>(
+                "Script sent for execution. With `stream`, newline-delimited `ExecutionEvent`s \
+                 followed by an `end` or `error` line; with `wait`, an `ExecutionResult`",
+            ),
+            "400": doc.error("Empty script or invalid JSON body"),
+            "422": doc.error("The script doesn't parse (with `check`)"),
+            "504": doc.json_response::<ExecutionResult>("The wait timed out"),
+        },
+    })
+}
+
+pub(super) fn execute_file(doc: &mut Builder) -> Value {
+    json!({
+        "requestBody": plain_text("Path of a `.lua`, `.luau` or `.txt` file"),
+        "responses": {
+            "200": doc.json_response::<SuccessResponse>("Script sent for execution"),
+            "400": doc.error("Empty path or unsupported file extension"),
+            "404": doc.error("File not found"),
+        },
+    })
+}
+
+pub(super) fn format(doc: &mut Builder) -> Value {
+    json!({
+        "parameters": doc.query::<FormatQuery>(),
+        "requestBody": plain_text("The script to format"),
+        "responses": {
+            "200": doc.json_response::<FormatResponse>("The formatted script"),
+            "422": doc.error("The script doesn't parse"),
+        },
+    })
+}
+
+pub(super) fn status(doc: &mut Builder) -> Value {
+    json!({
+        "responses": {
+            "200": doc.json_response::<BackendStatus>("Running servers and per-client traffic"),
+        },
+    })
+}
+
+pub(super) fn openapi(_doc: &mut Builder) -> Value {
+    json!({
+        "responses": {
+            "200": {
+                "description": "OpenAPI 3 description of the HTTP API",
+                "content": { "application/json": { "schema": { "type": "object" } } },
+            },
+        },
+    })
+}
+
+// Versioned API
+
+pub(super) fn clients(doc: &mut Builder) -> Value {
+    json!({
+        "responses": {
+            "200": doc.json_response::<Vec<AttachedClient>>("Attached clients, by username"),
+        },
+    })
+}
+
+pub(super) fn api_execute(doc: &mut Builder) -> Value {
+    json!({
+        "parameters": doc.query::<ApiExecuteQuery>(),
+        "requestBody": doc.json_body::<ApiExecuteRequest>(),
+        "responses": {
+            "200": doc.execute_response::<ApiExecuteResponse>(
+                "Script sent to the target clients. With `stream`, newline-delimited \
+                 `ExecutionEvent`s followed by an `end` or `error` line; with `wait`, an \
+                 `ExecutionResult`",
+            ),
+            "400": doc.error("Invalid body, or an empty script"),
+            "403": doc.error("HTTP request execution is disabled"),
+            "404": doc.error("Script file not found, or no attached client matches the target"),
+            "422": doc.error("The script doesn't parse (with `check`)"),
+            "502": doc.error("The script couldn't be sent"),
+            "504": doc.json_response::<ExecutionResult>("The wait timed out"),
+        },
+    })
+}
+
+pub(super) fn execution(doc: &mut Builder) -> Value {
+    json!({
+        "parameters": [path_parameter("id", json!({ "type": "string" }), "Execution ID")],
+        "responses": {
+            "200": doc.json_response::<ExecutionDetails>("The execution on each client and its output"),
+            "404": doc.error("Execution not found"),
+        },
+    })
+}
+
+pub(super) fn events(doc: &mut Builder) -> Value {
+    json!({
+        "parameters": doc.query::<EventsQuery>(),
+        "responses": {
+            "200": doc.response::<BackendEvent>(
+                "text/event-stream",
+                "Server-Sent Events named after the backend event, with a `BackendEvent` as data. \
+                 A `lagged` event reports how many events were missed",
+            ),
+        },
+    })
+}
+
+// Explorer and remote spy
+
+pub(super) fn session_start(doc: &mut Builder) -> Value {
+    json!({
+        "requestBody": doc.json_body::<StartRequest>(),
+        "responses": {
+            "200": doc.json_response::<SuccessResponse>("Session started"),
+            "400": doc.error("Invalid JSON body"),
+            "404": doc.error("The client isn't attached"),
+            "409": doc.error("The session is running on another client"),
+            "502": doc.error("The client couldn't be sent the request"),
+        },
+    })
+}
+
+pub(super) fn session_stop(doc: &mut Builder) -> Value {
+    json!({
+        "responses": {
+            "200": doc.json_response::<SuccessResponse>("Session stopped"),
+        },
+    })
+}
+
+pub(super) fn explorer_tree(doc: &mut Builder) -> Value {
+    explorer_request::<TreeRequest, TreeEvent>(doc)
+}
+
+pub(super) fn explorer_properties(doc: &mut Builder) -> Value {
+    explorer_request::<PropertiesRequest, PropertiesEvent>(doc)
+}
+
+pub(super) fn explorer_search(doc: &mut Builder) -> Value {
+    explorer_request::<SearchRequest, SearchResultsEvent>(doc)
+}
+
+pub(super) fn explorer_decompile(doc: &mut Builder) -> Value {
+    explorer_request::<DecompileRequest, DecompiledScriptEvent>(doc)
+}
+
+pub(super) fn remote_spy_decompile(doc: &mut Builder) -> Value {
+    call_request::<RemoteDecompiledEvent>(doc)
+}
+
+pub(super) fn remote_spy_code(doc: &mut Builder) -> Value {
+    call_request::<RemoteGeneratedCodeEvent>(doc)
+}
+
+/// An explorer request that returns the client's reply
+fn explorer_request<Request: JsonSchema, Reply: JsonSchema>(doc: &mut Builder) -> Value {
+    let body = doc.json_body::<Request>();
+    let mut responses = client_reply_responses::<Reply>(doc);
+    responses["400"] = doc.error("Invalid JSON body");
+
+    json!({ "requestBody": body, "responses": responses })
+}
+
+/// A request about a captured remote spy call that returns the client's reply
+fn call_request<Reply: JsonSchema>(doc: &mut Builder) -> Value {
+    let call_id = path_parameter(
+        "call_id",
+        json!({ "type": "integer", "format": "uint32", "minimum": 0 }),
+        "ID of a captured call",
+    );
+    json!({
+        "parameters": [call_id],
+        "responses": client_reply_responses::<Reply>(doc),
+    })
+}
+
+/// Responses of a request answered by the explorer or remote spy client
+fn client_reply_responses<Reply: JsonSchema>(doc: &mut Builder) -> Value {
+    json!({
+        "200": doc.json_response::<Reply>("The client's reply"),
+        "404": doc.error("The session's client is no longer attached"),
+        "409": doc.error("No session is running"),
+        "502": doc.error("The client couldn't be sent the request"),
+        "504": doc.error("The client didn't reply in time"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_covers_routes() {
+        let doc = document();
+        let paths = doc["paths"].as_object().unwrap();
+        let operations: usize = paths
+            .values()
+            .map(|item| item.as_object().unwrap().len())
+            .sum();
+        assert_eq!(operations, ROUTES.len());
+
+        for route in ROUTES {
+            let operation = &doc["paths"][route.path][route.method.name()];
+            assert_eq!(operation["summary"], route.summary);
+
+            // Path parameters are documented under the names the path uses
+            let expected: Vec<&str> = route
+                .path
+                .split('/')
+                .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+                .collect();
+            let documented: Vec<&str> = operation["parameters"]
+                .as_array()
+                .map(|parameters| {
+                    parameters
+                        .iter()
+                        .filter(|parameter| parameter["in"] == "path")
+                        .filter_map(|parameter| parameter["name"].as_str())
+                        .collect()
+                })
+                .unwrap_or_default();
+            assert_eq!(documented, expected, "{}", route.path);
+        }
+    }
+
+    /// Collect every `$ref` in a JSON value
+    fn refs<'a>(value: &'a Value, found: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    found.push(reference);
+                }
+                map.values().for_each(|value| refs(value, found));
+            }
+            Value::Array(values) => values.iter().for_each(|value| refs(value, found)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_document_refs_resolve() {
+        let doc = document();
+        let schemas = doc["components"]["schemas"].as_object().unwrap();
+        assert!(schemas.contains_key("SuccessResponse"));
+        assert!(schemas.contains_key("ErrorResponse"));
+        assert!(doc["paths"]["/v1/executions/{id}"]["get"].is_object());
+
+        let mut found = Vec::new();
+        refs(&doc, &mut found);
+        assert!(!found.is_empty());
+        for reference in found {
+            let name = reference
+                .strip_prefix("#/components/schemas/")
+                .unwrap_or_else(|| panic!("unexpected reference: {}", reference));
+            assert!(schemas.contains_key(name), "missing schema: {}", name);
+        }
+    }
+}
@@ -0,0 +1,337 @@
+//! Route table of the HTTP server
+//!
+//! Every route is defined once here, with its method, path and OpenAPI operation. The server
+//! mounts each handler on its route's filter, and `/openapi.json` is generated from `ROUTES`.
+
+use serde_json::Value;
+use std::str::FromStr;
+use warp::filters::BoxedFilter;
+use warp::path::FullPath;
+use warp::{Filter, Rejection};
+
+use super::openapi::{self, Builder};
+
+/// HTTP method of a route
+#[derive(Clone, Copy)]
+pub(super) enum Method {
+    Get,
+    Post,
+}
+
+impl Method {
+    /// Name of the method as used in OpenAPI path items
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Method::Get => "get",
+            Method::Post => "post",
+        }
+    }
+
+    fn filter(self) -> BoxedFilter<()> {
+        match self {
+            Method::Get => warp::get().boxed(),
+            Method::Post => warp::post().boxed(),
+        }
+    }
+}
+
+/// A route of the HTTP server
+pub(super) struct Route {
+    pub(super) method: Method,
+    /// Path, with parameters written as `{name}`
+    pub(super) path: &'static str,
+    pub(super) summary: &'static str,
+    /// Builds the route's OpenAPI operation, apart from its summary
+    pub(super) operation: fn(&mut Builder) -> Value,
+}
+
+impl Route {
+    /// Filter for requests to this route
+    pub(super) fn filter(&'static self) -> BoxedFilter<()> {
+        self.matcher().map(|_| ()).untuple_one().boxed()
+    }
+
+    /// Filter for requests to this route, extracting its path parameter
+    ///
+    /// Requests whose parameter doesn't parse as `T` are rejected as not found.
+    pub(super) fn filter_param<T: FromStr + Send + 'static>(&'static self) -> BoxedFilter<(T,)> {
+        self.matcher()
+            .and_then(|parameters: Vec<String>| async move {
+                parameters
+                    .first()
+                    .and_then(|parameter| parameter.parse().ok())
+                    .ok_or_else(warp::reject::not_found)
+            })
+            .boxed()
+    }
+
+    /// Filter matching the path and then the method, extracting the path parameters
+    ///
+    /// The path is matched first so that a request with the wrong method is rejected with 405.
+    fn matcher(&'static self) -> impl Filter<Extract = (Vec<String>,), Error = Rejection> + Clone {
+        warp::path::full()
+            .and_then(move |path: FullPath| async move {
+                self.parameters(path.as_str())
+                    .ok_or_else(warp::reject::not_found)
+            })
+            .and(self.method.filter())
+    }
+
+    /// Match a request path against the route's path
+    ///
+    /// # Returns
+    ///
+    /// The values of the path parameters, in order, or `None` if the path doesn't match
+    fn parameters(&self, path: &str) -> Option<Vec<String>> {
+        let mut segments = path.split('/');
+        let mut parameters = Vec::new();
+
+        for expected in self.path.split('/') {
+            let segment = segments.next()?;
+            if expected.starts_with('{') && expected.ends_with('}') {
+                if segment.is_empty() {
+                    return None;
+                }
+                parameters.push(segment.to_string());
+            } else if segment != expected {
+                return None;
+            }
+        }
+
+        segments.next().is_none().then_some(parameters)
+    }
+}
+
+// Legacy endpoints
+
+pub(super) static EXECUTE: Route = Route {
+    method: Method::Post,
+    path: "/execute",
+    summary: "Run a script through the frontend",
+    operation: openapi::execute,
+};
+
+pub(super) static EXECUTE_FILE: Route = Route {
+    method: Method::Post,
+    path: "/execute_file",
+    summary: "Run a script file through the frontend",
+    operation: openapi::execute_file,
+};
+
+pub(super) static FORMAT: Route = Route {
+    method: Method::Post,
+    path: "/format",
+    summary: "Format a script",
+    operation: openapi::format,
+};
+
+pub(super) static STATUS: Route = Route {
+    method: Method::Get,
+    path: "/status",
+    summary: "Backend status",
+    operation: openapi::status,
+};
+
+pub(super) static OPENAPI: Route = Route {
+    method: Method::Get,
+    path: "/openapi.json",
+    summary: "This document",
+    operation: openapi::openapi,
+};
+
+// Versioned API
+
+pub(super) static V1_CLIENTS: Route = Route {
+    method: Method::Get,
+    path: "/v1/clients",
+    summary: "List attached clients",
+    operation: openapi::clients,
+};
+
+pub(super) static V1_EXECUTE: Route = Route {
+    method: Method::Post,
+    path: "/v1/execute",
+    summary: "Run a script on attached clients",
+    operation: openapi::api_execute,
+};
+
+pub(super) static V1_EXECUTION: Route = Route {
+    method: Method::Get,
+    path: "/v1/executions/{id}",
+    summary: "Get an execution",
+    operation: openapi::execution,
+};
+
+pub(super) static V1_EVENTS: Route = Route {
+    method: Method::Get,
+    path: "/v1/events",
+    summary: "Stream backend events",
+    operation: openapi::events,
+};
+
+pub(super) static V1_STATUS: Route = Route {
+    method: Method::Get,
+    path: "/v1/status",
+    summary: "Backend status",
+    operation: openapi::status,
+};
+
+// Explorer and remote spy
+
+pub(super) static EXPLORER_START: Route = Route {
+    method: Method::Post,
+    path: "/v1/explorer/start",
+    summary: "Start the explorer on a client",
+    operation: openapi::session_start,
+};
+
+pub(super) static EXPLORER_STOP: Route = Route {
+    method: Method::Post,
+    path: "/v1/explorer/stop",
+    summary: "Stop the explorer",
+    operation: openapi::session_stop,
+};
+
+pub(super) static EXPLORER_TREE: Route = Route {
+    method: Method::Post,
+    path: "/v1/explorer/tree",
+    summary: "Get the explorer tree with the given instances expanded",
+    operation: openapi::explorer_tree,
+};
+
+pub(super) static EXPLORER_PROPERTIES: Route = Route {
+    method: Method::Post,
+    path: "/v1/explorer/properties",
+    summary: "Get an instance's properties",
+    operation: openapi::explorer_properties,
+};
+
+pub(super) static EXPLORER_SEARCH: Route = Route {
+    method: Method::Post,
+    path: "/v1/explorer/search",
+    summary: "Search the explorer",
+    operation: openapi::explorer_search,
+};
+
+pub(super) static EXPLORER_DECOMPILE: Route = Route {
+    method: Method::Post,
+    path: "/v1/explorer/decompile",
+    summary: "Decompile a script instance",
+    operation: openapi::explorer_decompile,
+};
+
+pub(super) static REMOTE_SPY_START: Route = Route {
+    method: Method::Post,
+    path: "/v1/remote-spy/start",
+    summary: "Start the remote spy on a client",
+    operation: openapi::session_start,
+};
+
+pub(super) static REMOTE_SPY_STOP: Route = Route {
+    method: Method::Post,
+    path: "/v1/remote-spy/stop",
+    summary: "Stop the remote spy",
+    operation: openapi::session_stop,
+};
+
+pub(super) static REMOTE_SPY_DECOMPILE: Route = Route {
+    method: Method::Get,
+    path: "/v1/remote-spy/calls/{call_id}/decompile",
+    summary: "Decompile the script that made a captured call",
+    operation: openapi::remote_spy_decompile,
+};
+
+pub(super) static REMOTE_SPY_CODE: Route = Route {
+    method: Method::Get,
+    path: "/v1/remote-spy/calls/{call_id}/code",
+    summary: "Generate code that repeats a captured call",
+    operation: openapi::remote_spy_code,
+};
+
+/// Every route the server mounts
+pub(super) static ROUTES: &[&Route] = &[
+    &EXECUTE,
+    &EXECUTE_FILE,
+    &FORMAT,
+    &STATUS,
+    &OPENAPI,
+    &V1_CLIENTS,
+    &V1_EXECUTE,
+    &V1_EXECUTION,
+    &V1_EVENTS,
+    &V1_STATUS,
+    &EXPLORER_START,
+    &EXPLORER_STOP,
+    &EXPLORER_TREE,
+    &EXPLORER_PROPERTIES,
+    &EXPLORER_SEARCH,
+    &EXPLORER_DECOMPILE,
+    &REMOTE_SPY_START,
+    &REMOTE_SPY_STOP,
+    &REMOTE_SPY_DECOMPILE,
+    &REMOTE_SPY_CODE,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_parameters() {
+        assert_eq!(STATUS.parameters("/status"), Some(vec![]));
+        assert_eq!(STATUS.parameters("/status/extra"), None);
+        assert_eq!(STATUS.parameters("/v1/status"), None);
+        assert_eq!(
+            V1_EXECUTION.parameters("/v1/executions/abc"),
+            Some(vec!["abc".to_string()])
+        );
+        assert_eq!(V1_EXECUTION.parameters("/v1/executions/"), None);
+        assert_eq!(
+            REMOTE_SPY_CODE.parameters("/v1/remote-spy/calls/7/code"),
+            Some(vec!["7".to_string()])
+        );
+        assert_eq!(
+            REMOTE_SPY_CODE.parameters("/v1/remote-spy/calls/7/decompile"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_routes_unique() {
+        let mut seen = HashSet::new();
+        for route in ROUTES {
+            assert!(
+                seen.insert((route.method.name(), route.path)),
+                "duplicate route: {} {}",
+                route.method.name(),
+                route.path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filter() {
+        let filter = V1_EXECUTION.filter_param::<u32>();
+        let id = warp::test::request()
+            .method("GET")
+            .path("/v1/executions/42")
+            .filter(&filter)
+            .await;
+        assert_eq!(id.ok(), Some(42));
+
+        let wrong_method = warp::test::request()
+            .method("POST")
+            .path("/v1/executions/42")
+            .filter(&filter)
+            .await;
+        assert!(wrong_method.is_err());
+
+        let bad_parameter = warp::test::request()
+            .method("GET")
+            .path("/v1/executions/abc")
+            .filter(&filter)
+            .await;
+        assert!(bad_parameter.is_err());
+    }
+}
@@ -1,14 +1,18 @@
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 use std::sync::Arc;
+use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::net::TcpListener;
-use tokio::sync::{Mutex as TokioMutex, RwLock};
+use tokio::sync::{watch, Mutex as TokioMutex, RwLock};
 use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::MaybeTlsStream;
 
-use crate::state::{LauncherQueueRegistry, QueuedLauncher};
+use crate::state::{ConnectionCounter, LauncherQueueRegistry, QueuedLauncher};
 
 const LAUNCHER_WS_PORT: u16 = 11375;
 
@@ -33,36 +37,60 @@ pub enum LauncherMessage {
 }
 
 /// Start the launcher WebSocket server
-pub async fn start_launcher_websocket(app_handle: AppHandle) -> Result<(), String> {
+///
+/// The port is fixed because launcher processes connect to it before reading any settings.
+/// The accept loop runs until `shutdown` is signalled.
+pub async fn start_launcher_websocket(
+    app_handle: AppHandle,
+    connections: ConnectionCounter,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<(SocketAddr, JoinHandle<()>), String> {
     let addr = format!("127.0.0.1:{}", LAUNCHER_WS_PORT);
     let listener = TcpListener::bind(&addr)
         .await
         .map_err(|e| format!("Failed to bind launcher WebSocket server: {}", e))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read launcher WebSocket address: {}", e))?;
 
     log::info!("Launcher WebSocket server listening on {}", addr);
 
     let app_handle = Arc::new(RwLock::new(app_handle));
 
-    tauri::async_runtime::spawn(async move {
-        while let Ok((stream, addr)) = listener.accept().await {
+    let task = tauri::async_runtime::spawn(async move {
+        loop {
+            let (stream, addr) = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(_) => break,
+                },
+                _ = shutdown.changed() => break,
+            };
+
             log::debug!("Launcher connection from: {}", addr);
 
             let app_handle = app_handle.clone();
+            let shutdown = shutdown.clone();
+            let connection = connections.track();
 
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = handle_launcher_connection(stream, app_handle).await {
+                let _connection = connection;
+                if let Err(e) = handle_launcher_connection(stream, app_handle, shutdown).await {
                     log::debug!("Launcher connection error: {}", e);
                 }
             });
         }
+
+        log::info!("Launcher WebSocket server stopped accepting connections");
     });
 
-    Ok(())
+    Ok((local_addr, task))
 }
 
 async fn handle_launcher_connection(
     stream: tokio::net::TcpStream,
     app_handle: Arc<RwLock<AppHandle>>,
+    mut shutdown: watch::Receiver<bool>,
 ) -> Result<(), String> {
     let ws_stream = accept_async(stream)
         .await
@@ -73,7 +101,23 @@ async fn handle_launcher_connection(
     // Track the launcher ID for this connection (if it joins the queue)
     let mut current_launcher_id: Option<String> = None;
 
-    while let Some(msg) = read.next().await {
+    loop {
+        let msg = tokio::select! {
+            msg = read.next() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+            _ = shutdown.changed() => {
+                let _ = write
+                    .send(Message::Close(Some(CloseFrame {
+                        code: CloseCode::Away,
+                        reason: "Proxima closed".into(),
+                    })))
+                    .await;
+                break;
+            }
+        };
+
         match msg {
             Ok(Message::Text(text)) => {
                 if let Ok(launcher_msg) = serde_json::from_str::<LauncherMessage>(&text) {
@@ -1,7 +1,24 @@
 pub mod api_dump;
 pub mod autoexec;
+pub mod bundler;
+pub mod cli;
+pub mod diagnostics;
+pub mod discovery;
+pub mod executions;
+pub mod explorer;
 pub mod file_watcher;
 pub mod filesystem;
+pub mod formatter;
+pub mod history;
 pub mod http_server;
 pub mod launcher;
+pub mod quick_open;
+pub mod remote_spy;
+pub mod scheduler;
+pub mod search;
+pub mod servers;
+pub mod status;
+pub mod trash;
+pub mod versions;
 pub mod websocket;
+pub mod workspace;
@@ -0,0 +1,313 @@
+//! Fuzzy quick open over workspace file paths
+//!
+//! The index holds the path of every file under `scripts/` and `autoexec/` that isn't
+//! ignored. It is built once at startup, then the file watcher applies each batch of
+//! changes to it, so queries never touch the disk.
+
+use crate::models::{FileChange, FileChangeKind, QuickOpenMatch};
+use crate::state::FileIndexState;
+use crate::utils::paths;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+use super::filesystem;
+
+const DEFAULT_LIMIT: usize = 50;
+/// Bonus for matching the character right after the previous match
+const CONSECUTIVE_BONUS: i64 = 8;
+/// Bonus for matching the first character of a path segment or word
+const BOUNDARY_BONUS: i64 = 10;
+/// Bonus when the whole query matches within the file name
+const FILE_NAME_BONUS: i64 = 20;
+/// Largest penalty for the characters skipped between two matches, one point each
+const MAX_GAP_PENALTY: i64 = 5;
+
+/// Relative paths of the workspace files, sorted
+#[derive(Default)]
+pub struct FileIndex {
+    paths: Vec<String>,
+}
+
+impl FileIndex {
+    /// Walk the workspace under `base_dir`
+    pub fn build(base_dir: &Path) -> Result<Self, String> {
+        let paths = filesystem::list_workspace_files(base_dir)?
+            .iter()
+            .filter_map(|file| file.strip_prefix(base_dir).ok())
+            .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+            .collect();
+
+        Ok(Self::from_paths(paths))
+    }
+
+    fn from_paths(mut paths: Vec<String>) -> Self {
+        paths.sort();
+        Self { paths }
+    }
+
+    /// Apply file watcher changes under `base_dir`
+    ///
+    /// Created and renamed-in folders add the files inside them, and deleted or
+    /// renamed-away folders remove theirs.
+    pub fn apply(&mut self, base_dir: &Path, changes: &[FileChange]) {
+        for change in changes {
+            match change.kind {
+                FileChangeKind::Created => self.add(base_dir, &change.path),
+                FileChangeKind::Deleted => self.remove(&change.path),
+                FileChangeKind::Renamed => {
+                    if let Some(old_path) = &change.old_path {
+                        self.remove(old_path);
+                    }
+                    self.add(base_dir, &change.path);
+                }
+                FileChangeKind::Modified => {}
+            }
+        }
+    }
+
+    /// Add a file, or every file in a folder
+    fn add(&mut self, base_dir: &Path, relative_path: &str) {
+        let path = base_dir.join(relative_path);
+        let files = if path.is_dir() {
+            match filesystem::list_folder_files(base_dir, &path) {
+                Ok(files) => files,
+                Err(e) => {
+                    log::error!("Failed to index {}: {}", relative_path, e);
+                    return;
+                }
+            }
+        } else if path.is_file() {
+            vec![path]
+        } else {
+            return;
+        };
+
+        for file in files {
+            let Ok(relative) = file.strip_prefix(base_dir) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            if let Err(position) = self.paths.binary_search(&relative) {
+                self.paths.insert(position, relative);
+            }
+        }
+    }
+
+    /// Remove a file, or every file in a folder
+    fn remove(&mut self, relative_path: &str) {
+        let folder_prefix = format!("{}/", relative_path);
+        self.paths
+            .retain(|path| path != relative_path && !path.starts_with(&folder_prefix));
+    }
+
+    /// Files matching `query`, best first
+    ///
+    /// The query's characters must appear in the path in order, ignoring case and
+    /// whitespace. An empty query lists files in path order.
+    pub fn query(&self, query: &str, limit: Option<usize>) -> Vec<QuickOpenMatch> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT);
+        let query: Vec<char> = query
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(lower)
+            .collect();
+
+        let mut matches: Vec<QuickOpenMatch> = self
+            .paths
+            .iter()
+            .filter_map(|path| {
+                let (score, positions) = fuzzy_match(&query, path)?;
+                Some(QuickOpenMatch {
+                    path: path.clone(),
+                    name: path.rsplit('/').next().unwrap_or(path).to_string(),
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+
+        // Shorter paths win ties; the index is already in path order
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.path.len().cmp(&b.path.len()))
+        });
+        matches.truncate(limit);
+        matches
+    }
+}
+
+/// Rebuild the index from disk, logging failures
+pub fn refresh(app: &AppHandle) {
+    let index = paths::get_base_directory(app).and_then(|base_dir| FileIndex::build(&base_dir));
+
+    match index {
+        Ok(index) => {
+            log::debug!("Quick open index rebuilt with {} files", index.paths.len());
+            *app.state::<FileIndexState>().blocking_write() = index;
+        }
+        Err(e) => log::error!("Failed to rebuild quick open index: {}", e),
+    }
+}
+
+/// Apply a batch of file watcher changes to the index
+///
+/// A changed `.proximaignore` can hide or reveal any file, so the index is rebuilt instead.
+pub fn apply_changes(app: &AppHandle, base_dir: &Path, changes: &[FileChange]) {
+    let ignore_changed = changes.iter().any(|change| {
+        change.path.ends_with(".proximaignore")
+            || change
+                .old_path
+                .as_ref()
+                .is_some_and(|path| path.ends_with(".proximaignore"))
+    });
+    if ignore_changed {
+        refresh(app);
+        return;
+    }
+
+    let state = app.state::<FileIndexState>();
+    let mut index = state.blocking_write();
+    index.apply(base_dir, changes);
+    log::debug!("Quick open index updated to {} files", index.paths.len());
+}
+
+/// Score `path` against a lowercase query
+///
+/// # Returns
+/// The score and the matched character indices, or `None` if the path doesn't match
+fn fuzzy_match(query: &[char], path: &str) -> Option<(i64, Vec<usize>)> {
+    let chars: Vec<char> = path.chars().collect();
+
+    // Matches within the file name beat matches spread across folders
+    let name_start = chars.iter().rposition(|&c| c == '/').map_or(0, |i| i + 1);
+    if let Some((score, positions)) = match_from(query, &chars, name_start) {
+        return Some((score + FILE_NAME_BONUS, positions));
+    }
+
+    match_from(query, &chars, 0)
+}
+
+/// Match the query's characters in order from `start`, taking the first occurrence of each
+fn match_from(query: &[char], chars: &[char], start: usize) -> Option<(i64, Vec<usize>)> {
+    let mut positions: Vec<usize> = Vec::with_capacity(query.len());
+    let mut score = 0;
+    let mut next = start;
+
+    for &wanted in query {
+        let index = (next..chars.len()).find(|&i| lower(chars[i]) == wanted)?;
+
+        score += 1;
+        if let Some(&last) = positions.last() {
+            if index == last + 1 {
+                score += CONSECUTIVE_BONUS;
+            } else {
+                score -= ((index - last - 1) as i64).min(MAX_GAP_PENALTY);
+            }
+        }
+        if is_boundary(chars, index) {
+            score += BOUNDARY_BONUS;
+        }
+
+        positions.push(index);
+        next = index + 1;
+    }
+
+    Some((score, positions))
+}
+
+/// Whether the character at `index` starts a path segment or a word
+fn is_boundary(chars: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+
+    let previous = chars[index - 1];
+    matches!(previous, '/' | '_' | '-' | '.' | ' ')
+        || (previous.is_lowercase() && chars[index].is_uppercase())
+}
+
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn index() -> FileIndex {
+        FileIndex::from_paths(
+            [
+                "scripts/maintenance/loader.lua",
+                "scripts/main.lua",
+                "scripts/ui/MainMenu.lua",
+                "autoexec/init.lua",
+            ]
+            .map(String::from)
+            .to_vec(),
+        )
+    }
+
+    #[test]
+    fn test_query_ranking() {
+        let index = index();
+
+        let paths: Vec<_> = index
+            .query("main", None)
+            .into_iter()
+            .map(|m| m.path)
+            .collect();
+        assert_eq!(paths[0], "scripts/main.lua");
+        assert_eq!(paths.len(), 3);
+
+        let matches = index.query("mm", None);
+        assert_eq!(matches[0].path, "scripts/ui/MainMenu.lua");
+        assert_eq!(matches[0].name, "MainMenu.lua");
+        assert_eq!(matches[0].positions, vec![11, 15]);
+
+        assert!(index.query("xyz", None).is_empty());
+        assert_eq!(index.query("", Some(2)).len(), 2);
+    }
+
+    #[test]
+    fn test_apply_changes() {
+        let temp = tempfile::tempdir().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("scripts/tools/ignored")).unwrap();
+        fs::write(base.join("scripts/.proximaignore"), "ignored\n").unwrap();
+        fs::write(base.join("scripts/tools/a.lua"), "").unwrap();
+        fs::write(base.join("scripts/tools/ignored/b.lua"), "").unwrap();
+        fs::write(base.join("scripts/new.lua"), "").unwrap();
+
+        let change = |kind, path: &str, old_path: Option<&str>| FileChange {
+            kind,
+            path: path.to_string(),
+            old_path: old_path.map(String::from),
+        };
+        let mut index = index();
+        index.apply(
+            base,
+            &[
+                change(FileChangeKind::Created, "scripts/new.lua", None),
+                change(
+                    FileChangeKind::Renamed,
+                    "scripts/tools",
+                    Some("scripts/maintenance"),
+                ),
+                change(FileChangeKind::Deleted, "autoexec/init.lua", None),
+                change(FileChangeKind::Modified, "scripts/main.lua", None),
+            ],
+        );
+
+        assert_eq!(
+            index.paths,
+            vec![
+                "scripts/main.lua",
+                "scripts/new.lua",
+                "scripts/tools/a.lua",
+                "scripts/ui/MainMenu.lua",
+            ]
+        );
+    }
+}
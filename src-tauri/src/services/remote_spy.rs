@@ -0,0 +1,140 @@
+//! Remote spy sessions
+//!
+//! One client at a time can run the remote spy. Requests about captured calls are sent
+//! to the active remote spy client, which replies with events; the `request_*` functions
+//! wait for the reply so callers outside the webview, like the HTTP API, get the result
+//! directly.
+
+use crate::models::remote_spy::{RemoteDecompiledEvent, RemoteGeneratedCodeEvent};
+use crate::state::{ActiveClientsState, ClientRegistry};
+use crate::utils::clients::{get_active_remote_spy, verify_client_exists, ClientRequestError};
+use crate::utils::events::{self, emit_or_error};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::websocket::{
+    send_decompile_request as send_decompile, send_generate_code_request as send_generate_code,
+    send_start_remote_spy, send_stop_remote_spy,
+};
+
+/// How long to wait for the remote spy client to reply
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Start the remote spy on a client
+///
+/// # Returns
+/// * `Ok(())` - If the remote spy started, or was already running on this client
+/// * `Err(ClientRequestError)` - If it's running on another client, or the client isn't
+///   attached or can't be reached
+pub async fn start(app: &AppHandle, client_id: &str) -> Result<(), ClientRequestError> {
+    log::info!("Starting remote spy for client: {}", client_id);
+
+    let active_clients = app.state::<ActiveClientsState>();
+    let clients = app.state::<ClientRegistry>();
+
+    // Check if another client already has remote spy active
+    {
+        let active = active_clients.read().await;
+        if let Some(existing_id) = &active.remote_spy {
+            if existing_id != client_id {
+                return Err(ClientRequestError::InUse(format!(
+                    "Remote spy is already active for another client: {}",
+                    existing_id
+                )));
+            }
+            // Already active for this client
+            return Ok(());
+        }
+    }
+
+    // Verify client exists
+    verify_client_exists(client_id, &clients).await?;
+
+    // Set as active remote spy client
+    {
+        let mut active = active_clients.write().await;
+        active.remote_spy = Some(client_id.to_string());
+    }
+
+    // Send start_remote_spy message to client
+    send_start_remote_spy(client_id, &clients).await?;
+
+    log_ui!(app, Info, "Starting remote spy for client: {}", client_id);
+
+    // Emit event to frontend
+    emit_or_error(app, "remote-spy-started", client_id.to_string())?;
+
+    Ok(())
+}
+
+/// Stop the remote spy, if it's running
+pub async fn stop(app: &AppHandle) -> Result<(), ClientRequestError> {
+    log::info!("Stopping remote spy");
+
+    let active = app.state::<ActiveClientsState>();
+    let client_id = active.write().await.remote_spy.take();
+
+    if let Some(id) = client_id {
+        // Send stop_remote_spy message to client (ignore errors if client disconnected)
+        let _ = send_stop_remote_spy(&id, &app.state::<ClientRegistry>()).await;
+
+        // Emit event to frontend
+        emit_or_error(app, "remote-spy-stopped", ())?;
+    }
+
+    Ok(())
+}
+
+/// Ask the remote spy client to decompile the script that made a call
+pub async fn send_decompile_request(
+    app: &AppHandle,
+    call_id: u32,
+) -> Result<String, ClientRequestError> {
+    let client_id = get_active_remote_spy(&app.state::<ActiveClientsState>()).await?;
+    send_decompile(&client_id, call_id, &app.state::<ClientRegistry>()).await?;
+    Ok(client_id)
+}
+
+/// Ask the remote spy client for code that repeats a call
+pub async fn send_generate_code_request(
+    app: &AppHandle,
+    call_id: u32,
+) -> Result<String, ClientRequestError> {
+    let client_id = get_active_remote_spy(&app.state::<ActiveClientsState>()).await?;
+    send_generate_code(&client_id, call_id, &app.state::<ClientRegistry>()).await?;
+    Ok(client_id)
+}
+
+/// Decompile the script that made a call
+pub async fn request_decompile(
+    app: &AppHandle,
+    call_id: u32,
+) -> Result<RemoteDecompiledEvent, ClientRequestError> {
+    let events = events::subscribe(app);
+    let client_id = send_decompile_request(app, call_id).await?;
+    events::wait_for_client_event(
+        events,
+        &client_id,
+        "remote-spy-decompiled",
+        REPLY_TIMEOUT,
+        |reply: &RemoteDecompiledEvent| reply.call_id == call_id,
+    )
+    .await
+}
+
+/// Get code that repeats a call
+pub async fn request_generated_code(
+    app: &AppHandle,
+    call_id: u32,
+) -> Result<RemoteGeneratedCodeEvent, ClientRequestError> {
+    let events = events::subscribe(app);
+    let client_id = send_generate_code_request(app, call_id).await?;
+    events::wait_for_client_event(
+        events,
+        &client_id,
+        "remote-spy-generated-code",
+        REPLY_TIMEOUT,
+        |reply: &RemoteGeneratedCodeEvent| reply.call_id == call_id,
+    )
+    .await
+}
@@ -0,0 +1,434 @@
+//! Scheduled and recurring script execution
+//!
+//! Schedules are stored in `.proxima/schedules.json` and run by a backend loop, so they
+//! keep running across UI reloads. A due schedule whose clients aren't attached is marked
+//! as waiting and runs as soon as one of them attaches.
+
+use crate::models::{
+    ClientSelector, ExecutionSource, Schedule, ScheduleRequest, ScheduleTrigger, ScheduledScript,
+    ScriptPayload,
+};
+use crate::state::{ClientRegistry, SchedulerState};
+use crate::utils::clients::resolve_selector;
+use crate::utils::events::emit_or_log;
+use crate::utils::paths;
+use crate::utils::time::now_millis;
+use chrono::{Local, TimeZone};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use super::history::{self, Execution};
+use super::workspace::Workspace;
+
+const SCHEDULES_FILE: &str = "schedules.json";
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Schedules backed by a JSON file
+#[derive(Default)]
+pub struct Scheduler {
+    path: Option<PathBuf>,
+    schedules: Vec<Schedule>,
+}
+
+impl Scheduler {
+    /// Load the schedules file, starting empty if it is missing or unreadable
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match paths::get_data_directory(app) {
+            Ok(dir) => dir.join(SCHEDULES_FILE),
+            Err(e) => {
+                log::error!("Failed to get data directory for schedules: {}", e);
+                return Self::default();
+            }
+        };
+
+        let mut schedules: Vec<Schedule> = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::error!("Failed to parse schedules: {}", e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+
+        // Waiting is re-evaluated on the next tick
+        for schedule in &mut schedules {
+            schedule.waiting = false;
+        }
+
+        Self {
+            path: Some(path),
+            schedules,
+        }
+    }
+
+    pub fn list(&self) -> Vec<Schedule> {
+        self.schedules.clone()
+    }
+
+    fn get(&self, id: &str) -> Result<&Schedule, String> {
+        self.schedules
+            .iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| format!("Schedule not found: {}", id))
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut Schedule, String> {
+        self.schedules
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| format!("Schedule not found: {}", id))
+    }
+
+    /// Create a schedule from a request
+    pub fn create(&mut self, request: ScheduleRequest) -> Result<Schedule, String> {
+        validate_selector(&request.selector)?;
+        let next_run = first_run(&request.trigger)?;
+
+        let schedule = Schedule {
+            id: Uuid::new_v4().to_string(),
+            name: request.name,
+            script: request.script,
+            selector: request.selector,
+            trigger: request.trigger,
+            redirect: request.redirect,
+            enabled: request.enabled,
+            waiting: false,
+            next_run: Some(next_run),
+            last_run: None,
+            run_count: 0,
+            last_error: None,
+        };
+
+        self.schedules.push(schedule.clone());
+        self.save();
+        Ok(schedule)
+    }
+
+    /// Replace a schedule's settings, keeping its run history
+    pub fn update(&mut self, id: &str, request: ScheduleRequest) -> Result<Schedule, String> {
+        validate_selector(&request.selector)?;
+        let next_run = first_run(&request.trigger)?;
+        let schedule = self.get_mut(id)?;
+
+        schedule.name = request.name;
+        schedule.script = request.script;
+        schedule.selector = request.selector;
+        schedule.trigger = request.trigger;
+        schedule.redirect = request.redirect;
+        schedule.enabled = request.enabled;
+        schedule.waiting = false;
+        schedule.next_run = Some(next_run);
+        schedule.last_error = None;
+
+        let schedule = schedule.clone();
+        self.save();
+        Ok(schedule)
+    }
+
+    /// Enable or disable a schedule, recomputing its next run when enabled
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) -> Result<Schedule, String> {
+        let schedule = self.get_mut(id)?;
+
+        if enabled {
+            schedule.next_run = Some(first_run(&schedule.trigger)?);
+        }
+        schedule.enabled = enabled;
+        schedule.waiting = false;
+
+        let schedule = schedule.clone();
+        self.save();
+        Ok(schedule)
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        let before = self.schedules.len();
+        self.schedules.retain(|s| s.id != id);
+
+        if self.schedules.len() == before {
+            return Err(format!("Schedule not found: {}", id));
+        }
+
+        self.save();
+        Ok(())
+    }
+
+    pub fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        match serde_json::to_string_pretty(&self.schedules) {
+            Ok(json) => {
+                if let Err(e) = fs::write(path, json) {
+                    log::error!("Failed to save schedules: {}", e);
+                }
+            }
+            Err(e) => log::error!("Failed to serialize schedules: {}", e),
+        }
+    }
+}
+
+/// Start the scheduler loop
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            interval.tick().await;
+            tick(&app).await;
+        }
+    });
+}
+
+/// Run every due schedule whose clients are attached
+///
+/// Due schedules are copied out so the scheduler isn't locked while scripts are sent,
+/// then their run state is written back.
+async fn tick(app: &AppHandle) {
+    let now = now_millis();
+    let scheduler = app.state::<SchedulerState>().inner().clone();
+    let clients = app.state::<ClientRegistry>().inner().clone();
+
+    let due: Vec<Schedule> = scheduler
+        .read()
+        .await
+        .schedules
+        .iter()
+        .filter(|schedule| schedule.enabled && schedule.next_run.is_some_and(|next| next <= now))
+        .cloned()
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+
+    // `None` when none of the schedule's clients are attached
+    let mut runs = Vec::with_capacity(due.len());
+    for schedule in due {
+        let targets = resolve_selector(&schedule.selector, &clients).await;
+        let result = if targets.is_empty() {
+            None
+        } else {
+            Some(dispatch(app, &schedule, targets).await)
+        };
+        runs.push((schedule, result));
+    }
+
+    let mut scheduler = scheduler.write().await;
+    let mut changed = false;
+
+    for (due, result) in runs {
+        // Deleted while its script was being sent
+        let Ok(schedule) = scheduler.get_mut(&due.id) else {
+            continue;
+        };
+        // Disabled or edited while its script was being sent, so its next run is already set
+        let unchanged = schedule.enabled && schedule.next_run == due.next_run;
+
+        let Some(result) = result else {
+            if unchanged && !schedule.waiting {
+                schedule.waiting = true;
+                changed = true;
+                log_ui!(
+                    app,
+                    Warning,
+                    "Schedule \"{}\" is waiting for its clients to attach",
+                    schedule.name
+                );
+            }
+            continue;
+        };
+
+        schedule.last_run = Some(now);
+        schedule.run_count += 1;
+        schedule.last_error = result.err();
+        changed = true;
+
+        if !unchanged {
+            continue;
+        }
+        schedule.waiting = false;
+
+        match next_run(&schedule.trigger, now) {
+            Ok(Some(next)) => schedule.next_run = Some(next),
+            Ok(None) => {
+                schedule.next_run = None;
+                schedule.enabled = false;
+            }
+            Err(e) => {
+                schedule.next_run = None;
+                schedule.enabled = false;
+                schedule.last_error = Some(e);
+            }
+        }
+    }
+
+    if changed {
+        scheduler.save();
+        emit_or_log(app, "schedules-update", scheduler.list());
+    }
+}
+
+/// Run a schedule now, outside of its trigger
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - IDs of the clients the script was sent to
+/// * `Err(String)` - If the schedule doesn't exist, has no attached clients or failed to send
+pub async fn run_now(app: &AppHandle, id: &str) -> Result<Vec<String>, String> {
+    let scheduler = app.state::<SchedulerState>().inner().clone();
+    let clients = app.state::<ClientRegistry>().inner().clone();
+
+    let schedule = scheduler.read().await.get(id)?.clone();
+
+    let targets = resolve_selector(&schedule.selector, &clients).await;
+    if targets.is_empty() {
+        return Err("None of the schedule's clients are attached".to_string());
+    }
+
+    let result = dispatch(app, &schedule, targets).await;
+
+    let mut scheduler = scheduler.write().await;
+    // Deleted while its script was being sent
+    if let Ok(schedule) = scheduler.get_mut(id) {
+        schedule.last_run = Some(now_millis());
+        schedule.run_count += 1;
+        schedule.last_error = result.as_ref().err().cloned();
+
+        scheduler.save();
+        emit_or_log(app, "schedules-update", scheduler.list());
+    }
+    result
+}
+
+/// Send a schedule's script to its target clients
+async fn dispatch(
+    app: &AppHandle,
+    schedule: &Schedule,
+    targets: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let script = match &schedule.script {
+        ScheduledScript::Inline { content } => content.clone(),
+        ScheduledScript::File { path } => {
+            let file_path = Workspace::load(app)
+                .and_then(|workspace| workspace.resolve_script(path))
+                .map_err(|e| e.to_string())?;
+            fs::read_to_string(&file_path).map_err(|e| format!("Failed to read {}: {}", path, e))?
+        }
+    };
+
+    log::info!(
+        "Running schedule \"{}\" on {} client(s)",
+        schedule.name,
+        targets.len()
+    );
+
+    history::execute(
+        app,
+        Execution {
+            source: ExecutionSource::Schedule,
+            client_ids: targets,
+            payload: ScriptPayload::new(script, schedule.redirect),
+            rerun_of: None,
+        },
+    )
+    .await
+}
+
+/// Compute the first run of a new or re-enabled trigger
+fn first_run(trigger: &ScheduleTrigger) -> Result<u64, String> {
+    next_run(trigger, now_millis())?.ok_or_else(|| "Scheduled time is in the past".to_string())
+}
+
+/// Compute the next run of a trigger after `after` (milliseconds since the Unix epoch)
+///
+/// # Returns
+/// * `Ok(Some(u64))` - The next run time
+/// * `Ok(None)` - If the trigger won't run again
+/// * `Err(String)` - If the trigger is invalid
+pub fn next_run(trigger: &ScheduleTrigger, after: u64) -> Result<Option<u64>, String> {
+    match trigger {
+        ScheduleTrigger::At { at } => Ok((*at > after).then_some(*at)),
+        ScheduleTrigger::Interval { seconds } => {
+            if *seconds == 0 {
+                return Err("Interval must be at least 1 second".to_string());
+            }
+            seconds
+                .checked_mul(1000)
+                .and_then(|ms| after.checked_add(ms))
+                .map(Some)
+                .ok_or_else(|| "Interval is too long".to_string())
+        }
+        ScheduleTrigger::Cron { expression } => {
+            let schedule = parse_cron(expression)?;
+            let after = Local
+                .timestamp_millis_opt(after as i64)
+                .single()
+                .ok_or_else(|| "Invalid time".to_string())?;
+
+            Ok(schedule
+                .after(&after)
+                .next()
+                .map(|next| next.timestamp_millis() as u64))
+        }
+    }
+}
+
+/// Parse a cron expression, accepting the common 5-field form as well as the
+/// 6/7-field form with seconds (and years)
+fn parse_cron(expression: &str) -> Result<cron::Schedule, String> {
+    let expression = expression.trim();
+    let expression = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+
+    cron::Schedule::from_str(&expression)
+        .map_err(|e| format!("Invalid cron expression \"{}\": {}", expression, e))
+}
+
+/// Reject selectors that can never match a client
+fn validate_selector(selector: &ClientSelector) -> Result<(), String> {
+    match selector {
+        ClientSelector::Ids { ids } if ids.is_empty() => {
+            Err("Select at least one client".to_string())
+        }
+        ClientSelector::Usernames { usernames } if usernames.is_empty() => {
+            Err("Select at least one username".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_and_at_triggers() {
+        let interval = ScheduleTrigger::Interval { seconds: 30 };
+        assert_eq!(next_run(&interval, 1_000).unwrap(), Some(31_000));
+        assert!(next_run(&ScheduleTrigger::Interval { seconds: 0 }, 1_000).is_err());
+        assert!(next_run(&ScheduleTrigger::Interval { seconds: u64::MAX }, 1_000).is_err());
+
+        let at = ScheduleTrigger::At { at: 5_000 };
+        assert_eq!(next_run(&at, 1_000).unwrap(), Some(5_000));
+        assert_eq!(next_run(&at, 5_000).unwrap(), None);
+    }
+
+    #[test]
+    fn test_cron_trigger() {
+        // Five-field expressions get a seconds field of 0
+        let every_minute = ScheduleTrigger::Cron {
+            expression: "* * * * *".to_string(),
+        };
+        let next = next_run(&every_minute, 60_000).unwrap().unwrap();
+        assert_eq!(next, 120_000);
+
+        let invalid = ScheduleTrigger::Cron {
+            expression: "not cron".to_string(),
+        };
+        assert!(next_run(&invalid, 0).is_err());
+    }
+}
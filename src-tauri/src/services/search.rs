@@ -0,0 +1,202 @@
+//! Full-text search of workspace files
+//!
+//! Searches every file under `scripts/` and `autoexec/` that the folder's `.proximaignore`
+//! doesn't exclude. Files over 1 MiB or that aren't UTF-8 text are skipped, and matches
+//! don't span lines.
+
+use crate::models::{SearchMatch, SearchRequest, SearchResults};
+use crate::utils::paths;
+use regex::{Regex, RegexBuilder};
+use std::fs;
+use tauri::AppHandle;
+
+use super::filesystem;
+
+const DEFAULT_CONTEXT_LINES: usize = 2;
+const DEFAULT_MAX_RESULTS: usize = 1000;
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Build the regex that finds the query, with the request's options applied
+pub fn build_matcher(request: &SearchRequest) -> Result<Regex, String> {
+    if request.query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+
+    let pattern = if request.regex {
+        request.query.clone()
+    } else {
+        regex::escape(&request.query)
+    };
+    let pattern = if request.whole_word {
+        format!(r"\b(?:{})\b", pattern)
+    } else {
+        pattern
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!request.case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid regex: {}", e))
+}
+
+/// Find up to `limit` matches in a file's content
+///
+/// Empty matches, such as from `^`, are skipped.
+pub fn search_content(
+    matcher: &Regex,
+    path: &str,
+    content: &str,
+    context_lines: usize,
+    limit: usize,
+) -> Vec<SearchMatch> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut matches = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        for found in matcher.find_iter(line) {
+            if found.as_str().is_empty() {
+                continue;
+            }
+            if matches.len() >= limit {
+                return matches;
+            }
+
+            let context_end = index
+                .saturating_add(1)
+                .saturating_add(context_lines)
+                .min(lines.len());
+            matches.push(SearchMatch {
+                path: path.to_string(),
+                line: index + 1,
+                column: line[..found.start()].chars().count() + 1,
+                length: found.as_str().chars().count(),
+                text: line.to_string(),
+                context_before: lines[index.saturating_sub(context_lines)..index]
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect(),
+                context_after: lines[index + 1..context_end]
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect(),
+            });
+        }
+    }
+
+    matches
+}
+
+/// Search the scripts and autoexec folders
+pub fn search_workspace(app: &AppHandle, request: &SearchRequest) -> Result<SearchResults, String> {
+    let matcher = build_matcher(request)?;
+    let context_lines = request.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES);
+    let max_results = request.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+    let base_dir = paths::get_base_directory(app)?;
+
+    let mut results = SearchResults {
+        matches: Vec::new(),
+        files_searched: 0,
+        truncated: false,
+    };
+
+    for file in filesystem::list_workspace_files(&base_dir)? {
+        let small_file = fs::metadata(&file).is_ok_and(|metadata| metadata.len() <= MAX_FILE_BYTES);
+        if !small_file {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        results.files_searched += 1;
+
+        let relative_path = file
+            .strip_prefix(&base_dir)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        // Look for one more match than needed to know whether results were cut off
+        let remaining = max_results - results.matches.len();
+        let mut found = search_content(
+            &matcher,
+            &relative_path,
+            &content,
+            context_lines,
+            remaining + 1,
+        );
+        if found.len() > remaining {
+            found.truncate(remaining);
+            results.matches.extend(found);
+            results.truncated = true;
+            return Ok(results);
+        }
+        results.matches.extend(found);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(query: &str) -> SearchRequest {
+        SearchRequest {
+            query: query.to_string(),
+            regex: false,
+            case_sensitive: false,
+            whole_word: false,
+            context_lines: None,
+            max_results: None,
+        }
+    }
+
+    #[test]
+    fn test_build_matcher() {
+        let matcher = build_matcher(&request("a.b")).unwrap();
+        assert!(matcher.is_match("A.B"));
+        assert!(!matcher.is_match("axb"));
+
+        let matcher = build_matcher(&SearchRequest {
+            case_sensitive: true,
+            whole_word: true,
+            ..request("Part")
+        })
+        .unwrap();
+        assert!(matcher.is_match("local Part = 1"));
+        assert!(!matcher.is_match("local Parts = 1"));
+        assert!(!matcher.is_match("local part = 1"));
+
+        assert!(build_matcher(&SearchRequest {
+            regex: true,
+            ..request("(")
+        })
+        .is_err());
+        assert!(build_matcher(&request("")).is_err());
+    }
+
+    #[test]
+    fn test_search_content() {
+        let matcher = build_matcher(&request("print")).unwrap();
+        let content = "local a = 1\n-- é print(a) print(b)\nreturn a\n";
+
+        let matches = search_content(&matcher, "scripts/a.lua", content, 1, 10);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].line, 2);
+        assert_eq!(matches[0].column, 6);
+        assert_eq!(matches[0].length, 5);
+        assert_eq!(matches[0].context_before, vec!["local a = 1"]);
+        assert_eq!(matches[0].context_after, vec!["return a"]);
+        assert_eq!(matches[1].column, 15);
+
+        assert_eq!(
+            search_content(&matcher, "scripts/a.lua", content, 1, 1).len(),
+            1
+        );
+
+        // Context larger than the file is cut to the file
+        let matches = search_content(&matcher, "scripts/a.lua", content, usize::MAX, 10);
+        assert_eq!(matches[0].context_before, vec!["local a = 1"]);
+        assert_eq!(matches[0].context_after, vec!["return a"]);
+    }
+}
@@ -0,0 +1,193 @@
+//! Lifecycle management for the backend servers
+//!
+//! Owns starting, stopping and restarting the WebSocket, HTTP and launcher servers,
+//! and closing them gracefully when the app exits.
+
+use crate::models::ServerKind;
+use crate::state::{
+    ActiveClientsState, ApiDumpCache, ClientRegistry, ConnectionCounter, RunningServer,
+    ServerRegistry,
+};
+use crate::utils::settings;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::sync::watch;
+
+use super::{discovery, http_server, launcher, websocket};
+
+const DEFAULT_WEBSOCKET_PORT: u16 = 13376;
+const DEFAULT_HTTP_PORT: u16 = 13377;
+
+// How long to wait for a server task to finish after signalling shutdown
+const STOP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Ports configured in the "network" settings section
+struct NetworkSettings {
+    websocket_port: u16,
+    http_port: u16,
+}
+
+impl NetworkSettings {
+    fn load(app: &AppHandle) -> Self {
+        let network = settings::get_settings_section(app, "network");
+        let port = |key: &str, default: u16| {
+            network
+                .as_ref()
+                .and_then(|n| n.get(key))
+                .and_then(|v| v.as_u64())
+                .and_then(|v| u16::try_from(v).ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            websocket_port: port("websocketPort", DEFAULT_WEBSOCKET_PORT),
+            http_port: port("httpPort", DEFAULT_HTTP_PORT),
+        }
+    }
+}
+
+/// Start every backend server, logging failures instead of returning them
+pub async fn start_all(app: &AppHandle) {
+    for kind in ServerKind::ALL {
+        if let Err(e) = start_server(app, kind).await {
+            log_ui!(app, Error, "Failed to start {} server: {}", kind, e);
+        }
+    }
+}
+
+/// Start a backend server
+///
+/// Ports are re-read from settings on every start, so a restart picks up changes.
+///
+/// # Returns
+/// * `Ok(SocketAddr)` - The address the server is bound to
+/// * `Err(String)` - If the server is already running or failed to bind
+pub async fn start_server(app: &AppHandle, kind: ServerKind) -> Result<SocketAddr, String> {
+    let registry = app.state::<ServerRegistry>().inner().clone();
+    let mut servers = registry.write().await;
+
+    if let Some(running) = servers.get(&kind) {
+        return Err(format!(
+            "{} server is already running on {}",
+            kind, running.addr
+        ));
+    }
+
+    let network = NetworkSettings::load(app);
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let connections = ConnectionCounter::default();
+
+    let (addr, task) = match kind {
+        ServerKind::Websocket => websocket::start_websocket_server(
+            app.clone(),
+            app.state::<ClientRegistry>().inner().clone(),
+            app.state::<ActiveClientsState>().inner().clone(),
+            app.state::<ApiDumpCache>().inner().clone(),
+            network.websocket_port,
+            connections.clone(),
+            shutdown_rx,
+        )
+        .await
+        .map_err(|e| e.to_string())?,
+        ServerKind::Http => http_server::start_http_server(
+            app.clone(),
+            network.http_port,
+            connections.clone(),
+            shutdown_rx,
+        )
+        .await
+        .map_err(|e| e.to_string())?,
+        ServerKind::Launcher => {
+            launcher::start_launcher_websocket(app.clone(), connections.clone(), shutdown_rx)
+                .await?
+        }
+    };
+
+    servers.insert(
+        kind,
+        RunningServer {
+            addr,
+            started_at: Instant::now(),
+            connections,
+            shutdown: shutdown_tx,
+            task,
+        },
+    );
+
+    if kind == ServerKind::Http {
+        if let Err(e) = discovery::write(addr.port()) {
+            log::warn!("{}", e);
+        }
+    }
+
+    Ok(addr)
+}
+
+/// Stop a backend server
+///
+/// Connected clients are sent a close frame with a reason before the server stops.
+pub async fn stop_server(app: &AppHandle, kind: ServerKind) -> Result<(), String> {
+    let registry = app.state::<ServerRegistry>().inner().clone();
+    let running = registry
+        .write()
+        .await
+        .remove(&kind)
+        .ok_or_else(|| format!("{} server is not running", kind))?;
+
+    let _ = running.shutdown.send(true);
+
+    if kind == ServerKind::Http {
+        discovery::remove();
+    }
+
+    if kind == ServerKind::Websocket {
+        let clients = app.state::<ClientRegistry>().inner().clone();
+        websocket::wait_for_disconnects(&clients).await;
+    }
+
+    if tokio::time::timeout(STOP_TIMEOUT, running.task)
+        .await
+        .is_err()
+    {
+        log::warn!("{} server did not stop in time", kind);
+    }
+
+    log_ui!(app, Info, "{} server stopped", kind);
+
+    Ok(())
+}
+
+/// Stop and start a backend server
+pub async fn restart_server(app: &AppHandle, kind: ServerKind) -> Result<SocketAddr, String> {
+    let is_running = app
+        .state::<ServerRegistry>()
+        .read()
+        .await
+        .contains_key(&kind);
+
+    if is_running {
+        stop_server(app, kind).await?;
+    }
+
+    start_server(app, kind).await
+}
+
+/// Stop every running backend server
+///
+/// Called when the app exits so clients get a close reason instead of a dropped socket.
+pub async fn shutdown_all(app: &AppHandle) {
+    let running: Vec<ServerKind> = app
+        .state::<ServerRegistry>()
+        .read()
+        .await
+        .keys()
+        .copied()
+        .collect();
+
+    for kind in running {
+        if let Err(e) = stop_server(app, kind).await {
+            log::error!("Failed to stop {} server: {}", kind, e);
+        }
+    }
+}
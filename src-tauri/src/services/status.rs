@@ -0,0 +1,46 @@
+//! Backend status and traffic metrics
+//!
+//! Collects a snapshot of the running servers and per-client traffic counters
+//! for the status command and the HTTP `/status` endpoint.
+
+use crate::models::{BackendStatus, ClientStatus, ServerKind, ServerStatus};
+use crate::state::{ClientRegistry, ServerRegistry};
+use tauri::{AppHandle, Manager};
+
+/// Collect the current state of every running server and attached client
+pub async fn collect_status(app: &AppHandle) -> BackendStatus {
+    let servers = {
+        let registry = app.state::<ServerRegistry>();
+        let running = registry.read().await;
+
+        ServerKind::ALL
+            .iter()
+            .filter_map(|kind| {
+                running.get(kind).map(|server| ServerStatus {
+                    server: *kind,
+                    address: server.addr.to_string(),
+                    uptime_secs: server.started_at.elapsed().as_secs(),
+                    connections: server.connections.count(),
+                })
+            })
+            .collect()
+    };
+
+    let mut clients = Vec::new();
+    {
+        let registry = app.state::<ClientRegistry>();
+        let attached = registry.read().await;
+
+        for (id, info) in attached.iter() {
+            clients.push(ClientStatus {
+                id: id.clone(),
+                username: info.username.clone(),
+                missed_pings: *info.missed_pings.read().await,
+                traffic: info.traffic.read().await.clone(),
+            });
+        }
+    }
+    clients.sort_by(|a, b| a.username.cmp(&b.username));
+
+    BackendStatus { servers, clients }
+}
@@ -0,0 +1,302 @@
+//! Recycle bin for deleted workspace files
+//!
+//! Deleted files and folders are moved into `.proxima/trash/<id>/` and recorded in
+//! `.proxima/trash/index.json` with their original path, so they can be restored.
+//! Items older than the `trash.retentionDays` setting are removed for good.
+
+use crate::models::TrashEntry;
+use crate::utils::time::now_millis;
+use crate::utils::{paths, settings};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::workspace::{Workspace, WorkspaceError};
+
+const TRASH_DIR: &str = "trash";
+const INDEX_FILE: &str = "index.json";
+const DEFAULT_RETENTION_DAYS: u64 = 30;
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// Held while the trash index is loaded, changed and saved
+static TRASH_LOCK: Mutex<()> = Mutex::new(());
+
+/// Days deleted items are kept, from the "trash" settings section
+pub fn retention_days(app: &AppHandle) -> u64 {
+    settings::get_settings_section(app, "trash")
+        .and_then(|trash| trash.get("retentionDays").and_then(|v| v.as_u64()))
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+}
+
+/// The trash folder and its index
+///
+/// Entries are kept oldest first.
+pub struct Trash {
+    dir: PathBuf,
+    entries: Vec<TrashEntry>,
+}
+
+impl Trash {
+    /// Load the trash index, starting empty if it is missing
+    ///
+    /// An index that can't be read or parsed is an error rather than an empty trash, so
+    /// saving never overwrites the original paths of items already in the trash.
+    fn open(dir: PathBuf) -> Result<Self, WorkspaceError> {
+        let index_path = dir.join(INDEX_FILE);
+        let entries = match fs::read_to_string(&index_path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| {
+                WorkspaceError::Io(format!(
+                    "Trash index is corrupt, fix or remove {}: {}",
+                    index_path.display(),
+                    e
+                ))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(WorkspaceError::Io(format!(
+                    "Failed to read trash index: {}",
+                    e
+                )))
+            }
+        };
+
+        Ok(Self { dir, entries })
+    }
+
+    /// Entries, newest first
+    pub fn iter(&self) -> impl Iterator<Item = &TrashEntry> {
+        self.entries.iter().rev()
+    }
+
+    /// Move a file or folder into the trash
+    ///
+    /// # Arguments
+    /// * `path` - The item to move
+    /// * `original_path` - Its path relative to the base directory, to restore it to
+    pub fn put(
+        &mut self,
+        path: &Path,
+        original_path: String,
+        is_folder: bool,
+    ) -> Result<TrashEntry, WorkspaceError> {
+        let name = path
+            .file_name()
+            .ok_or_else(|| WorkspaceError::InvalidName(original_path.clone()))?;
+
+        let entry = TrashEntry {
+            id: Uuid::new_v4().to_string(),
+            original_path,
+            is_folder,
+            deleted_at: now_millis(),
+        };
+
+        let entry_dir = self.dir.join(&entry.id);
+        fs::create_dir_all(&entry_dir)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to create trash folder: {}", e)))?;
+        if let Err(e) = fs::rename(path, entry_dir.join(name)) {
+            let _ = fs::remove_dir(&entry_dir);
+            return Err(WorkspaceError::Io(format!(
+                "Failed to move to trash: {}",
+                e
+            )));
+        }
+
+        self.entries.push(entry.clone());
+        self.save()?;
+
+        Ok(entry)
+    }
+
+    /// Move an item back to its original path
+    ///
+    /// # Returns
+    /// * `Ok(String)` - The restored path, relative to the base directory
+    /// * `Err(WorkspaceError)` - If the entry is missing or something now exists at its path
+    pub fn restore(&mut self, id: &str, workspace: &Workspace) -> Result<String, WorkspaceError> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.id == id)
+            .ok_or_else(|| WorkspaceError::NotFound(format!("trash entry {}", id)))?;
+        let entry = &self.entries[index];
+
+        let (folder, name) = entry
+            .original_path
+            .rsplit_once('/')
+            .unwrap_or(("", entry.original_path.as_str()));
+        let target = workspace.resolve_new(folder, name)?;
+        if target.exists() {
+            return Err(WorkspaceError::AlreadyExists(name.to_string()));
+        }
+
+        let entry_dir = self.dir.join(&entry.id);
+        let item = entry_dir.join(name);
+        if !item.exists() {
+            self.remove(index)?;
+            return Err(WorkspaceError::NotFound(format!("trash entry {}", id)));
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| WorkspaceError::Io(format!("Failed to create directory: {}", e)))?;
+        }
+        fs::rename(&item, &target)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to restore: {}", e)))?;
+
+        self.remove(index)?;
+        Ok(workspace.relative(&target))
+    }
+
+    /// Remove every item for good
+    ///
+    /// # Returns
+    /// The number of entries removed
+    pub fn empty(&mut self) -> Result<usize, WorkspaceError> {
+        let removed = self.entries.len();
+
+        // Also clears items that never made it into the index
+        if let Ok(items) = fs::read_dir(&self.dir) {
+            for item in items.flatten() {
+                if item.path().is_dir() {
+                    remove_item(&item.path());
+                }
+            }
+        }
+
+        self.entries.clear();
+        self.save()?;
+
+        Ok(removed)
+    }
+
+    /// Remove items deleted more than `max_age_days` ago
+    ///
+    /// # Returns
+    /// The number of entries removed
+    pub fn expire(&mut self, max_age_days: u64) -> Result<usize, WorkspaceError> {
+        let cutoff = now_millis().saturating_sub(max_age_days * MILLIS_PER_DAY);
+        let before = self.entries.len();
+
+        let dir = &self.dir;
+        self.entries.retain(|entry| {
+            let keep = entry.deleted_at >= cutoff;
+            if !keep {
+                remove_item(&dir.join(&entry.id));
+            }
+            keep
+        });
+
+        let removed = before - self.entries.len();
+        if removed > 0 {
+            self.save()?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Forget an entry and delete its folder
+    fn remove(&mut self, index: usize) -> Result<(), WorkspaceError> {
+        let entry = self.entries.remove(index);
+        remove_item(&self.dir.join(&entry.id));
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), WorkspaceError> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to create trash folder: {}", e)))?;
+
+        let json = serde_json::to_string(&self.entries)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to serialize trash index: {}", e)))?;
+        fs::write(self.dir.join(INDEX_FILE), json)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to save trash index: {}", e)))
+    }
+}
+
+/// Load the trash and run `f` on it, one caller at a time
+pub fn with_trash<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut Trash) -> Result<T, WorkspaceError>,
+) -> Result<T, WorkspaceError> {
+    let _guard = TRASH_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut trash = Trash::open(paths::get_data_directory(app)?.join(TRASH_DIR))?;
+    f(&mut trash)
+}
+
+/// Remove a trash entry's folder, logging failures
+fn remove_item(entry_dir: &Path) {
+    if let Err(e) = fs::remove_dir_all(entry_dir) {
+        log::warn!("Failed to remove {}: {}", entry_dir.display(), e);
+    }
+}
+
+/// Remove expired items, logging the outcome
+pub fn expire(app: &AppHandle) {
+    let max_age_days = retention_days(app);
+    let result = with_trash(app, |trash| trash.expire(max_age_days));
+
+    match result {
+        Ok(0) => {}
+        Ok(removed) => log::info!("Removed {} expired item(s) from the trash", removed),
+        Err(e) => log::error!("Failed to expire trash: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_restore_expire() {
+        let temp = tempfile::tempdir().unwrap();
+        let base = temp.path();
+        fs::create_dir_all(base.join("scripts/sub")).unwrap();
+        fs::write(base.join("scripts/sub/a.lua"), "print(1)").unwrap();
+        let workspace = Workspace::new(base, ["scripts".to_string()]).unwrap();
+
+        let mut trash = Trash::open(base.join(".proxima").join(TRASH_DIR)).unwrap();
+        let path = workspace.resolve_entry("scripts/sub").unwrap();
+        let entry = trash.put(&path, "scripts/sub".to_string(), true).unwrap();
+        assert!(!base.join("scripts/sub").exists());
+
+        // The index survives a reload
+        let mut trash = Trash::open(base.join(".proxima").join(TRASH_DIR)).unwrap();
+        assert_eq!(trash.iter().count(), 1);
+
+        let restored = trash.restore(&entry.id, &workspace).unwrap();
+        assert_eq!(restored, "scripts/sub");
+        assert_eq!(
+            fs::read_to_string(base.join("scripts/sub/a.lua")).unwrap(),
+            "print(1)"
+        );
+        assert_eq!(trash.iter().count(), 0);
+
+        let path = workspace.resolve_entry("scripts/sub/a.lua").unwrap();
+        let entry = trash
+            .put(&path, "scripts/sub/a.lua".to_string(), false)
+            .unwrap();
+        assert_eq!(trash.expire(1).unwrap(), 0);
+        trash.entries[0].deleted_at = 0;
+        assert_eq!(trash.expire(1).unwrap(), 1);
+        assert!(!trash.dir.join(&entry.id).exists());
+    }
+
+    #[test]
+    fn test_corrupt_index() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(INDEX_FILE), "[{\"id\":").unwrap();
+
+        // The index is left as it was for the user to fix
+        assert!(matches!(
+            Trash::open(dir.to_path_buf()),
+            Err(WorkspaceError::Io(_))
+        ));
+        assert_eq!(
+            fs::read_to_string(dir.join(INDEX_FILE)).unwrap(),
+            "[{\"id\":"
+        );
+    }
+}
@@ -0,0 +1,611 @@
+//! Local version history for workspace files
+//!
+//! Editor saves and external changes seen by the file watcher are snapshotted into
+//! `.proxima/versions/`. Each content is stored once in `objects/<hash>`, and `index.json`
+//! lists the versions of every file. Saving content a file already had moves that version
+//! to the top instead of adding a copy. Each file keeps at most `versions.maxPerFile`
+//! versions.
+
+use crate::models::{DiffKind, DiffLine, FileChange, FileChangeKind, FileVersion, VersionSource};
+use crate::utils::time::now_millis;
+use crate::utils::{paths, settings};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use super::history::hash_script;
+use super::workspace::WorkspaceError;
+
+const VERSIONS_DIR: &str = "versions";
+const OBJECTS_DIR: &str = "objects";
+const INDEX_FILE: &str = "index.json";
+const DEFAULT_MAX_PER_FILE: usize = 50;
+/// Larger files aren't snapshotted
+const MAX_SNAPSHOT_BYTES: usize = 1024 * 1024;
+
+/// Serializes index access between commands and the file watcher thread
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Versions kept per file, from the "versions" settings section
+pub fn max_per_file(app: &AppHandle) -> usize {
+    settings::get_settings_section(app, "versions")
+        .and_then(|versions| versions.get("maxPerFile").and_then(|v| v.as_u64()))
+        .map(|v| v as usize)
+        .unwrap_or(DEFAULT_MAX_PER_FILE)
+}
+
+/// Snapshot contents and the index of every file's versions
+///
+/// Versions are kept oldest first.
+pub struct VersionStore {
+    dir: PathBuf,
+    files: BTreeMap<String, Vec<FileVersion>>,
+}
+
+impl VersionStore {
+    fn open(dir: PathBuf) -> Self {
+        let files = match fs::read_to_string(dir.join(INDEX_FILE)) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::error!("Failed to parse version index: {}", e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+
+        Self { dir, files }
+    }
+
+    /// Versions of a file, newest first
+    pub fn list(&self, relative_path: &str) -> Vec<FileVersion> {
+        self.files
+            .get(relative_path)
+            .map(|versions| versions.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Record content as the newest version of a file
+    ///
+    /// # Returns
+    /// * `Ok(Some(FileVersion))` - The new newest version
+    /// * `Ok(None)` - If the content is already the newest version
+    pub fn snapshot(
+        &mut self,
+        relative_path: &str,
+        content: &str,
+        source: VersionSource,
+    ) -> Result<Option<FileVersion>, WorkspaceError> {
+        let hash = hash_script(content);
+        let versions = self.files.entry(relative_path.to_string()).or_default();
+        if versions.last().is_some_and(|version| version.hash == hash) {
+            return Ok(None);
+        }
+
+        let object = self.dir.join(OBJECTS_DIR).join(&hash);
+        if !object.exists() {
+            fs::create_dir_all(self.dir.join(OBJECTS_DIR)).map_err(|e| {
+                WorkspaceError::Io(format!("Failed to create versions folder: {}", e))
+            })?;
+            fs::write(&object, content)
+                .map_err(|e| WorkspaceError::Io(format!("Failed to write version: {}", e)))?;
+        }
+
+        let version = FileVersion {
+            hash,
+            saved_at: now_millis(),
+            size: content.len() as u64,
+            source,
+        };
+        versions.retain(|existing| existing.hash != version.hash);
+        versions.push(version.clone());
+
+        Ok(Some(version))
+    }
+
+    /// Read the content of one of a file's versions
+    pub fn read(&self, relative_path: &str, hash: &str) -> Result<String, WorkspaceError> {
+        let known = self
+            .files
+            .get(relative_path)
+            .is_some_and(|versions| versions.iter().any(|version| version.hash == hash));
+        if !known {
+            return Err(WorkspaceError::NotFound(format!(
+                "{} version {}",
+                relative_path, hash
+            )));
+        }
+
+        fs::read_to_string(self.dir.join(OBJECTS_DIR).join(hash))
+            .map_err(|e| WorkspaceError::Io(format!("Failed to read version: {}", e)))
+    }
+
+    /// Move the versions of a file, or of every file in a folder, to its new path
+    pub fn rename(&mut self, old_path: &str, new_path: &str) {
+        let folder_prefix = format!("{}/", old_path);
+        let moved: Vec<String> = self
+            .files
+            .keys()
+            .filter(|path| *path == old_path || path.starts_with(&folder_prefix))
+            .cloned()
+            .collect();
+
+        for path in moved {
+            if let Some(versions) = self.files.remove(&path) {
+                let renamed = format!("{}{}", new_path, &path[old_path.len()..]);
+                self.files.insert(renamed, versions);
+            }
+        }
+    }
+
+    /// Apply changes made outside the editor under `base_dir`
+    ///
+    /// Renamed files keep their versions under the new path, and created or modified text
+    /// files up to 1 MiB are snapshotted.
+    ///
+    /// # Returns
+    /// Whether the index changed
+    pub fn apply_changes(
+        &mut self,
+        base_dir: &Path,
+        changes: &[FileChange],
+    ) -> Result<bool, WorkspaceError> {
+        let mut changed = false;
+        for change in changes {
+            if let (FileChangeKind::Renamed, Some(old_path)) = (&change.kind, &change.old_path) {
+                self.rename(old_path, &change.path);
+                changed = true;
+            }
+            if change.kind == FileChangeKind::Deleted {
+                continue;
+            }
+
+            let path = base_dir.join(&change.path);
+            let small_file = fs::metadata(&path).is_ok_and(|metadata| {
+                metadata.is_file() && metadata.len() as usize <= MAX_SNAPSHOT_BYTES
+            });
+            if !small_file {
+                continue;
+            }
+            if let Ok(content) = fs::read_to_string(&path) {
+                changed |= self
+                    .snapshot(&change.path, &content, VersionSource::External)?
+                    .is_some();
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Drop each file's oldest versions beyond `max_per_file`, and contents no version uses
+    ///
+    /// # Returns
+    /// The number of versions removed
+    pub fn prune(&mut self, max_per_file: usize) -> usize {
+        let mut removed = 0;
+        for versions in self.files.values_mut() {
+            let excess = versions.len().saturating_sub(max_per_file);
+            versions.drain(..excess);
+            removed += excess;
+        }
+        self.files.retain(|_, versions| !versions.is_empty());
+
+        if removed > 0 {
+            let used: HashSet<&str> = self
+                .files
+                .values()
+                .flatten()
+                .map(|version| version.hash.as_str())
+                .collect();
+
+            if let Ok(objects) = fs::read_dir(self.dir.join(OBJECTS_DIR)) {
+                for object in objects.flatten() {
+                    let name = object.file_name();
+                    if !used.contains(name.to_string_lossy().as_ref()) {
+                        let _ = fs::remove_file(object.path());
+                    }
+                }
+            }
+        }
+
+        removed
+    }
+
+    pub fn save(&self) -> Result<(), WorkspaceError> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to create versions folder: {}", e)))?;
+
+        let json = serde_json::to_string(&self.files)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to serialize version index: {}", e)))?;
+        fs::write(self.dir.join(INDEX_FILE), json)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to save version index: {}", e)))
+    }
+}
+
+/// Load the version store and run `f` on it, one caller at a time
+pub fn with_store<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut VersionStore) -> Result<T, WorkspaceError>,
+) -> Result<T, WorkspaceError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut store = VersionStore::open(paths::get_data_directory(app)?.join(VERSIONS_DIR));
+    f(&mut store)
+}
+
+/// Snapshot a file's content, logging failures
+///
+/// Content over 1 MiB is skipped.
+pub fn record(app: &AppHandle, relative_path: &str, content: &str, source: VersionSource) {
+    if content.len() > MAX_SNAPSHOT_BYTES {
+        return;
+    }
+
+    let result = with_store(app, |store| {
+        if store.snapshot(relative_path, content, source)?.is_some() {
+            store.prune(max_per_file(app));
+            store.save()?;
+        }
+        Ok(())
+    });
+
+    if let Err(e) = result {
+        log::error!("Failed to record version of {}: {}", relative_path, e);
+    }
+}
+
+/// Apply a batch of file watcher changes to the version history, logging failures
+///
+/// The store is opened, pruned and saved once per batch.
+pub fn record_changes(app: &AppHandle, base_dir: &Path, changes: &[FileChange]) {
+    let result = with_store(app, |store| {
+        if store.apply_changes(base_dir, changes)? {
+            store.prune(max_per_file(app));
+            store.save()?;
+        }
+        Ok(())
+    });
+
+    if let Err(e) = result {
+        log::error!("Failed to record versions of changed files: {}", e);
+    }
+}
+
+/// Keep a renamed file's versions, logging failures
+pub fn rename(app: &AppHandle, old_path: &str, new_path: &str) {
+    let result = with_store(app, |store| {
+        store.rename(old_path, new_path);
+        store.save()
+    });
+
+    if let Err(e) = result {
+        log::error!("Failed to move versions of {}: {}", old_path, e);
+    }
+}
+
+/// Line diff between two texts
+///
+/// Uses Myers' algorithm with its linear space refinement, so memory grows with the
+/// number of lines rather than their product.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let mut diff = DiffBuilder::default();
+    diff_region(&old, &new, &mut diff);
+    diff.lines
+}
+
+/// Diff two runs of lines, splitting the changed middle at a point on a shortest edit path
+fn diff_region(old: &[&str], new: &[&str], diff: &mut DiffBuilder) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    for line in &old[..prefix] {
+        diff.push(DiffKind::Equal, line);
+    }
+
+    if a.is_empty() || b.is_empty() {
+        for line in a {
+            diff.push(DiffKind::Removed, line);
+        }
+        for line in b {
+            diff.push(DiffKind::Added, line);
+        }
+    } else {
+        match middle_split(a, b) {
+            Some((x, y)) => {
+                diff_region(&a[..x], &b[..y], diff);
+                diff_region(&a[x..], &b[y..], diff);
+            }
+            None => {
+                for line in a {
+                    diff.push(DiffKind::Removed, line);
+                }
+                for line in b {
+                    diff.push(DiffKind::Added, line);
+                }
+            }
+        }
+    }
+
+    for line in &old[old.len() - suffix..] {
+        diff.push(DiffKind::Equal, line);
+    }
+}
+
+/// Find where the forward and backward searches for a shortest edit path meet
+///
+/// Each search keeps the furthest point reached on every diagonal, for one edit count at
+/// a time. `a` and `b` must be non-empty and differ in their first and last lines.
+///
+/// # Returns
+/// The split point in `a` and `b`, or `None` if the texts have no line in common
+fn middle_split(a: &[&str], b: &[&str]) -> Option<(usize, usize)> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d;
+    let length = 2 * max_d as usize + 2;
+    let delta = n - m;
+    // With an odd delta the paths meet during a forward step, otherwise a backward one
+    let front = delta % 2 != 0;
+
+    let mut forward = vec![-1isize; length];
+    let mut backward = vec![-1isize; length];
+    forward[offset as usize + 1] = 0;
+    backward[offset as usize + 1] = 0;
+
+    // Diagonals that ran off the edges of the grid are skipped from then on
+    let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0, 0, 0, 0);
+
+    for d in 0..max_d {
+        let mut k1 = -d + k1_start;
+        while k1 <= d - k1_end {
+            let k1_offset = (offset + k1) as usize;
+            let mut x1 = if k1 == -d || (k1 != d && forward[k1_offset - 1] < forward[k1_offset + 1])
+            {
+                forward[k1_offset + 1]
+            } else {
+                forward[k1_offset - 1] + 1
+            };
+            let mut y1 = x1 - k1;
+            while x1 < n && y1 < m && a[x1 as usize] == b[y1 as usize] {
+                x1 += 1;
+                y1 += 1;
+            }
+            forward[k1_offset] = x1;
+
+            if x1 > n {
+                k1_end += 2;
+            } else if y1 > m {
+                k1_start += 2;
+            } else if front {
+                let k2_offset = offset + delta - k1;
+                if (0..length as isize).contains(&k2_offset)
+                    && backward[k2_offset as usize] != -1
+                    && x1 >= n - backward[k2_offset as usize]
+                {
+                    return Some((x1 as usize, y1 as usize));
+                }
+            }
+            k1 += 2;
+        }
+
+        let mut k2 = -d + k2_start;
+        while k2 <= d - k2_end {
+            let k2_offset = (offset + k2) as usize;
+            let mut x2 =
+                if k2 == -d || (k2 != d && backward[k2_offset - 1] < backward[k2_offset + 1]) {
+                    backward[k2_offset + 1]
+                } else {
+                    backward[k2_offset - 1] + 1
+                };
+            let mut y2 = x2 - k2;
+            while x2 < n && y2 < m && a[(n - x2 - 1) as usize] == b[(m - y2 - 1) as usize] {
+                x2 += 1;
+                y2 += 1;
+            }
+            backward[k2_offset] = x2;
+
+            if x2 > n {
+                k2_end += 2;
+            } else if y2 > m {
+                k2_start += 2;
+            } else if !front {
+                let k1_offset = offset + delta - k2;
+                if (0..length as isize).contains(&k1_offset) && forward[k1_offset as usize] != -1 {
+                    let x1 = forward[k1_offset as usize];
+                    let y1 = offset + x1 - k1_offset;
+                    if x1 >= n - x2 {
+                        return Some((x1 as usize, y1 as usize));
+                    }
+                }
+            }
+            k2 += 2;
+        }
+    }
+
+    None
+}
+
+/// Numbers diff lines on each side as they are added
+#[derive(Default)]
+struct DiffBuilder {
+    lines: Vec<DiffLine>,
+    old_line: usize,
+    new_line: usize,
+}
+
+impl DiffBuilder {
+    fn push(&mut self, kind: DiffKind, text: &str) {
+        let old_line = (kind != DiffKind::Added).then(|| {
+            self.old_line += 1;
+            self.old_line
+        });
+        let new_line = (kind != DiffKind::Removed).then(|| {
+            self.new_line += 1;
+            self.new_line
+        });
+
+        self.lines.push(DiffLine {
+            kind,
+            text: text.to_string(),
+            old_line,
+            new_line,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_dedup_and_prune() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let mut store = VersionStore::open(dir.to_path_buf());
+
+        let first = store
+            .snapshot("scripts/a.lua", "print(1)", VersionSource::Save)
+            .unwrap()
+            .unwrap();
+        assert!(store
+            .snapshot("scripts/a.lua", "print(1)", VersionSource::External)
+            .unwrap()
+            .is_none());
+        store
+            .snapshot("scripts/a.lua", "print(2)", VersionSource::Save)
+            .unwrap();
+
+        // Content the file had before moves back to the top
+        store
+            .snapshot("scripts/a.lua", "print(1)", VersionSource::Restore)
+            .unwrap();
+        let versions = store.list("scripts/a.lua");
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].hash, first.hash);
+        assert_eq!(versions[0].source, VersionSource::Restore);
+
+        store.rename("scripts", "scripts/old");
+        assert!(store.list("scripts/a.lua").is_empty());
+        assert_eq!(
+            store.read("scripts/old/a.lua", &first.hash).unwrap(),
+            "print(1)"
+        );
+
+        assert_eq!(store.prune(1), 1);
+        assert_eq!(fs::read_dir(dir.join(OBJECTS_DIR)).unwrap().count(), 1);
+
+        store.save().unwrap();
+        assert_eq!(
+            VersionStore::open(dir.to_path_buf())
+                .list("scripts/old/a.lua")
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_apply_changes() {
+        let temp = tempfile::tempdir().unwrap();
+        let base = temp.path();
+        let mut store = VersionStore::open(base.join(".proxima/versions"));
+        fs::create_dir_all(base.join("scripts")).unwrap();
+        fs::write(base.join("scripts/a.lua"), "print(1)").unwrap();
+
+        let change = |kind, path: &str, old_path: Option<&str>| FileChange {
+            kind,
+            path: path.to_string(),
+            old_path: old_path.map(String::from),
+        };
+        assert!(store
+            .apply_changes(
+                base,
+                &[change(FileChangeKind::Created, "scripts/a.lua", None)]
+            )
+            .unwrap());
+
+        // A rename outside the app keeps the history, and unchanged content isn't recorded
+        fs::rename(base.join("scripts/a.lua"), base.join("scripts/b.lua")).unwrap();
+        assert!(store
+            .apply_changes(
+                base,
+                &[change(
+                    FileChangeKind::Renamed,
+                    "scripts/b.lua",
+                    Some("scripts/a.lua")
+                )]
+            )
+            .unwrap());
+        assert!(store.list("scripts/a.lua").is_empty());
+        assert_eq!(store.list("scripts/b.lua").len(), 1);
+
+        assert!(!store
+            .apply_changes(
+                base,
+                &[
+                    change(FileChangeKind::Modified, "scripts/b.lua", None),
+                    change(FileChangeKind::Deleted, "scripts/c.lua", None),
+                ]
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc\nd", "a\nc\nx\nd");
+        let summary: Vec<_> = diff
+            .iter()
+            .map(|line| (line.kind, line.text.as_str(), line.old_line, line.new_line))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (DiffKind::Equal, "a", Some(1), Some(1)),
+                (DiffKind::Removed, "b", Some(2), None),
+                (DiffKind::Equal, "c", Some(3), Some(2)),
+                (DiffKind::Added, "x", None, Some(3)),
+                (DiffKind::Equal, "d", Some(4), Some(4)),
+            ]
+        );
+        assert!(diff_lines("same", "same")
+            .iter()
+            .all(|line| line.kind == DiffKind::Equal));
+    }
+
+    #[test]
+    fn test_diff_lines_large() {
+        let old: Vec<String> = (0..20_000).map(|i| format!("line {}", i)).collect();
+        let mut new = old.clone();
+        new[100] = "changed".to_string();
+        new.remove(5_000);
+        new.insert(15_000, "inserted".to_string());
+        new.truncate(19_000);
+
+        let diff = diff_lines(&old.join("\n"), &new.join("\n"));
+        let side = |keep: DiffKind| -> Vec<&str> {
+            diff.iter()
+                .filter(|line| line.kind == DiffKind::Equal || line.kind == keep)
+                .map(|line| line.text.as_str())
+                .collect()
+        };
+        assert_eq!(side(DiffKind::Removed), old);
+        assert_eq!(side(DiffKind::Added), new);
+
+        // One line changed, one removed, one inserted and 1000 cut from the end
+        let edits = diff
+            .iter()
+            .filter(|line| line.kind != DiffKind::Equal)
+            .count();
+        assert_eq!(edits, 2 + 1 + 1 + 1_000);
+    }
+}
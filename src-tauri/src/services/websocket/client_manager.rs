@@ -1,51 +1,143 @@
-use crate::models::Client;
-use crate::state::ClientRegistry;
+use crate::models::{Client, ScriptPayload, TemplateVariables};
+use crate::state::{ClientInfo, ClientRegistry};
+use crate::utils::clients::ClientRequestError;
 use crate::utils::events::emit_or_log;
+use crate::utils::template;
+use serde_json::Value;
+use std::time::Duration;
 use tauri::AppHandle;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
 use tokio_tungstenite::tungstenite::Message;
 
 use super::messages::ServerMessage;
 
+/// Close reason sent to clients when the WebSocket server shuts down
+pub const SHUTDOWN_REASON: &str = "Proxima closed";
+
+// How long to wait for clients to acknowledge a shutdown before giving up
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Broadcast a script to multiple clients
+///
+/// Templated scripts are expanded and serialized for each client; otherwise every
+/// client gets the same message.
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - IDs of the clients the script was sent to
+/// * `Err(String)` - If the exec message could not be serialized
 pub async fn broadcast_to_clients(
     client_ids: Vec<String>,
-    script: String,
-    redirect: bool,
+    payload: ScriptPayload,
     clients: ClientRegistry,
-) -> Result<(), String> {
+) -> Result<Vec<String>, String> {
     log::info!("Broadcasting script to {} client(s)", client_ids.len());
 
-    let execute_msg = ServerMessage::Exec { script, redirect };
-    let message_text = serde_json::to_string(&execute_msg)
-        .map_err(|e| format!("Failed to serialize exec message: {}", e))?;
+    // Untemplated scripts are serialized once for every client
+    let shared_text = match payload.template {
+        Some(_) => String::new(),
+        None => exec_message(payload.script.clone(), &payload)?,
+    };
 
     let clients_lock = clients.read().await;
-    let mut executed_count = 0;
-
-    for client_id in &client_ids {
-        if let Some(client_info) = clients_lock.get(client_id) {
-            if client_info
-                .sender
-                .send(Message::Text(message_text.clone()))
-                .is_ok()
-            {
-                executed_count += 1;
-                log::debug!("Sent to client: {}", client_id);
-            } else {
-                log::error!("Failed to send to client: {}", client_id);
+    let mut delivered = Vec::new();
+
+    for (index, client_id) in client_ids.iter().enumerate() {
+        let Some(client_info) = clients_lock.get(client_id) else {
+            log::error!("Client not found: {}", client_id);
+            continue;
+        };
+
+        let message_text = match &payload.template {
+            Some(template) => {
+                let context = TemplateContext {
+                    client_id,
+                    client: client_info,
+                    index: index + 1,
+                    count: client_ids.len(),
+                };
+                let script =
+                    template::expand(&payload.script, |name| context.lookup(template, name));
+                exec_message(script, &payload)?
             }
+            None => shared_text.clone(),
+        };
+
+        if client_info.sender.send(Message::Text(message_text)).is_ok() {
+            delivered.push(client_id.clone());
+            log::debug!("Sent to client: {}", client_id);
         } else {
-            log::error!("Client not found: {}", client_id);
+            log::error!("Failed to send to client: {}", client_id);
         }
     }
 
     log::info!(
         "Successfully sent to {}/{} clients",
-        executed_count,
+        delivered.len(),
         client_ids.len()
     );
 
-    Ok(())
+    Ok(delivered)
+}
+
+/// Serialize an exec message for a script
+fn exec_message(script: String, payload: &ScriptPayload) -> Result<String, String> {
+    let args = payload.args.clone().map(chunk_args);
+    let execute_msg = ServerMessage::Exec {
+        id: payload.id.clone(),
+        script,
+        redirect: payload.redirect,
+        delay: None,
+        wait_for_load: false,
+        line_map: payload.line_map.clone(),
+        arg_count: args.as_ref().map(Vec::len),
+        args,
+    };
+
+    serde_json::to_string(&execute_msg)
+        .map_err(|e| format!("Failed to serialize exec message: {}", e))
+}
+
+/// Spread a JSON array into separate chunk arguments; any other value is a single argument
+fn chunk_args(args: Value) -> Vec<Value> {
+    match args {
+        Value::Array(values) => values,
+        value => vec![value],
+    }
+}
+
+/// The client a templated script is being expanded for
+struct TemplateContext<'a> {
+    client_id: &'a str,
+    client: &'a ClientInfo,
+    /// 1-based position of the client among the targets
+    index: usize,
+    count: usize,
+}
+
+impl TemplateContext<'_> {
+    /// Resolve a variable, preferring built-ins, then the client's own variables, then
+    /// variables shared by all clients
+    fn lookup(&self, template: &TemplateVariables, name: &str) -> Option<String> {
+        let builtin = match name {
+            "username" => Some(self.client.username.clone()),
+            "client_id" => Some(self.client_id.to_string()),
+            "place_id" => self.client.place_id.map(|id| id.to_string()),
+            "game_id" => self.client.game_id.map(|id| id.to_string()),
+            "index" => Some(self.index.to_string()),
+            "count" => Some(self.count.to_string()),
+            _ => None,
+        };
+
+        builtin
+            .or_else(|| {
+                [self.client.username.as_str(), self.client_id]
+                    .iter()
+                    .find_map(|key| template.clients.get(*key)?.get(name).cloned())
+            })
+            .or_else(|| template.variables.get(name).cloned())
+    }
 }
 
 /// Get list of all attached clients
@@ -66,17 +158,17 @@ pub async fn send_to_client(
     client_id: &str,
     message: &str,
     clients: &ClientRegistry,
-) -> Result<(), String> {
+) -> Result<(), ClientRequestError> {
     let clients_lock = clients.read().await;
 
     if let Some(client_info) = clients_lock.get(client_id) {
         client_info
             .sender
             .send(Message::Text(message.to_string()))
-            .map_err(|e| format!("Failed to send message to client: {}", e))?;
+            .map_err(|e| ClientRequestError::SendFailed(e.to_string()))?;
         Ok(())
     } else {
-        Err(format!("Client not found: {}", client_id))
+        Err(ClientRequestError::ClientNotFound(client_id.to_string()))
     }
 }
 
@@ -94,3 +186,37 @@ pub async fn emit_clients_update(app_handle: &AppHandle, clients: &ClientRegistr
 
     emit_or_log(app_handle, "clients-update", clients_list);
 }
+
+/// Tell a client the server is going away and close its connection
+///
+/// Sends a `shutdown` message with the reason first, since not every executor
+/// exposes the close frame reason to scripts.
+pub fn send_shutdown(sender: &UnboundedSender<Message>, reason: &str) {
+    let shutdown_msg = ServerMessage::Shutdown {
+        reason: reason.to_string(),
+    };
+    if let Ok(text) = serde_json::to_string(&shutdown_msg) {
+        let _ = sender.send(Message::Text(text));
+    }
+
+    let _ = sender.send(Message::Close(Some(CloseFrame {
+        code: CloseCode::Away,
+        reason: reason.to_string().into(),
+    })));
+}
+
+/// Wait for all registered clients to disconnect after a shutdown
+///
+/// Returns early once the registry is empty, or after a short timeout.
+pub async fn wait_for_disconnects(clients: &ClientRegistry) {
+    let deadline = tokio::time::Instant::now() + SHUTDOWN_TIMEOUT;
+
+    while tokio::time::Instant::now() < deadline {
+        if clients.read().await.is_empty() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    log::warn!("Timed out waiting for clients to disconnect");
+}
@@ -1,94 +1,173 @@
-use crate::services::autoexec;
-use crate::utils::paths;
-use serde_json::Value;
+use crate::models::{AutoexecStatus, ExecutionSource, ScriptPayload};
+use crate::services::autoexec::{self, AutoexecScript, ClientContext, RuleMatch};
+use crate::services::executions;
+use crate::services::history::{self, Execution};
+use crate::state::ClientRegistry;
+use crate::utils::settings;
 use tauri::AppHandle;
-use tauri_plugin_store::StoreExt;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio_tungstenite::tungstenite::Message;
 
+use super::super::client_manager::send_to_client;
 use super::super::messages::ServerMessage;
 
 /// Handle the "ready" message from a client
-pub async fn handle_ready(tx: &UnboundedSender<Message>, app_handle: &AppHandle) {
+///
+/// Sends the autoexec scripts whose rules match the client.
+///
+/// # Returns
+/// * The scripts restricted by username, to be checked once the client registers
+/// * The execution IDs of the scripts sent, to be tracked once the client registers
+pub async fn handle_ready(
+    tx: &UnboundedSender<Message>,
+    app_handle: &AppHandle,
+    client: &ClientContext,
+) -> (Vec<AutoexecScript>, Vec<String>) {
     log::info!("Client ready, sending auto-execute scripts");
 
     // Check if auto-execute is enabled
-    let (auto_execute, redirect_print) = get_execution_settings(app_handle).await;
-
-    if auto_execute {
-        // Get autoexec scripts
-        let scripts = autoexec::get_autoexec_scripts(app_handle);
-
-        if !scripts.is_empty() {
-            let script_count = scripts.len();
-
-            // Log autoexec
-            let script_text = if script_count == 1 {
-                "1 script".to_string()
-            } else {
-                format!("{} scripts", script_count)
-            };
-            log_ui!(
-                app_handle,
-                Success,
-                "Auto-executing {} on new client",
-                script_text
-            );
-
-            // Execute each script on this client
-            for script in scripts {
-                let execute_msg = ServerMessage::Exec {
-                    script,
-                    redirect: redirect_print,
-                };
-                if let Ok(msg_text) = serde_json::to_string(&execute_msg) {
-                    if tx.send(Message::Text(msg_text)).is_err() {
-                        log::error!("Failed to send autoexec script");
-                        break;
-                    }
-                }
-            }
+    let (auto_execute, redirect_print) = settings::get_execution_settings(app_handle);
+
+    if !auto_execute {
+        return (Vec::new(), Vec::new());
+    }
+
+    let mut scripts = Vec::new();
+    let mut deferred = Vec::new();
+
+    let plan = autoexec::plan_autoexec(autoexec::get_autoexec_scripts(app_handle), client);
+    for (script, status, reason) in plan {
+        match status {
+            AutoexecStatus::Run => scripts.push(script),
+            AutoexecStatus::Deferred => deferred.push(script),
+            AutoexecStatus::Skipped => log::debug!(
+                "Skipping autoexec script {} ({})",
+                script.path,
+                reason.unwrap_or_default()
+            ),
         }
     }
+
+    let sent = send_autoexec(tx, app_handle, scripts, redirect_print, None).await;
+    (deferred, sent)
+}
+
+/// Send autoexec scripts that were waiting for the client to register
+pub async fn handle_registered(
+    tx: &UnboundedSender<Message>,
+    app_handle: &AppHandle,
+    client: &ClientContext,
+    client_id: &str,
+    deferred: Vec<AutoexecScript>,
+) {
+    if deferred.is_empty() {
+        return;
+    }
+
+    // Deferred scripts are already in run order
+    let (_, redirect_print) = settings::get_execution_settings(app_handle);
+    let scripts = deferred
+        .into_iter()
+        .filter(|script| script.rules.matches(client) == RuleMatch::Run)
+        .collect();
+
+    send_autoexec(tx, app_handle, scripts, redirect_print, Some(client_id)).await;
 }
 
-/// Read the execution settings from the Tauri store
-/// Returns (autoExecute, redirectPrint)
-async fn get_execution_settings(app: &AppHandle) -> (bool, bool) {
-    // Get the base directory (same as scripts/autoexec location)
-    let base_dir = match paths::get_base_directory(app) {
-        Ok(dir) => dir,
-        Err(e) => {
-            log::error!("Failed to get base directory: {}", e);
-            return (true, false); // Default to auto_execute=true, redirect=false on error
-        }
+/// Send autoexec scripts to a client and record them in the history
+///
+/// Scripts sent to a registered client are tracked as running right away.
+///
+/// # Returns
+/// Execution IDs of the scripts sent
+async fn send_autoexec(
+    tx: &UnboundedSender<Message>,
+    app_handle: &AppHandle,
+    scripts: Vec<AutoexecScript>,
+    redirect_print: bool,
+    client_id: Option<&str>,
+) -> Vec<String> {
+    let mut sent = Vec::new();
+
+    if scripts.is_empty() {
+        return sent;
+    }
+
+    let script_count = scripts.len();
+
+    // Log autoexec
+    let script_text = if script_count == 1 {
+        "1 script".to_string()
+    } else {
+        format!("{} scripts", script_count)
     };
+    log_ui!(
+        app_handle,
+        Success,
+        "Auto-executing {} on new client",
+        script_text
+    );
+
+    // Execute each script on this client
+    for script in scripts {
+        let payload = ScriptPayload::new(script.content, redirect_print);
+        let execute_msg = ServerMessage::Exec {
+            id: payload.id.clone(),
+            script: payload.script.clone(),
+            redirect: redirect_print,
+            delay: script.rules.delay,
+            wait_for_load: script.rules.wait_for_load,
+            line_map: None,
+            args: None,
+            arg_count: None,
+        };
+        let result: Result<Vec<String>, String> = serde_json::to_string(&execute_msg)
+            .map_err(|e| format!("Failed to serialize exec message: {}", e))
+            .and_then(|msg_text| {
+                tx.send(Message::Text(msg_text))
+                    .map(|_| client_id.map(str::to_string).into_iter().collect())
+                    .map_err(|_| "Client disconnected".to_string())
+            });
+        let failed = result.is_err();
+
+        if let Ok(delivered) = &result {
+            sent.push(payload.id.clone());
+            executions::track(
+                app_handle,
+                &payload.id,
+                ExecutionSource::Autoexec,
+                delivered,
+            )
+            .await;
+        }
+
+        // Scripts sent on ready go out before the client registers, so they have no target ID
+        let execution = Execution {
+            source: ExecutionSource::Autoexec,
+            client_ids: client_id.map(str::to_string).into_iter().collect(),
+            payload,
+            rerun_of: None,
+        };
+        history::record(app_handle, execution, &result).await;
 
-    let settings_path = base_dir.join("settings.json");
-    let settings_path_str = settings_path.to_string_lossy().to_string();
-
-    match app.store(&settings_path_str) {
-        Ok(store) => match store.get("settings") {
-            Some(Value::Object(settings)) => {
-                if let Some(Value::Object(execution)) = settings.get("execution") {
-                    let auto_execute = execution
-                        .get("autoExecute")
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(true);
-                    let redirect_print = execution
-                        .get("redirectPrint")
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(false);
-                    return (auto_execute, redirect_print);
-                }
-            }
-            _ => {}
-        },
-        Err(e) => {
-            log::error!("Failed to access settings store: {}", e);
+        if failed {
+            log::error!("Failed to send autoexec script");
+            break;
         }
     }
 
-    // Default to auto_execute=true, redirect=false if settings not found
-    (true, false)
+    sent
+}
+
+/// Send exec_cancel message to a client
+pub async fn send_exec_cancel(
+    client_id: &str,
+    id: &str,
+    clients: &ClientRegistry,
+) -> Result<(), String> {
+    let msg = ServerMessage::ExecCancel { id: id.to_string() };
+    let msg_text = serde_json::to_string(&msg)
+        .map_err(|e| format!("Failed to serialize exec_cancel message: {}", e))?;
+
+    Ok(send_to_client(client_id, &msg_text, clients).await?)
 }
@@ -1,5 +1,6 @@
 use crate::models::explorer::*;
 use crate::state::ClientRegistry;
+use crate::utils::clients::ClientRequestError;
 use crate::utils::events::emit_or_log;
 use serde::Serialize;
 use std::collections::HashMap;
@@ -77,7 +78,10 @@ pub fn handle_exp_decompiled(app_handle: &AppHandle, id: u32, source: String) {
 // Public API functions for sending messages to clients
 
 /// Send exp_start message to a client
-pub async fn send_start_explorer(client_id: &str, clients: &ClientRegistry) -> Result<(), String> {
+pub async fn send_start_explorer(
+    client_id: &str,
+    clients: &ClientRegistry,
+) -> Result<(), ClientRequestError> {
     let msg = ServerMessage::ExpStart;
     let msg_text = serde_json::to_string(&msg)
         .map_err(|e| format!("Failed to serialize exp_start message: {}", e))?;
@@ -86,7 +90,10 @@ pub async fn send_start_explorer(client_id: &str, clients: &ClientRegistry) -> R
 }
 
 /// Send exp_stop message to a client
-pub async fn send_stop_explorer(client_id: &str, clients: &ClientRegistry) -> Result<(), String> {
+pub async fn send_stop_explorer(
+    client_id: &str,
+    clients: &ClientRegistry,
+) -> Result<(), ClientRequestError> {
     let msg = ServerMessage::ExpStop;
     let msg_text = serde_json::to_string(&msg)
         .map_err(|e| format!("Failed to serialize exp_stop message: {}", e))?;
@@ -99,7 +106,7 @@ pub async fn send_get_explorer_tree(
     client_id: &str,
     expanded_ids: Vec<u32>,
     clients: &ClientRegistry,
-) -> Result<(), String> {
+) -> Result<(), ClientRequestError> {
     let msg = GetExplorerTreeMessage::new(expanded_ids);
     let msg_text = serde_json::to_string(&msg)
         .map_err(|e| format!("Failed to serialize exp_get_tree message: {}", e))?;
@@ -114,7 +121,7 @@ pub async fn send_get_explorer_properties(
     properties: Vec<crate::services::api_dump::PropertyMetadata>,
     special_properties: Vec<crate::services::api_dump::PropertyMetadata>,
     clients: &ClientRegistry,
-) -> Result<(), String> {
+) -> Result<(), ClientRequestError> {
     let msg = GetExplorerPropertiesMessage::new(id, properties, special_properties);
     let msg_text = serde_json::to_string(&msg)
         .map_err(|e| format!("Failed to serialize exp_get_properties message: {}", e))?;
@@ -129,7 +136,7 @@ pub async fn send_search_explorer(
     search_by: String,
     limit: u32,
     clients: &ClientRegistry,
-) -> Result<(), String> {
+) -> Result<(), ClientRequestError> {
     let msg = SearchExplorerMessage::new(query, search_by, limit);
     let msg_text = serde_json::to_string(&msg)
         .map_err(|e| format!("Failed to serialize exp_search message: {}", e))?;
@@ -142,7 +149,7 @@ pub async fn send_decompile_script(
     client_id: &str,
     id: u32,
     clients: &ClientRegistry,
-) -> Result<(), String> {
+) -> Result<(), ClientRequestError> {
     let msg = ServerMessage::ExpDecompile { id };
     let msg_text = serde_json::to_string(&msg)
         .map_err(|e| format!("Failed to serialize exp_decompile message: {}", e))?;
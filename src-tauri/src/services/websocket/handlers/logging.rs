@@ -3,7 +3,22 @@ use crate::utils::events::emit_or_log;
 use tauri::AppHandle;
 
 /// Handle log message from client
-pub fn handle_log(app_handle: &AppHandle, level: u8, message: String) {
-    let log_msg = LogMessage { level, message };
+///
+/// # Arguments
+/// * `execution_id` - The execution that printed the message, for redirected output
+/// * `client_id` - The client that sent the message, if registered
+pub fn handle_log(
+    app_handle: &AppHandle,
+    level: u8,
+    message: String,
+    execution_id: Option<String>,
+    client_id: Option<String>,
+) {
+    let log_msg = LogMessage {
+        level,
+        message,
+        execution_id,
+        client_id,
+    };
     emit_or_log(app_handle, "log-message", &log_msg);
 }
@@ -1,5 +1,6 @@
 use crate::models::remote_spy::*;
 use crate::state::ClientRegistry;
+use crate::utils::clients::ClientRequestError;
 use crate::utils::events::emit_or_log;
 use serde::Serialize;
 use tauri::AppHandle;
@@ -55,10 +56,7 @@ pub fn handle_rspy_decompiled(app_handle: &AppHandle, call_id: u32, source: Stri
     emit_remote_spy_event(
         app_handle,
         "remote-spy-decompiled",
-        serde_json::json!({
-            "callId": call_id,
-            "source": source,
-        }),
+        RemoteDecompiledEvent { call_id, source },
     );
 }
 
@@ -67,10 +65,7 @@ pub fn handle_rspy_generated_code(app_handle: &AppHandle, call_id: u32, code: St
     emit_remote_spy_event(
         app_handle,
         "remote-spy-generated-code",
-        serde_json::json!({
-            "callId": call_id,
-            "code": code,
-        }),
+        RemoteGeneratedCodeEvent { call_id, code },
     );
 }
 
@@ -80,7 +75,7 @@ pub fn handle_rspy_generated_code(app_handle: &AppHandle, call_id: u32, code: St
 pub async fn send_start_remote_spy(
     client_id: &str,
     clients: &ClientRegistry,
-) -> Result<(), String> {
+) -> Result<(), ClientRequestError> {
     let msg = ServerMessage::RspyStart;
     let msg_text = serde_json::to_string(&msg)
         .map_err(|e| format!("Failed to serialize rspy_start message: {}", e))?;
@@ -89,7 +84,10 @@ pub async fn send_start_remote_spy(
 }
 
 /// Send rspy_stop message to a client
-pub async fn send_stop_remote_spy(client_id: &str, clients: &ClientRegistry) -> Result<(), String> {
+pub async fn send_stop_remote_spy(
+    client_id: &str,
+    clients: &ClientRegistry,
+) -> Result<(), ClientRequestError> {
     let msg = ServerMessage::RspyStop;
     let msg_text = serde_json::to_string(&msg)
         .map_err(|e| format!("Failed to serialize rspy_stop message: {}", e))?;
@@ -102,7 +100,7 @@ pub async fn send_decompile_request(
     client_id: &str,
     call_id: u32,
     clients: &ClientRegistry,
-) -> Result<(), String> {
+) -> Result<(), ClientRequestError> {
     let msg = ServerMessage::RspyDecompile { call_id };
     let msg_text = serde_json::to_string(&msg)
         .map_err(|e| format!("Failed to serialize rspy_decompile message: {}", e))?;
@@ -115,7 +113,7 @@ pub async fn send_generate_code_request(
     client_id: &str,
     call_id: u32,
     clients: &ClientRegistry,
-) -> Result<(), String> {
+) -> Result<(), ClientRequestError> {
     let msg = ServerMessage::RspyGenerateCode { call_id };
     let msg_text = serde_json::to_string(&msg)
         .map_err(|e| format!("Failed to serialize rspy_generate_code message: {}", e))?;
@@ -1,8 +1,10 @@
 use crate::models::explorer::*;
 use crate::models::remote_spy::*;
+use crate::models::{ExecutionStatus, LineMapping};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use tokio_tungstenite::tungstenite::Message;
 
 // Custom deserializer for HashMap that handles empty arrays from Lua
 pub(super) fn deserialize_props<'de, D>(
@@ -23,18 +25,56 @@ where
     }
 }
 
+// Lua encodes an empty table as an object, and tags may be missing or malformed
+fn deserialize_tags<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(match value {
+        Value::Array(arr) => arr
+            .into_iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
 /// Messages sent from WebSocket clients to server
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "type")]
 pub(super) enum ClientMessage {
     #[serde(rename = "ready")]
-    Ready,
+    Ready {
+        #[serde(default)]
+        place_id: Option<u64>,
+        #[serde(default)]
+        game_id: Option<u64>,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default, deserialize_with = "deserialize_tags")]
+        tags: Vec<String>,
+    },
     #[serde(rename = "register")]
     Register { username: String },
     #[serde(rename = "pong")]
     Pong,
+    /// An execution's chunk, every thread it spawned and every connection it made have ended
+    #[serde(rename = "exec_done")]
+    ExecDone {
+        id: String,
+        status: ExecutionStatus,
+        #[serde(default)]
+        error: Option<String>,
+    },
     #[serde(rename = "log")]
-    Log { level: u8, message: String },
+    Log {
+        level: u8,
+        message: String,
+        /// Set for output redirected from an execution
+        #[serde(default)]
+        execution_id: Option<String>,
+    },
     #[serde(rename = "exp_tree")]
     ExpTree { nodes: Vec<ExplorerNode> },
     #[serde(rename = "exp_properties")]
@@ -99,7 +139,28 @@ pub(super) enum ClientMessage {
 #[serde(tag = "type")]
 pub(super) enum ServerMessage {
     #[serde(rename = "exec")]
-    Exec { script: String, redirect: bool },
+    Exec {
+        id: String,
+        script: String,
+        redirect: bool,
+        /// Seconds to wait before running
+        #[serde(skip_serializing_if = "Option::is_none")]
+        delay: Option<f64>,
+        /// Wait for the game to load before running
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        wait_for_load: bool,
+        /// Maps bundle lines back to source files
+        #[serde(skip_serializing_if = "Option::is_none")]
+        line_map: Option<Vec<LineMapping>>,
+        /// Values passed to the chunk as `...`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        args: Option<Vec<Value>>,
+        /// Length of `args`, since `null` elements leave holes in the decoded Lua table
+        #[serde(skip_serializing_if = "Option::is_none")]
+        arg_count: Option<usize>,
+    },
+    #[serde(rename = "exec_cancel")]
+    ExecCancel { id: String },
     #[serde(rename = "ping")]
     Ping,
     #[serde(rename = "exp_start")]
@@ -124,4 +185,81 @@ pub(super) enum ServerMessage {
     },
     #[serde(rename = "relay")]
     Relay { content: String },
+    #[serde(rename = "shutdown")]
+    Shutdown { reason: String },
+}
+
+impl ClientMessage {
+    /// The message's `type` tag, for traffic counting
+    pub(super) fn kind(&self) -> &'static str {
+        match self {
+            ClientMessage::Ready { .. } => "ready",
+            ClientMessage::Register { .. } => "register",
+            ClientMessage::Pong => "pong",
+            ClientMessage::ExecDone { .. } => "exec_done",
+            ClientMessage::Log { .. } => "log",
+            ClientMessage::ExpTree { .. } => "exp_tree",
+            ClientMessage::ExpProperties { .. } => "exp_properties",
+            ClientMessage::ExpSearchResults { .. } => "exp_search_results",
+            ClientMessage::ExpTreeChanged => "exp_tree_changed",
+            ClientMessage::ExpDecompiled { .. } => "exp_decompiled",
+            ClientMessage::RspyCall { .. } => "rspy_call",
+            ClientMessage::RspyDecompiled { .. } => "rspy_decompiled",
+            ClientMessage::RspyGeneratedCode { .. } => "rspy_generated_code",
+            ClientMessage::Relay { .. } => "relay",
+        }
+    }
+}
+
+/// Read the `type` tag of a message for traffic counting
+///
+/// Used for outgoing messages and for incoming ones that don't parse. Returns `"unknown"`
+/// if the text isn't a tagged JSON object.
+pub(super) fn message_type(text: &str) -> String {
+    #[derive(Deserialize)]
+    struct Tagged {
+        #[serde(rename = "type")]
+        kind: String,
+    }
+
+    serde_json::from_str::<Tagged>(text)
+        .map(|tagged| tagged.kind)
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Traffic counter key and size of a WebSocket frame
+pub(super) fn frame_stats(msg: &Message) -> (String, usize) {
+    match msg {
+        Message::Text(text) => (message_type(text), text.len()),
+        Message::Binary(data) => ("binary".to_string(), data.len()),
+        Message::Ping(data) => ("ws_ping".to_string(), data.len()),
+        Message::Pong(data) => ("ws_pong".to_string(), data.len()),
+        Message::Close(_) => ("close".to_string(), 0),
+        Message::Frame(frame) => ("frame".to_string(), frame.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_matches_tag() {
+        let messages = [
+            r#"{"type":"register","username":"a"}"#,
+            r#"{"type":"pong"}"#,
+            r#"{"type":"exec_done","id":"x","status":"finished"}"#,
+            r#"{"type":"log","level":1,"message":"hi"}"#,
+            r#"{"type":"exp_tree_changed"}"#,
+            r#"{"type":"exp_decompiled","id":1,"source":""}"#,
+            r#"{"type":"rspy_decompiled","callId":1,"source":""}"#,
+            r#"{"type":"relay","content":""}"#,
+        ];
+        for text in messages {
+            let message: ClientMessage = serde_json::from_str(text).unwrap();
+            assert_eq!(message.kind(), message_type(text));
+        }
+
+        assert_eq!(message_type("not json"), "unknown");
+    }
 }
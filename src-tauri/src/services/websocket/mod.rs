@@ -25,22 +25,14 @@
 //!
 //! ## Usage
 //!
-//! The server is started automatically when the Tauri app initializes:
+//! The server is started by the server manager (`services::servers`) when the
+//! Tauri app initializes, and can be stopped or restarted at runtime:
 //!
 //! ```rust
-//! use crate::services::websocket::start_websocket_server;
-//!
-//! tauri::async_runtime::spawn(async move {
-//!     if let Err(e) = start_websocket_server(
-//!         app_handle,
-//!         clients,
-//!         active_explorer,
-//!         active_remote_spy,
-//!         api_dump_cache,
-//!     ).await {
-//!         log::error!("WebSocket server error: {}", e);
-//!     }
-//! });
+//! use crate::models::ServerKind;
+//! use crate::services::servers;
+//!
+//! servers::restart_server(&app_handle, ServerKind::Websocket).await?;
 //! ```
 
 mod client_manager;
@@ -51,7 +43,8 @@ mod server;
 pub mod handlers;
 
 // Re-export public API
-pub use client_manager::{broadcast_to_clients, get_attached_clients};
+pub use client_manager::{broadcast_to_clients, get_attached_clients, wait_for_disconnects};
+pub use handlers::executor::send_exec_cancel;
 pub use handlers::explorer::{
     send_decompile_script, send_get_explorer_properties, send_get_explorer_tree,
     send_search_explorer, send_start_explorer, send_stop_explorer,
@@ -1,57 +1,109 @@
-use crate::state::{ActiveClientsState, ApiDumpCache, ClientInfo, ClientRegistry};
-use crate::utils::events::emit_or_log;
+use crate::models::{ClientTraffic, ExecutionSource};
+use crate::services::autoexec::{AutoexecScript, ClientContext};
+use crate::services::executions;
+use crate::state::{
+    ActiveClientsState, ApiDumpCache, ClientInfo, ClientRegistry, ConnectionCounter,
+};
+use crate::utils::events::{emit_or_log, with_source_client};
 use futures_util::{SinkExt, StreamExt};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
 use tauri::AppHandle;
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::mpsc;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 use tokio_tungstenite::{accept_async, tungstenite::Message};
 use uuid::Uuid;
 
-use super::client_manager::emit_clients_update;
+use super::client_manager::{emit_clients_update, send_shutdown, SHUTDOWN_REASON};
 use super::handlers::{executor, explorer, logging, relay, remote_spy};
 use super::heartbeat::start_heartbeat_monitor;
-use super::messages::ClientMessage;
+use super::messages::{frame_stats, message_type, ClientMessage};
+
+// How long to wait for the close frame to be written when shutting down
+const CLOSE_FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
 
 /// Start the WebSocket server
+///
+/// Binds the listener and spawns the accept loop, which runs until `shutdown` is signalled.
+/// Connected clients are sent a close frame with a reason when that happens.
 pub async fn start_websocket_server(
     app_handle: AppHandle,
     clients: ClientRegistry,
     active_clients: ActiveClientsState,
     api_dump_cache: ApiDumpCache,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let listener = TcpListener::bind("127.0.0.1:13376").await?;
+    port: u16,
+    connections: ConnectionCounter,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<(SocketAddr, JoinHandle<()>), Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    let local_addr = listener.local_addr()?;
     log_ui!(
         &app_handle,
         Success,
-        "WebSocket server started on port 13376"
+        "WebSocket server started on port {}",
+        local_addr.port()
     );
 
-    while let Ok((stream, addr)) = listener.accept().await {
-        let clients = Arc::clone(&clients);
-        let app_handle = app_handle.clone();
-        let active_clients = Arc::clone(&active_clients);
-        let api_dump_cache = Arc::clone(&api_dump_cache);
-
-        tauri::async_runtime::spawn(async move {
-            if let Err(e) = handle_client(
-                stream,
-                addr,
-                clients,
-                app_handle,
-                active_clients,
-                api_dump_cache,
-            )
-            .await
-            {
-                log::error!("Error handling client {}: {}", addr, e);
-            }
-        });
-    }
+    let task = tauri::async_runtime::spawn(async move {
+        loop {
+            let (stream, addr) = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        log::error!("WebSocket server failed to accept connection: {}", e);
+                        break;
+                    }
+                },
+                _ = shutdown.changed() => break,
+            };
 
-    Ok(())
+            let clients = Arc::clone(&clients);
+            let app_handle = app_handle.clone();
+            let active_clients = Arc::clone(&active_clients);
+            let api_dump_cache = Arc::clone(&api_dump_cache);
+            let shutdown = shutdown.clone();
+            let connection = connections.track();
+
+            tauri::async_runtime::spawn(async move {
+                let _connection = connection;
+                if let Err(e) = handle_client(
+                    stream,
+                    addr,
+                    clients,
+                    app_handle,
+                    active_clients,
+                    api_dump_cache,
+                    shutdown,
+                )
+                .await
+                {
+                    log::error!("Error handling client {}: {}", addr, e);
+                }
+            });
+        }
+
+        log::info!("WebSocket server stopped accepting connections");
+    });
+
+    Ok((local_addr, task))
+}
+
+/// Per-connection state
+///
+/// The counters are shared with the registry once the client registers.
+#[derive(Default)]
+struct ConnectionState {
+    missed_pings: Arc<RwLock<u32>>,
+    traffic: Arc<RwLock<ClientTraffic>>,
+    /// What the client reported in its ready message
+    client: ClientContext,
+    /// Autoexec scripts waiting for the client's username
+    deferred_autoexec: Vec<AutoexecScript>,
+    /// Autoexec executions sent before the client registered, tracked once it does
+    untracked_executions: Vec<String>,
 }
 
 /// Handle a single WebSocket client connection
@@ -62,6 +114,7 @@ async fn handle_client(
     app_handle: AppHandle,
     active_clients: ActiveClientsState,
     _api_dump_cache: ApiDumpCache,
+    mut shutdown: watch::Receiver<bool>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let ws_stream = accept_async(stream).await?;
     log::info!("WebSocket connection established: {}", addr);
@@ -74,12 +127,16 @@ async fn handle_client(
     let mut client_id: Option<String> = None;
     let clients_clone = Arc::clone(&clients);
     let app_handle_clone = app_handle.clone();
-    let missed_pings = Arc::new(RwLock::new(0u32));
+    let mut connection = ConnectionState::default();
 
-    // Spawn task to handle outgoing messages
+    // Spawn task to handle outgoing messages (stops after a close frame)
+    let traffic_out = Arc::clone(&connection.traffic);
     let send_task = tauri::async_runtime::spawn(async move {
         while let Some(msg) = rx.recv().await {
-            if ws_sender.send(msg).await.is_err() {
+            let is_close = msg.is_close();
+            let (kind, bytes) = frame_stats(&msg);
+            traffic_out.write().await.record_out(&kind, bytes);
+            if ws_sender.send(msg).await.is_err() || is_close {
                 break;
             }
         }
@@ -89,34 +146,67 @@ async fn handle_client(
     let client_id_shared = Arc::new(RwLock::new(None::<String>));
     let heartbeat_task = start_heartbeat_monitor(
         tx.clone(),
-        Arc::clone(&missed_pings),
+        Arc::clone(&connection.missed_pings),
         Arc::clone(&client_id_shared),
         Arc::clone(&clients_clone),
         app_handle_clone.clone(),
     );
 
-    // Handle incoming messages
-    while let Some(result) = ws_receiver.next().await {
+    // Handle incoming messages until the client leaves or the server shuts down
+    let mut shutting_down = false;
+    loop {
+        let next = tokio::select! {
+            next = ws_receiver.next() => next,
+            _ = shutdown.changed() => {
+                send_shutdown(&tx, SHUTDOWN_REASON);
+                shutting_down = true;
+                break;
+            }
+        };
+
+        let Some(result) = next else {
+            break;
+        };
+
         match result {
+            Ok(Message::Text(text)) => {
+                // Count the message by its type, reading just the tag if it doesn't parse
+                let parsed = serde_json::from_str::<ClientMessage>(&text);
+                let kind = match &parsed {
+                    Ok(client_msg) => client_msg.kind().to_string(),
+                    Err(_) => message_type(&text),
+                };
+                connection
+                    .traffic
+                    .write()
+                    .await
+                    .record_in(&kind, text.len());
+
+                if let Ok(client_msg) = parsed {
+                    // Attribute the events this message causes to the client
+                    let source_client = client_id.clone();
+                    with_source_client(
+                        source_client,
+                        handle_message(
+                            client_msg,
+                            &mut client_id,
+                            &client_id_shared,
+                            &tx,
+                            &clients_clone,
+                            &app_handle_clone,
+                            &mut connection,
+                        ),
+                    )
+                    .await;
+                } else {
+                    log::warn!("Failed to parse client message: {}", text);
+                }
+            }
             Ok(msg) => {
-                if msg.is_text() {
-                    if let Ok(text) = msg.to_text() {
-                        if let Ok(client_msg) = serde_json::from_str::<ClientMessage>(text) {
-                            handle_message(
-                                client_msg,
-                                &mut client_id,
-                                &client_id_shared,
-                                &tx,
-                                &clients_clone,
-                                &app_handle_clone,
-                                &missed_pings,
-                            )
-                            .await;
-                        } else {
-                            log::warn!("Failed to parse client message: {}", text);
-                        }
-                    }
-                } else if msg.is_close() {
+                let (kind, bytes) = frame_stats(&msg);
+                connection.traffic.write().await.record_in(&kind, bytes);
+
+                if msg.is_close() {
                     break;
                 }
             }
@@ -136,8 +226,13 @@ async fn handle_client(
     )
     .await;
 
-    send_task.abort();
     heartbeat_task.abort();
+    if shutting_down {
+        // Let the send task flush the shutdown notice and close frame
+        let _ = tokio::time::timeout(CLOSE_FLUSH_TIMEOUT, send_task).await;
+    } else {
+        send_task.abort();
+    }
     Ok(())
 }
 
@@ -149,18 +244,36 @@ async fn handle_message(
     tx: &mpsc::UnboundedSender<Message>,
     clients: &ClientRegistry,
     app_handle: &AppHandle,
-    missed_pings: &Arc<RwLock<u32>>,
+    connection: &mut ConnectionState,
 ) {
     match msg {
-        ClientMessage::Ready => {
-            executor::handle_ready(tx, app_handle).await;
+        ClientMessage::Ready {
+            place_id,
+            game_id,
+            username,
+            tags,
+        } => {
+            connection.client = ClientContext {
+                place_id,
+                game_id,
+                username,
+                tags,
+            };
+            let (deferred, sent) = executor::handle_ready(tx, app_handle, &connection.client).await;
+            connection.deferred_autoexec = deferred;
+            connection.untracked_executions = sent;
         }
         ClientMessage::Register { username } => {
             let id = Uuid::new_v4().to_string();
 
             let client_info = ClientInfo {
                 username: username.clone(),
+                place_id: connection.client.place_id,
+                game_id: connection.client.game_id,
+                tags: connection.client.tags.clone(),
                 sender: tx.clone(),
+                traffic: Arc::clone(&connection.traffic),
+                missed_pings: Arc::clone(&connection.missed_pings),
             };
 
             clients.write().await.insert(id.clone(), client_info);
@@ -172,19 +285,50 @@ async fn handle_message(
 
             // Emit clients-update event with full list
             emit_clients_update(app_handle, clients).await;
+
+            // Track the autoexec scripts that are still running
+            let delivered = [id.clone()];
+            for execution_id in std::mem::take(&mut connection.untracked_executions) {
+                executions::track(
+                    app_handle,
+                    &execution_id,
+                    ExecutionSource::Autoexec,
+                    &delivered,
+                )
+                .await;
+            }
+
+            // Run autoexec scripts that were waiting for the username
+            connection.client.username = Some(username);
+            let deferred = std::mem::take(&mut connection.deferred_autoexec);
+            executor::handle_registered(tx, app_handle, &connection.client, &id, deferred).await;
         }
         ClientMessage::Pong => {
             if let Some(id) = client_id {
                 log::debug!("Received pong from {}, resetting missed ping counter", id);
             }
-            *missed_pings.write().await = 0;
+            *connection.missed_pings.write().await = 0;
         }
-        ClientMessage::Log { level, message } => {
+        ClientMessage::ExecDone { id, status, error } => match client_id {
+            Some(client_id) => {
+                executions::finish(app_handle, client_id, &id, status, error).await;
+            }
+            None => connection.untracked_executions.retain(|e| *e != id),
+        },
+        ClientMessage::Log {
+            level,
+            message,
+            execution_id,
+        } => {
             // Validate level is 0-3
             if level > 3 {
                 log::error!("Invalid log level from WebSocket client: {}", level);
             } else {
-                logging::handle_log(app_handle, level, message);
+                if let (Some(execution_id), Some(client_id)) = (&execution_id, client_id.as_ref()) {
+                    executions::record_output(app_handle, execution_id, client_id, level, &message)
+                        .await;
+                }
+                logging::handle_log(app_handle, level, message, execution_id, client_id.clone());
             }
         }
         ClientMessage::ExpTree { nodes } => {
@@ -288,6 +432,7 @@ async fn handle_disconnect(
         }
 
         clients.write().await.remove(&id);
+        executions::remove_client(app_handle, &id).await;
 
         // Log client disconnection
         if let Some(username) = username {
@@ -0,0 +1,335 @@
+//! Path sandboxing for editor file commands
+//!
+//! Paths from the frontend are relative to the base directory. They are resolved with
+//! symlinks followed and must land inside a workspace root: `scripts/`, `autoexec/`, or a
+//! folder listed in the `editor.extraRoots` setting.
+
+use crate::utils::paths;
+use crate::utils::settings::get_settings_section;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use tauri::AppHandle;
+
+/// Folders under the base directory that are always workspace roots
+const DEFAULT_ROOTS: [&str; 2] = ["scripts", "autoexec"];
+
+/// Why an editor file operation failed
+///
+/// Sent to the frontend as `{ kind, message }`.
+#[derive(Debug, PartialEq)]
+pub enum WorkspaceError {
+    /// The path is absolute or resolves outside every workspace root
+    OutsideWorkspace(String),
+    /// A file or folder name is empty, `.`/`..` or contains a path separator
+    InvalidName(String),
+    NotFound(String),
+    AlreadyExists(String),
+    /// A filesystem operation failed
+    Io(String),
+}
+
+impl WorkspaceError {
+    fn kind(&self) -> &'static str {
+        match self {
+            WorkspaceError::OutsideWorkspace(_) => "outside_workspace",
+            WorkspaceError::InvalidName(_) => "invalid_name",
+            WorkspaceError::NotFound(_) => "not_found",
+            WorkspaceError::AlreadyExists(_) => "already_exists",
+            WorkspaceError::Io(_) => "io",
+        }
+    }
+}
+
+impl fmt::Display for WorkspaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkspaceError::OutsideWorkspace(path) => {
+                write!(f, "Path is outside the workspace: {}", path)
+            }
+            WorkspaceError::InvalidName(name) => write!(f, "Invalid name: '{}'", name),
+            WorkspaceError::NotFound(path) => write!(f, "File or folder not found: {}", path),
+            WorkspaceError::AlreadyExists(name) => {
+                write!(f, "A file or folder named '{}' already exists", name)
+            }
+            WorkspaceError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Serialize for WorkspaceError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("WorkspaceError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+/// Errors from helpers that report failures as strings
+impl From<String> for WorkspaceError {
+    fn from(message: String) -> Self {
+        WorkspaceError::Io(message)
+    }
+}
+
+/// The folders editor commands may touch
+pub struct Workspace {
+    base_dir: PathBuf,
+    roots: Vec<PathBuf>,
+}
+
+impl Workspace {
+    /// Load the workspace roots from the base directory and settings
+    ///
+    /// Roots that don't exist yet or resolve outside the base directory are skipped.
+    pub fn load(app: &AppHandle) -> Result<Self, WorkspaceError> {
+        let extra_roots: Vec<String> = get_settings_section(app, "editor")
+            .and_then(|editor| editor.get("extraRoots").cloned())
+            .and_then(|roots| serde_json::from_value(roots).ok())
+            .unwrap_or_default();
+
+        let roots = DEFAULT_ROOTS
+            .iter()
+            .map(|root| root.to_string())
+            .chain(extra_roots);
+
+        Self::new(&paths::get_base_directory(app)?, roots)
+    }
+
+    pub(crate) fn new(
+        base_dir: &Path,
+        roots: impl IntoIterator<Item = String>,
+    ) -> Result<Self, WorkspaceError> {
+        let base_dir = canonicalize(base_dir)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to resolve base directory: {}", e)))?;
+
+        let roots = roots
+            .into_iter()
+            .filter_map(|root| match canonicalize(&base_dir.join(&root)) {
+                Ok(path) if path.starts_with(&base_dir) && path != base_dir => Some(path),
+                Ok(_) => {
+                    log::warn!(
+                        "Ignoring workspace root outside the base directory: {}",
+                        root
+                    );
+                    None
+                }
+                Err(_) => None,
+            })
+            .collect();
+
+        Ok(Self { base_dir, roots })
+    }
+
+    /// Resolve an existing file or folder, which may be a workspace root itself
+    pub fn resolve_existing(&self, relative_path: &str) -> Result<PathBuf, WorkspaceError> {
+        self.resolve_path(&self.join(relative_path)?, relative_path)
+    }
+
+    /// Resolve an existing file or folder from a path that may contain `..`, such as a
+    /// reference relative to another workspace file
+    ///
+    /// # Arguments
+    /// * `path` - The path, absolute or relative to the current directory
+    /// * `display` - How the path is named in errors
+    pub fn resolve_path(&self, path: &Path, display: &str) -> Result<PathBuf, WorkspaceError> {
+        let path = canonicalize(path).map_err(|_| WorkspaceError::NotFound(display.to_string()))?;
+
+        if self.roots.iter().any(|root| path.starts_with(root)) {
+            Ok(path)
+        } else {
+            Err(WorkspaceError::OutsideWorkspace(display.to_string()))
+        }
+    }
+
+    /// Resolve an existing script given relative to `scripts/`
+    pub fn resolve_script(&self, path: &str) -> Result<PathBuf, WorkspaceError> {
+        self.resolve_existing(&format!("scripts/{}", path))
+    }
+
+    /// Resolve an existing file or folder inside a workspace root, for renaming or deleting
+    pub fn resolve_entry(&self, relative_path: &str) -> Result<PathBuf, WorkspaceError> {
+        let path = self.resolve_existing(relative_path)?;
+
+        if self.roots.contains(&path) {
+            Err(WorkspaceError::OutsideWorkspace(relative_path.to_string()))
+        } else {
+            Ok(path)
+        }
+    }
+
+    /// Resolve where a file named `filename` in `folder` would be saved
+    ///
+    /// The folder doesn't need to exist yet.
+    pub fn resolve_new(&self, folder: &str, filename: &str) -> Result<PathBuf, WorkspaceError> {
+        validate_name(filename)?;
+        let path = self.join(folder)?.join(filename);
+
+        // Resolve the closest existing ancestor, then re-append the rest, which only has
+        // plain names
+        let mut existing = path.as_path();
+        let mut rest = Vec::new();
+        while !existing.exists() {
+            rest.push(existing.file_name().unwrap_or_default().to_os_string());
+            existing = existing
+                .parent()
+                .ok_or_else(|| WorkspaceError::OutsideWorkspace(folder.to_string()))?;
+        }
+        let mut resolved = canonicalize(existing)
+            .map_err(|e| WorkspaceError::Io(format!("Failed to resolve path: {}", e)))?;
+        resolved.extend(rest.iter().rev());
+
+        if self
+            .roots
+            .iter()
+            .any(|root| resolved.starts_with(root) && &resolved != root)
+        {
+            Ok(resolved)
+        } else {
+            Err(WorkspaceError::OutsideWorkspace(format!(
+                "{}/{}",
+                folder, filename
+            )))
+        }
+    }
+
+    /// The resolved base directory
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    /// Path relative to the base directory, with forward slashes
+    pub fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.base_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    /// Join a relative path onto the base directory, rejecting absolute paths and `..`
+    fn join(&self, relative_path: &str) -> Result<PathBuf, WorkspaceError> {
+        let relative = Path::new(relative_path);
+        let is_plain = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+
+        if is_plain {
+            Ok(self.base_dir.join(relative))
+        } else {
+            Err(WorkspaceError::OutsideWorkspace(relative_path.to_string()))
+        }
+    }
+}
+
+/// Canonicalize a path, without the `\\?\` prefix Windows adds
+///
+/// Explorer and most tools don't accept verbatim paths.
+fn canonicalize(path: &Path) -> std::io::Result<PathBuf> {
+    let path = path.canonicalize()?;
+
+    #[cfg(windows)]
+    {
+        let text = path.to_string_lossy();
+        if let Some(stripped) = text.strip_prefix(r"\\?\") {
+            if !stripped.starts_with("UNC\\") {
+                return Ok(PathBuf::from(stripped));
+            }
+        }
+    }
+
+    Ok(path)
+}
+
+/// Check that a file or folder name is a single path component
+pub fn validate_name(name: &str) -> Result<(), WorkspaceError> {
+    let invalid =
+        name.trim().is_empty() || name == "." || name == ".." || name.contains(['/', '\\', '\0']);
+
+    if invalid {
+        Err(WorkspaceError::InvalidName(name.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn workspace() -> (TempDir, Workspace) {
+        let temp = tempfile::tempdir().unwrap();
+        let base = temp.path();
+        std::fs::create_dir_all(base.join("scripts/sub")).unwrap();
+        std::fs::create_dir_all(base.join("private")).unwrap();
+        std::fs::write(base.join("scripts/sub/a.lua"), "").unwrap();
+        std::fs::write(base.join("private/secret.txt"), "").unwrap();
+
+        let workspace = Workspace::new(base, DEFAULT_ROOTS.map(String::from)).unwrap();
+        (temp, workspace)
+    }
+
+    #[test]
+    fn test_resolve_existing() {
+        let (temp, workspace) = workspace();
+        let base = temp.path();
+
+        let path = workspace.resolve_existing("scripts/sub/a.lua").unwrap();
+        assert_eq!(workspace.relative(&path), "scripts/sub/a.lua");
+        assert!(workspace.resolve_existing("scripts").is_ok());
+        assert!(workspace.resolve_entry("scripts").is_err());
+
+        for escape in [
+            "private/secret.txt",
+            "scripts/../private/secret.txt",
+            "../x",
+        ] {
+            assert_eq!(
+                workspace.resolve_existing(escape),
+                Err(WorkspaceError::OutsideWorkspace(escape.to_string()))
+            );
+        }
+        let absolute = base.join("scripts/sub/a.lua").to_string_lossy().to_string();
+        assert!(workspace.resolve_existing(&absolute).is_err());
+        assert_eq!(
+            workspace.resolve_existing("scripts/missing.lua"),
+            Err(WorkspaceError::NotFound("scripts/missing.lua".to_string()))
+        );
+        assert_eq!(workspace.resolve_script("sub/a.lua"), Ok(path.clone()));
+        assert!(workspace.resolve_script("../private/secret.txt").is_err());
+        assert_eq!(
+            workspace.resolve_path(&base.join("scripts/sub/../sub/a.lua"), "a"),
+            Ok(path)
+        );
+        assert_eq!(
+            workspace.resolve_path(&base.join("scripts/../private/secret.txt"), "secret"),
+            Err(WorkspaceError::OutsideWorkspace("secret".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_resolve_new() {
+        let (_temp, workspace) = workspace();
+
+        let path = workspace
+            .resolve_new("scripts/new/folder", "b.lua")
+            .unwrap();
+        assert_eq!(workspace.relative(&path), "scripts/new/folder/b.lua");
+        assert!(workspace.resolve_new("elsewhere", "b.lua").is_err());
+        assert!(workspace.resolve_new("", "scripts").is_err());
+        assert!(workspace.resolve_new("scripts", "../b.lua").is_err());
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("main.lua").is_ok());
+        for name in ["", " ", ".", "..", "a/b", "a\\b"] {
+            assert_eq!(
+                validate_name(name),
+                Err(WorkspaceError::InvalidName(name.to_string()))
+            );
+        }
+    }
+}
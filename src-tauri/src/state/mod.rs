@@ -1,15 +1,25 @@
+use crate::models::{BackendEvent, ClientTraffic, ServerKind};
 use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, watch, RwLock};
 use tokio_tungstenite::tungstenite::Message;
 
 /// Client information stored in the registry
 ///
-/// Contains the username and message sender for each connected WebSocket client.
+/// Contains the username, game and message sender for each connected WebSocket client,
+/// along with the connection's traffic counters and heartbeat state.
 pub struct ClientInfo {
     pub username: String,
+    pub place_id: Option<u64>,
+    pub game_id: Option<u64>,
+    pub tags: Vec<String>,
     pub sender: UnboundedSender<Message>,
+    pub traffic: Arc<RwLock<ClientTraffic>>,
+    pub missed_pings: Arc<RwLock<u32>>,
 }
 
 /// Registry of all connected WebSocket clients
@@ -24,6 +34,31 @@ pub type ClientRegistry = Arc<RwLock<HashMap<String, ClientInfo>>>;
 /// Shared across explorer features.
 pub type ApiDumpCache = Arc<RwLock<crate::services::api_dump::ApiDumpService>>;
 
+/// Execution history
+///
+/// Loaded from `.proxima/history.json` at startup and saved on every change.
+pub type HistoryState = Arc<RwLock<crate::services::history::ExecutionHistory>>;
+
+/// Running executions
+///
+/// Scripts sent to clients that haven't finished yet, per client.
+pub type ExecutionsState = Arc<RwLock<crate::services::executions::ExecutionTracker>>;
+
+/// Backend event bus
+///
+/// Every event emitted to the frontend is also sent here, for `GET /v1/events`.
+pub type EventBus = broadcast::Sender<BackendEvent>;
+
+/// Script schedules
+///
+/// Loaded from `.proxima/schedules.json` at startup and run by the scheduler loop.
+pub type SchedulerState = Arc<RwLock<crate::services::scheduler::Scheduler>>;
+
+/// Quick open index
+///
+/// Workspace file paths, rebuilt by the file watcher when files change.
+pub type FileIndexState = Arc<RwLock<crate::services::quick_open::FileIndex>>;
+
 /// Active feature clients
 ///
 /// Stores which client (if any) is currently using each feature.
@@ -48,3 +83,44 @@ pub struct QueuedLauncher {
 /// Maps launcher ID to queued launcher info with heartbeat tracking.
 /// Launchers are removed if they don't send heartbeats or explicitly leave.
 pub type LauncherQueueRegistry = Arc<RwLock<HashMap<String, QueuedLauncher>>>;
+
+/// A backend server started by the server manager
+///
+/// Sending `true` on `shutdown` asks the server to close its connections and stop accepting.
+pub struct RunningServer {
+    pub addr: SocketAddr,
+    pub started_at: Instant,
+    pub connections: ConnectionCounter,
+    pub shutdown: watch::Sender<bool>,
+    pub task: tauri::async_runtime::JoinHandle<()>,
+}
+
+/// Count of open connections on a server
+///
+/// Each connection holds a `ConnectionGuard` which decrements the count when dropped.
+#[derive(Clone, Default)]
+pub struct ConnectionCounter(Arc<AtomicUsize>);
+
+impl ConnectionCounter {
+    pub fn track(&self) -> ConnectionGuard {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard(Arc::clone(&self.0))
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+pub struct ConnectionGuard(Arc<AtomicUsize>);
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Registry of running backend servers
+///
+/// Servers missing from the registry are stopped.
+pub type ServerRegistry = Arc<RwLock<HashMap<ServerKind, RunningServer>>>;
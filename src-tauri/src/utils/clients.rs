@@ -1,21 +1,74 @@
+use crate::models::ClientSelector;
 use crate::state::{ActiveClientsState, ClientRegistry};
+use std::fmt;
+
+/// Error from a request sent to a client
+#[derive(Debug, PartialEq)]
+pub enum ClientRequestError {
+    /// No attached client has the ID
+    ClientNotFound(String),
+    /// The session the request is for isn't running
+    NotRunning(String),
+    /// The session is already running on another client
+    InUse(String),
+    /// The message couldn't be sent over the client's connection
+    SendFailed(String),
+    /// The client didn't reply in time
+    Timeout,
+    Other(String),
+}
+
+impl fmt::Display for ClientRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientRequestError::ClientNotFound(id) => write!(f, "Client not found: {}", id),
+            ClientRequestError::NotRunning(message)
+            | ClientRequestError::InUse(message)
+            | ClientRequestError::Other(message) => write!(f, "{}", message),
+            ClientRequestError::SendFailed(e) => {
+                write!(f, "Failed to send message to client: {}", e)
+            }
+            ClientRequestError::Timeout => {
+                write!(f, "Timed out waiting for the client to respond")
+            }
+        }
+    }
+}
+
+/// Errors from helpers that report failures as strings
+impl From<String> for ClientRequestError {
+    fn from(message: String) -> Self {
+        ClientRequestError::Other(message)
+    }
+}
+
+/// For commands, which report errors to the frontend as strings
+impl From<ClientRequestError> for String {
+    fn from(error: ClientRequestError) -> Self {
+        error.to_string()
+    }
+}
 
 /// Get the active explorer client ID
-pub async fn get_active_explorer(active_clients: &ActiveClientsState) -> Result<String, String> {
+pub async fn get_active_explorer(
+    active_clients: &ActiveClientsState,
+) -> Result<String, ClientRequestError> {
     let active = active_clients.read().await;
     active
         .explorer
         .clone()
-        .ok_or_else(|| "No active explorer client".to_string())
+        .ok_or_else(|| ClientRequestError::NotRunning("No active explorer client".to_string()))
 }
 
 /// Get the active remote spy client ID
-pub async fn get_active_remote_spy(active_clients: &ActiveClientsState) -> Result<String, String> {
+pub async fn get_active_remote_spy(
+    active_clients: &ActiveClientsState,
+) -> Result<String, ClientRequestError> {
     let active = active_clients.read().await;
     active
         .remote_spy
         .clone()
-        .ok_or_else(|| "No active remote spy client".to_string())
+        .ok_or_else(|| ClientRequestError::NotRunning("No active remote spy client".to_string()))
 }
 
 /// Helper to verify a client exists in the registry
@@ -26,12 +79,38 @@ pub async fn get_active_remote_spy(active_clients: &ActiveClientsState) -> Resul
 ///
 /// # Returns
 /// * `Ok(())` - If the client exists
-/// * `Err(String)` - If the client doesn't exist
-pub async fn verify_client_exists(client_id: &str, clients: &ClientRegistry) -> Result<(), String> {
+/// * `Err(ClientRequestError::ClientNotFound)` - If the client doesn't exist
+pub async fn verify_client_exists(
+    client_id: &str,
+    clients: &ClientRegistry,
+) -> Result<(), ClientRequestError> {
     let clients_lock = clients.read().await;
     if clients_lock.contains_key(client_id) {
         Ok(())
     } else {
-        Err(format!("Client not found: {}", client_id))
+        Err(ClientRequestError::ClientNotFound(client_id.to_string()))
     }
 }
+
+/// Resolve a client selector to the IDs of matching attached clients
+///
+/// # Returns
+/// The matching client IDs, sorted for a stable order (may be empty)
+pub async fn resolve_selector(selector: &ClientSelector, clients: &ClientRegistry) -> Vec<String> {
+    let clients_lock = clients.read().await;
+
+    let mut ids: Vec<String> = clients_lock
+        .iter()
+        .filter(|(id, info)| match selector {
+            ClientSelector::All => true,
+            ClientSelector::Ids { ids } => ids.contains(id),
+            ClientSelector::Usernames { usernames } => usernames
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&info.username)),
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    ids.sort();
+    ids
+}
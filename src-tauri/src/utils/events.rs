@@ -1,5 +1,26 @@
+use crate::models::BackendEvent;
+use crate::state::EventBus;
+use crate::utils::clients::ClientRequestError;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
-use tauri::{AppHandle, Emitter};
+use std::future::Future;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+
+tokio::task_local! {
+    /// Client whose message is being handled, so the events it causes can be attributed
+    static SOURCE_CLIENT: Option<String>;
+}
+
+/// Run a future with the events it emits attributed to a client
+///
+/// # Arguments
+/// * `client_id` - The client whose message the future handles, if registered
+/// * `future` - The future to run
+pub async fn with_source_client<F: Future>(client_id: Option<String>, future: F) -> F::Output {
+    SOURCE_CLIENT.scope(client_id, future).await
+}
 
 /// Emit an event and log errors instead of returning them
 ///
@@ -11,6 +32,7 @@ use tauri::{AppHandle, Emitter};
 /// * `event` - The event name
 /// * `payload` - The event payload
 pub fn emit_or_log<T: Serialize + Clone>(app: &AppHandle, event: &str, payload: T) {
+    publish(app, event, &payload);
     if let Err(e) = app.emit(event, payload) {
         log::error!("Failed to emit {} event: {}", event, e);
     }
@@ -33,6 +55,84 @@ pub fn emit_or_error<T: Serialize + Clone>(
     event: &str,
     payload: T,
 ) -> Result<(), String> {
+    publish(app, event, &payload);
     app.emit(event, payload)
         .map_err(|e| format!("Failed to emit {} event: {}", event, e))
 }
+
+/// Send an event to the event bus, if anything is listening
+fn publish<T: Serialize>(app: &AppHandle, event: &str, payload: &T) {
+    let Some(bus) = app.try_state::<EventBus>() else {
+        return;
+    };
+    if bus.receiver_count() == 0 {
+        return;
+    }
+
+    let payload = match serde_json::to_value(payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            log::error!("Failed to serialize {} event: {}", event, e);
+            return;
+        }
+    };
+
+    let _ = bus.send(BackendEvent {
+        event: event.to_string(),
+        client_id: SOURCE_CLIENT.try_with(Clone::clone).ok().flatten(),
+        payload,
+    });
+}
+
+/// Subscribe to the events emitted from now on
+pub fn subscribe(app: &AppHandle) -> broadcast::Receiver<BackendEvent> {
+    app.state::<EventBus>().subscribe()
+}
+
+/// Wait for a client's reply to a request, delivered as an event
+///
+/// Subscribe before sending the request, so the reply can't be missed.
+///
+/// # Arguments
+/// * `events` - Receiver subscribed before the request was sent
+/// * `client_id` - The client the request was sent to
+/// * `event` - The event name the reply is emitted as
+/// * `timeout` - How long to wait for the reply
+/// * `matches` - Whether a payload is the reply to this request
+///
+/// # Returns
+/// * `Ok(T)` - The reply payload
+/// * `Err(ClientRequestError::Timeout)` - If no reply arrived in time
+pub async fn wait_for_client_event<T: DeserializeOwned>(
+    mut events: broadcast::Receiver<BackendEvent>,
+    client_id: &str,
+    event: &str,
+    timeout: Duration,
+    matches: impl Fn(&T) -> bool,
+) -> Result<T, ClientRequestError> {
+    let reply = async {
+        loop {
+            let received = match events.recv().await {
+                Ok(received) => received,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            };
+
+            if received.event != event || received.client_id.as_deref() != Some(client_id) {
+                continue;
+            }
+
+            match serde_json::from_value::<T>(received.payload) {
+                Ok(payload) if matches(&payload) => return Some(payload),
+                Ok(_) => {}
+                Err(e) => log::error!("Failed to parse {} event: {}", event, e),
+            }
+        }
+    };
+
+    tokio::time::timeout(timeout, reply)
+        .await
+        .ok()
+        .flatten()
+        .ok_or(ClientRequestError::Timeout)
+}
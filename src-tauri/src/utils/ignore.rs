@@ -108,6 +108,20 @@ impl IgnorePatterns {
         ignored
     }
 
+    /// Check if a path, or any folder it is inside, should be ignored
+    ///
+    /// The file tree never descends into ignored folders, but watcher events can come from
+    /// deep inside them.
+    pub fn is_ignored_within(&self, path: &Path, is_dir: bool) -> bool {
+        let ignored_folder = path
+            .ancestors()
+            .skip(1)
+            .filter(|folder| !folder.as_os_str().is_empty())
+            .any(|folder| self.is_ignored(folder, true));
+
+        ignored_folder || self.is_ignored(path, is_dir)
+    }
+
     /// Check if a pattern matches a path
     fn matches_pattern(path: &str, pattern: &str, is_dir: bool) -> bool {
         // Normalize paths to use forward slashes
@@ -259,6 +273,14 @@ mod tests {
         assert!(!patterns.is_ignored(Path::new("important.log"), false));
     }
 
+    #[test]
+    fn test_ignored_within_folder() {
+        let patterns = IgnorePatterns::from_string("build/\n*.log");
+        assert!(patterns.is_ignored_within(Path::new("build/out/main.lua"), false));
+        assert!(patterns.is_ignored_within(Path::new("src/debug.log"), false));
+        assert!(!patterns.is_ignored_within(Path::new("src/build.lua"), false));
+    }
+
     #[test]
     fn test_path_pattern() {
         let patterns = IgnorePatterns::from_string("src/temp/*");
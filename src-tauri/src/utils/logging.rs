@@ -1,5 +1,6 @@
 use crate::models::LogMessage;
-use tauri::{AppHandle, Emitter};
+use crate::utils::events::emit_or_log;
+use tauri::AppHandle;
 
 /// Log levels that match the frontend
 #[derive(Debug, Clone, Copy)]
@@ -16,11 +17,11 @@ pub fn emit_log(app: &AppHandle, level: LogLevel, message: impl AsRef<str>) {
     let log_msg = LogMessage {
         level: level as u8,
         message: message.as_ref().to_string(),
+        execution_id: None,
+        client_id: None,
     };
 
-    if let Err(e) = app.emit("log-message", log_msg) {
-        log::error!("Failed to emit log event: {}", e);
-    }
+    emit_or_log(app, "log-message", log_msg);
 }
 
 /// Log to console with proper formatting and emit to UI
@@ -4,3 +4,6 @@ pub mod clients;
 pub mod events;
 pub mod ignore;
 pub mod paths;
+pub mod settings;
+pub mod template;
+pub mod time;
@@ -25,3 +25,17 @@ pub fn get_base_directory(_app: &AppHandle) -> Result<PathBuf, String> {
         Ok(exe_dir.to_path_buf())
     }
 }
+
+/// Get the directory for Proxima's own data files (history, schedules, etc.)
+///
+/// Lives in `.proxima` under the base directory and is created if missing.
+pub fn get_data_directory(app: &AppHandle) -> Result<PathBuf, String> {
+    let data_dir = get_base_directory(app)?.join(".proxima");
+
+    if !data_dir.exists() {
+        std::fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    Ok(data_dir)
+}
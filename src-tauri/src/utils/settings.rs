@@ -0,0 +1,59 @@
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use super::paths;
+
+/// Read a section of the persisted settings from the Tauri store
+///
+/// Settings are stored as `{ "settings": { "<section>": { ... } } }` in `settings.json`
+/// next to the scripts and autoexec folders.
+///
+/// # Returns
+/// * `Some(Map)` - The section object
+/// * `None` - If the store, settings or section could not be read
+pub fn get_settings_section(app: &AppHandle, section: &str) -> Option<Map<String, Value>> {
+    let base_dir = match paths::get_base_directory(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::error!("Failed to get base directory: {}", e);
+            return None;
+        }
+    };
+
+    let settings_path = base_dir.join("settings.json");
+    let settings_path_str = settings_path.to_string_lossy().to_string();
+
+    match app.store(&settings_path_str) {
+        Ok(store) => match store.get("settings") {
+            Some(Value::Object(settings)) => match settings.get(section) {
+                Some(Value::Object(section)) => Some(section.clone()),
+                _ => None,
+            },
+            _ => None,
+        },
+        Err(e) => {
+            log::error!("Failed to access settings store: {}", e);
+            None
+        }
+    }
+}
+
+/// Read the execution settings from the Tauri store
+/// Returns (autoExecute, redirectPrint)
+pub fn get_execution_settings(app: &AppHandle) -> (bool, bool) {
+    if let Some(execution) = get_settings_section(app, "execution") {
+        let auto_execute = execution
+            .get("autoExecute")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        let redirect_print = execution
+            .get("redirectPrint")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        return (auto_execute, redirect_print);
+    }
+
+    // Default to auto_execute=true, redirect=false if settings not found
+    (true, false)
+}
@@ -0,0 +1,37 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+static PLACEHOLDER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").expect("valid placeholder regex")
+});
+
+/// Expand `{{name}}` placeholders in a script
+///
+/// Placeholders `lookup` doesn't know are left as they are, so scripts can still contain
+/// literal `{{...}}` text.
+pub fn expand(script: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    PLACEHOLDER_PATTERN
+        .replace_all(script, |captures: &Captures| {
+            lookup(&captures[1]).unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let lookup = |name: &str| match name {
+            "username" => Some("Alt1".to_string()),
+            "index" => Some("2".to_string()),
+            _ => None,
+        };
+
+        assert_eq!(
+            expand("print('{{username}}', {{ index }}, '{{unknown}}')", lookup),
+            "print('Alt1', 2, '{{unknown}}')"
+        );
+    }
+}
@@ -5,3 +5,9 @@ export interface FileNode {
     path: string;
     children: FileNode[];
 }
+
+export interface FileChange {
+    kind: "created" | "modified" | "deleted" | "renamed";
+    path: string;
+    old_path?: string;
+}
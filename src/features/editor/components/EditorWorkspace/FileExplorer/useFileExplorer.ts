@@ -2,7 +2,7 @@ import { ref, onMounted, onUnmounted, computed } from "vue";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { watchDebounced } from "@vueuse/core";
-import type { FileNode } from "./types";
+import type { FileChange, FileNode } from "./types";
 import { useLogger } from "@/composables/useLogger";
 
 const MAX_SEARCH_RESULTS = 100;
@@ -16,7 +16,7 @@ export function useFileExplorer() {
   const isLoading = ref(true);
   const isSearching = ref(false);
   const resultsLimited = ref(false);
-  let unlistenFn: UnlistenFn | null = null;
+  let unlistenFns: UnlistenFn[] = [];
 
   async function loadFileTree() {
     try {
@@ -29,6 +29,57 @@ export function useFileExplorer() {
     }
   }
 
+  function parentPath(path: string) {
+    const index = path.lastIndexOf("/");
+    return index === -1 ? "" : path.slice(0, index);
+  }
+
+  function findFolder(nodes: FileNode[], path: string): FileNode | null {
+    for (const node of nodes) {
+      if (node.type !== "folder") continue;
+      if (node.path === path) return node;
+      if (path.startsWith(`${node.path}/`)) {
+        return findFolder(node.children, path);
+      }
+    }
+    return null;
+  }
+
+  // Reload only the folders that gained or lost entries
+  async function applyFileChanges(changes: FileChange[]) {
+    const folders = new Set<string>();
+    for (const change of changes) {
+      if (change.kind === "modified") continue;
+      folders.add(parentPath(change.path));
+      if (change.old_path) folders.add(parentPath(change.old_path));
+    }
+
+    // Reloading a folder also reloads everything inside it
+    const outermost = [...folders].filter(
+      (path) =>
+        ![...folders].some((other) => path.startsWith(`${other}/`)),
+    );
+
+    for (const path of outermost) {
+      const folder = findFolder(fileTree.value, path);
+      if (!folder) {
+        await loadFileTree();
+        return;
+      }
+
+      try {
+        const subtree = await invoke<FileNode>("read_file_subtree", {
+          relativePath: path,
+          id: folder.id,
+        });
+        folder.children = subtree.children;
+      } catch {
+        await loadFileTree();
+        return;
+      }
+    }
+  }
+
   // Debounced search with loading state management
   watchDebounced(
     searchQuery,
@@ -120,7 +171,11 @@ export function useFileExplorer() {
       await loadFileTree();
 
       // Start watching for file changes
-      unlistenFn = await listen("file-tree-changed", loadFileTree);
+      unlistenFns = [
+        await listen<FileChange[]>("file-changes", (event) =>
+          applyFileChanges(event.payload),
+        ),
+      ];
     } catch (error) {
       const errorMessage =
         error instanceof Error ? error.message : String(error);
@@ -131,7 +186,7 @@ export function useFileExplorer() {
   });
 
   onUnmounted(() => {
-    unlistenFn?.();
+    unlistenFns.forEach((unlisten) => unlisten());
   });
 
   return {
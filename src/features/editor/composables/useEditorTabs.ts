@@ -235,10 +235,11 @@ export function useEditorTabs() {
     return tab.content !== tab.savedContent;
   };
 
-  const markTabAsSaved = (tabId: number) => {
+  // Mark a tab as saved, with the content on disk if it isn't the tab's content
+  const markTabAsSaved = (tabId: number, savedContent?: string) => {
     const tab = tabs.value.find((t) => t.id === tabId);
     if (tab) {
-      tab.savedContent = tab.content;
+      tab.savedContent = savedContent ?? tab.content;
     }
   };
 
@@ -1,9 +1,16 @@
 import { invoke } from "@tauri-apps/api/core";
 import { toast } from "vue-sonner";
-import type { ExecuteRequest } from "@/types/executor";
+import type { ExecuteRequest, ExecutionSource } from "@/types/executor";
 import { useLogger } from "@/composables/useLogger";
 import { useSettings } from "@/features/settings/composables/useSettings";
 
+export interface ExecuteOptions {
+  /** JSON passed to the script as `...` */
+  args?: unknown;
+  /** Execution ID to run the script with */
+  id?: string;
+}
+
 export function useExecutor() {
   const { addLog } = useLogger();
   const { executionSettings } = useSettings();
@@ -11,6 +18,8 @@ export function useExecutor() {
   const executeScript = async (
     script: string,
     clientIds: string[],
+    source: ExecutionSource = "editor",
+    options: ExecuteOptions = {},
   ): Promise<boolean> => {
     // Validate script is not empty
     if (!script || script.trim().length === 0) {
@@ -34,6 +43,9 @@ export function useExecutor() {
         client_ids: clientIds,
         script: script,
         redirect: executionSettings.value.redirectPrint,
+        source,
+        args: options.args,
+        id: options.id,
       };
 
       await invoke("exec", { request });
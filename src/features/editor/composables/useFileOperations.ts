@@ -3,6 +3,9 @@ import { invoke } from "@tauri-apps/api/core";
 import { toast } from "vue-sonner";
 import { useEditorTabs } from "./useEditorTabs";
 import { useLogger } from "@/composables/useLogger";
+import { getFileErrorMessage } from "../utils/fileErrors";
+import type { Tab } from "../types/tab";
+import type { SavedFile } from "../types/workspace";
 
 export function useFileOperations() {
   const {
@@ -12,12 +15,22 @@ export function useFileOperations() {
     updateActiveTabFilePath,
     markTabAsSaved,
     getActiveTab,
+    updateTabContent,
   } = useEditorTabs();
   const { addLog } = useLogger();
 
   const fileInputRef = ref<HTMLInputElement | null>(null);
   const saveDialogOpen = ref(false);
 
+  // Record what the backend wrote, which was formatted when format on save is on.
+  // Edits made while the save was in flight are kept and leave the tab unsaved.
+  const applySavedFile = (tab: Tab, sent: string, saved: SavedFile) => {
+    if (tab.content === sent) {
+      updateTabContent(tab.id, saved.content);
+    }
+    markTabAsSaved(tab.id, saved.content);
+  };
+
   const handleOpenScript = () => {
     fileInputRef.value?.click();
   };
@@ -49,6 +62,7 @@ export function useFileOperations() {
 
   const handleDirectSave = async (filePath: string) => {
     try {
+      const activeTab = getActiveTab();
       const content = getActiveTabContent();
 
       // Parse the filepath to extract filename and full folder path
@@ -58,25 +72,23 @@ export function useFileOperations() {
       // Get everything except the filename as the folder path
       const folder = pathParts.slice(0, -1).join("/");
 
-      const relativePath = await invoke<string>("save_file", {
+      const saved = await invoke<SavedFile>("save_file", {
         filename,
         folder,
         content,
       });
 
       // Mark tab as saved
-      const activeTab = getActiveTab();
       if (activeTab) {
-        markTabAsSaved(activeTab.id);
+        applySavedFile(activeTab, content, saved);
       }
 
       toast.success("File saved successfully", {
-        description: relativePath,
+        description: saved.path,
       });
       // Backend logs this automatically
     } catch (error) {
-      const errorMessage =
-        error instanceof Error ? error.message : String(error);
+      const errorMessage = getFileErrorMessage(error);
       toast.error("Failed to save file", {
         description: errorMessage,
       });
@@ -89,30 +101,29 @@ export function useFileOperations() {
     folder: "scripts" | "autoexec",
   ) => {
     try {
+      const activeTab = getActiveTab();
       const content = getActiveTabContent();
 
-      const relativePath = await invoke<string>("save_file", {
+      const saved = await invoke<SavedFile>("save_file", {
         filename,
         folder,
         content,
       });
 
       // Update the active tab's filepath
-      updateActiveTabFilePath(relativePath);
+      updateActiveTabFilePath(saved.path);
 
       // Mark tab as saved
-      const activeTab = getActiveTab();
       if (activeTab) {
-        markTabAsSaved(activeTab.id);
+        applySavedFile(activeTab, content, saved);
       }
 
       toast.success("File saved successfully", {
-        description: relativePath,
+        description: saved.path,
       });
       // Backend logs this automatically
     } catch (error) {
-      const errorMessage =
-        error instanceof Error ? error.message : String(error);
+      const errorMessage = getFileErrorMessage(error);
       toast.error("Failed to save file", {
         description: errorMessage,
       });
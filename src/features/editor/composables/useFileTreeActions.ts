@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { toast } from "vue-sonner";
 import { useLogger } from "@/composables/useLogger";
+import { getFileErrorMessage } from "../utils/fileErrors";
 
 export function useFileTreeActions() {
   const { addLog } = useLogger();
@@ -17,8 +18,7 @@ export function useFileTreeActions() {
         description: relativePath,
       });
     } catch (error) {
-      const errorMessage =
-        error instanceof Error ? error.message : String(error);
+      const errorMessage = getFileErrorMessage(error);
       addLog("error", `Failed to open file/folder location: ${errorMessage}`);
       toast.error("Failed to open file/folder location", {
         description: errorMessage,
@@ -44,8 +44,7 @@ export function useFileTreeActions() {
       });
       return newPath;
     } catch (error) {
-      const errorMessage =
-        error instanceof Error ? error.message : String(error);
+      const errorMessage = getFileErrorMessage(error);
       addLog("error", `Failed to rename: ${errorMessage}`);
       toast.error("Failed to rename", {
         description: errorMessage,
@@ -71,8 +70,7 @@ export function useFileTreeActions() {
       });
       return true;
     } catch (error) {
-      const errorMessage =
-        error instanceof Error ? error.message : String(error);
+      const errorMessage = getFileErrorMessage(error);
       addLog("error", `Failed to delete: ${errorMessage}`);
       toast.error("Failed to delete", {
         description: errorMessage,
@@ -8,6 +8,8 @@ import { toast } from "vue-sonner";
 interface HttpExecutePayload {
   script: string;
   source: "http_file" | "http_direct";
+  args?: unknown;
+  execution_id: string;
 }
 
 let unlistenFn: UnlistenFn | null = null;
@@ -24,7 +26,7 @@ export function useHttpExecutor() {
     unlistenFn = await listen<HttpExecutePayload>(
       "http-execute-script",
       async (event) => {
-        const { script, source } = event.payload;
+        const { script, source, args, execution_id } = event.payload;
 
         // Check if HTTP request execution is enabled
         if (!executionSettings.value.httpRequestExecution) {
@@ -43,7 +45,10 @@ export function useHttpExecutor() {
         addLog("info", `HTTP request received: ${source}`);
 
         // Execute using normal flow (handles validation, logging, toasts)
-        await executeScript(script, clientIds);
+        await executeScript(script, clientIds, "http", {
+          args,
+          id: execution_id,
+        });
       },
     );
   };
@@ -0,0 +1,82 @@
+import { ref } from "vue";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { invoke } from "@tauri-apps/api/core";
+import { toast } from "vue-sonner";
+import { useLogger } from "@/composables/useLogger";
+import type { RunningExecution } from "@/types/execution";
+
+const executions = ref<RunningExecution[]>([]);
+const isInitialized = ref(false);
+
+let unlistenFn: UnlistenFn | null = null;
+
+export function useRunningExecutions() {
+  const { addLog } = useLogger();
+
+  const init = async () => {
+    if (isInitialized.value) return;
+
+    try {
+      executions.value = await invoke<RunningExecution[]>("executions_list");
+
+      unlistenFn = await listen<RunningExecution[]>(
+        "executions-update",
+        (event) => {
+          executions.value = event.payload;
+        },
+      );
+
+      isInitialized.value = true;
+    } catch (error) {
+      addLog("error", `Failed to initialize running executions: ${error}`);
+    }
+  };
+
+  const cleanup = () => {
+    if (unlistenFn) {
+      unlistenFn();
+      unlistenFn = null;
+    }
+  };
+
+  const getClientExecutions = (clientId: string) => {
+    return executions.value.filter((e) => e.client_id === clientId);
+  };
+
+  /**
+   * Cancel an execution on one client, or on every client running it
+   */
+  const cancelExecution = async (id: string, clientId?: string) => {
+    try {
+      await invoke<string[]>("execution_cancel", {
+        id,
+        clientId: clientId ?? null,
+      });
+    } catch (error) {
+      const errorMessage =
+        error instanceof Error ? error.message : String(error);
+      toast.error("Failed to cancel execution", {
+        description: errorMessage,
+      });
+      addLog("error", `Failed to cancel execution: ${errorMessage}`);
+    }
+  };
+
+  /**
+   * Cancel every execution running on a client
+   */
+  const cancelClientExecutions = async (clientId: string) => {
+    for (const execution of getClientExecutions(clientId)) {
+      await cancelExecution(execution.id, clientId);
+    }
+  };
+
+  return {
+    executions,
+    init,
+    cleanup,
+    getClientExecutions,
+    cancelExecution,
+    cancelClientExecutions,
+  };
+}
@@ -0,0 +1,22 @@
+/**
+ * Error returned by editor file commands
+ */
+export interface WorkspaceError {
+  kind:
+    | "outside_workspace"
+    | "invalid_name"
+    | "not_found"
+    | "already_exists"
+    | "io";
+  message: string;
+}
+
+/**
+ * File written by `save_file`
+ */
+export interface SavedFile {
+  /** Path relative to the base directory */
+  path: string;
+  /** The content written, formatted when format on save is on */
+  content: string;
+}
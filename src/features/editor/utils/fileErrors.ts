@@ -0,0 +1,18 @@
+import type { WorkspaceError } from "../types/workspace";
+
+/**
+ * Gets a readable message from an error thrown by a file command
+ * @param error - The caught error: a `WorkspaceError`, an `Error` or a string
+ * @returns The error message
+ */
+export function getFileErrorMessage(error: unknown): string {
+  if (error instanceof Error) {
+    return error.message;
+  }
+
+  if (typeof error === "object" && error !== null && "message" in error) {
+    return (error as WorkspaceError).message;
+  }
+
+  return String(error);
+}
@@ -7,6 +7,7 @@ import {
   type ExecutionSettings,
   type ApplicationSettings,
   type LauncherSettings,
+  type NetworkSettings,
 } from "../types/settings";
 import { useLogger } from "@/composables/useLogger";
 import { getScriptsPath } from "@/utils/paths";
@@ -26,6 +27,9 @@ const applicationSettings: Ref<ApplicationSettings> = ref({
 const launcherSettings: Ref<LauncherSettings> = ref({
   ...DEFAULT_SETTINGS.launcher,
 });
+const networkSettings: Ref<NetworkSettings> = ref({
+  ...DEFAULT_SETTINGS.network,
+});
 
 async function initializeStore() {
   if (isInitialized) return;
@@ -59,6 +63,10 @@ async function initializeStore() {
         ...DEFAULT_SETTINGS.launcher,
         ...savedSettings.launcher,
       };
+      networkSettings.value = {
+        ...DEFAULT_SETTINGS.network,
+        ...savedSettings.network,
+      };
     } else {
       // Save defaults if no settings exist
       await saveSettings();
@@ -71,6 +79,7 @@ async function initializeStore() {
         executionSettings,
         applicationSettings,
         launcherSettings,
+        networkSettings,
       ],
       async () => {
         await saveSettings();
@@ -93,6 +102,7 @@ async function saveSettings() {
     execution: executionSettings.value,
     application: applicationSettings.value,
     launcher: launcherSettings.value,
+    network: networkSettings.value,
   };
 
   await store.set("settings", settings);
@@ -103,6 +113,7 @@ async function resetSettings() {
   executionSettings.value = { ...DEFAULT_SETTINGS.execution };
   applicationSettings.value = { ...DEFAULT_SETTINGS.application };
   launcherSettings.value = { ...DEFAULT_SETTINGS.launcher };
+  networkSettings.value = { ...DEFAULT_SETTINGS.network };
 
   await saveSettings();
 }
@@ -126,6 +137,9 @@ export function useSettings() {
     // Launcher settings
     launcherSettings,
 
+    // Network settings
+    networkSettings,
+
     // Methods
     resetSettings,
   };
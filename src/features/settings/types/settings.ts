@@ -1,6 +1,7 @@
 export interface EditorSettings {
   wordWrap: boolean;
   minimap: boolean;
+  formatOnSave: boolean;
   fontSize: number;
   font: string;
   fontLigatures: boolean;
@@ -25,17 +26,24 @@ export interface LauncherSettings {
   multiInstance: boolean;
 }
 
+export interface NetworkSettings {
+  websocketPort: number;
+  httpPort: number;
+}
+
 export interface Settings {
   editor: EditorSettings;
   execution: ExecutionSettings;
   application: ApplicationSettings;
   launcher: LauncherSettings;
+  network: NetworkSettings;
 }
 
 export const DEFAULT_SETTINGS: Settings = {
   editor: {
     wordWrap: false,
     minimap: false,
+    formatOnSave: false,
     fontSize: 14,
     font: "Lilex",
     fontLigatures: false,
@@ -56,4 +64,8 @@ export const DEFAULT_SETTINGS: Settings = {
     cooldown: 60,
     multiInstance: false,
   },
+  network: {
+    websocketPort: 13376,
+    httpPort: 13377,
+  },
 };
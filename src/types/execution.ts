@@ -0,0 +1,29 @@
+import type { ExecutionSource } from "./executor";
+
+/**
+ * A script still running on a client
+ * Running until its chunk, every thread it spawned and every signal connection it made
+ * have ended
+ */
+export interface RunningExecution {
+  id: string;
+  client_id: string;
+  username: string;
+  source: ExecutionSource;
+  /** Milliseconds since the Unix epoch */
+  started_at: number;
+}
+
+/**
+ * A line of redirected output from an execution
+ */
+export interface ExecutionOutputLine {
+  execution_id: string;
+  client_id: string;
+  username: string;
+  /** Log level: 0 info, 1 success, 2 warning, 3 error */
+  level: number;
+  message: string;
+  /** Milliseconds since the Unix epoch */
+  timestamp: number;
+}
@@ -6,4 +6,38 @@ export interface ExecuteRequest {
   client_ids: string[];
   script: string;
   redirect: boolean;
+  source?: ExecutionSource;
+  /** Bundle local `require("./...")` and `--#include` references before sending */
+  bundle?: boolean;
+  /** Path of the script relative to the Proxima folder, used to resolve relative references */
+  path?: string;
+  /** Refuse to send the script if it has syntax errors */
+  check_syntax?: boolean;
+  /** Expand `{{placeholders}}` for each client before sending */
+  template?: TemplateVariables;
+  /** JSON passed to the script as `...`; an array is passed as separate values */
+  args?: unknown;
+  /** Execution ID to use instead of a generated one, so callers can follow the output */
+  id?: string;
 }
+
+/**
+ * User-defined template variables, alongside the built-in `{{username}}`,
+ * `{{client_id}}`, `{{place_id}}`, `{{game_id}}`, `{{index}}` and `{{count}}`
+ */
+export interface TemplateVariables {
+  /** Variables for every client */
+  variables?: Record<string, string>;
+  /** Variables for specific clients, keyed by username or client ID */
+  clients?: Record<string, Record<string, string>>;
+}
+
+/**
+ * Where an execution was started from, recorded in the execution history
+ */
+export type ExecutionSource =
+  | "editor"
+  | "http"
+  | "autoexec"
+  | "schedule"
+  | "history";